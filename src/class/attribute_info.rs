@@ -1,7 +1,12 @@
 use core::fmt;
 use std::rc::Rc;
 
-use crate::{local::Local, opcode::Opcode, value::MistValue};
+use crate::{
+  local::Local,
+  opcode::Opcode,
+  run::{RuntimeContext, RuntimeError},
+  value::MistValue,
+};
 
 // #[derive(Clone, Debug)]
 // pub struct AttributeInfo {
@@ -16,10 +21,47 @@ use crate::{local::Local, opcode::Opcode, value::MistValue};
 pub enum AttributeInfo {
   Code(Code),
   LineNumberTable(LineNumberTable),
-  Bytes(Vec<u8>),
+  SourceFile(String),
+  /// Index of the constant pool entry holding the value of the field.
+  ConstantValue(u16),
+  /// Classes of the checked exceptions a method may throw.
+  Exceptions(Vec<String>),
+  InnerClasses(Vec<InnerClass>),
+  EnclosingMethod(EnclosingMethod),
+  Signature(String),
+  LocalVariableTable(Vec<LocalVariable>),
+  /// Like [AttributeInfo::LocalVariableTable], with generic signatures in
+  /// place of descriptors.
+  LocalVariableTypeTable(Vec<LocalVariable>),
+  StackMapTable(Vec<StackMapFrame>),
+  BootstrapMethods(Vec<BootstrapMethod>),
+  NestHost(String),
+  NestMembers(Vec<String>),
+  Record(Vec<RecordComponent>),
+  PermittedSubclasses(Vec<String>),
+  MethodParameters(Vec<MethodParameter>),
+  RuntimeVisibleAnnotations(Vec<Annotation>),
+  RuntimeInvisibleAnnotations(Vec<Annotation>),
+  RuntimeVisibleParameterAnnotations(Vec<Vec<Annotation>>),
+  RuntimeInvisibleParameterAnnotations(Vec<Vec<Annotation>>),
+  RuntimeVisibleTypeAnnotations(Vec<TypeAnnotation>),
+  RuntimeInvisibleTypeAnnotations(Vec<TypeAnnotation>),
+  AnnotationDefault(ElementValue),
+  Synthetic,
+  Deprecated,
+  /// An attribute this reader does not interpret, kept as it was.
+  Bytes {
+    name: String,
+    info: Vec<u8>,
+  },
 }
 
-pub type NativeCode = Option<Rc<dyn Fn(&Local) -> Option<MistValue>>>;
+/// A method implemented in Rust, called with its arguments.
+pub type NativeCode = Option<Rc<NativeFn>>;
+
+pub type NativeFn = dyn Fn(&mut RuntimeContext<'_>, &Local) -> NativeResult;
+
+pub type NativeResult = Result<Option<MistValue>, RuntimeError>;
 
 #[derive(Clone)]
 pub struct Code {
@@ -37,12 +79,9 @@ impl Code {
     self.native.is_some()
   }
 
-  pub fn native(
-    f: impl Fn(&Local) -> Option<MistValue> + 'static,
-    max_local: u16,
-  ) -> Self {
+  pub fn native(native: Rc<NativeFn>, max_local: u16) -> Self {
     Self {
-      native: Some(Rc::new(f)),
+      native: Some(native),
       max_local,
       max_stack: 0,
       code: Vec::new(),
@@ -50,6 +89,22 @@ impl Code {
       attributes: Vec::new(),
     }
   }
+
+  /// The source line of the instruction at `pc`, if the method has a
+  /// `LineNumberTable` covering it.
+  pub fn line_number(&self, pc: usize) -> Option<u16> {
+    self
+      .attributes
+      .iter()
+      .filter_map(|attribute| match attribute {
+        AttributeInfo::LineNumberTable(table) => Some(table),
+        _ => None,
+      })
+      .flat_map(|table| &table.line_number_table)
+      .filter(|entry| entry.start_pc as usize <= pc)
+      .max_by_key(|entry| entry.start_pc)
+      .map(|entry| entry.line_number)
+  }
 }
 
 impl fmt::Debug for Code {
@@ -85,3 +140,156 @@ pub struct LineNumberTableInfo {
   pub start_pc: u16,
   pub line_number: u16,
 }
+
+#[derive(Clone, Debug)]
+pub struct InnerClass {
+  pub inner_class: String,
+  /// Absent for local and anonymous classes.
+  pub outer_class: Option<String>,
+  /// Absent for anonymous classes.
+  pub inner_name: Option<String>,
+  pub inner_class_access_flags: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct EnclosingMethod {
+  pub class: String,
+  /// Name and descriptor, absent when not enclosed by a method.
+  pub method: Option<(String, String)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LocalVariable {
+  pub start_pc: u16,
+  pub length: u16,
+  pub name: String,
+  pub descriptor: String,
+  pub index: u16,
+}
+
+#[derive(Clone, Debug)]
+pub enum StackMapFrame {
+  Same {
+    offset_delta: u16,
+  },
+  SameLocals1StackItem {
+    offset_delta: u16,
+    stack: VerificationType,
+  },
+  Chop {
+    offset_delta: u16,
+    /// How many locals are absent compared to the previous frame.
+    k: u8,
+  },
+  Append {
+    offset_delta: u16,
+    locals: Vec<VerificationType>,
+  },
+  Full {
+    offset_delta: u16,
+    locals: Vec<VerificationType>,
+    stack: Vec<VerificationType>,
+  },
+}
+
+#[derive(Clone, Debug)]
+pub enum VerificationType {
+  Top,
+  Integer,
+  Float,
+  Double,
+  Long,
+  Null,
+  UninitializedThis,
+  Object(String),
+  /// Offset of the `new` instruction that created the object.
+  Uninitialized(u16),
+}
+
+#[derive(Clone, Debug)]
+pub struct BootstrapMethod {
+  /// Index of a `CONSTANT_MethodHandle`.
+  pub bootstrap_method_ref: u16,
+  /// Indices of loadable constant pool entries.
+  pub bootstrap_arguments: Vec<u16>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RecordComponent {
+  pub name: String,
+  pub descriptor: String,
+  pub attributes: Vec<AttributeInfo>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MethodParameter {
+  /// Absent for formal parameters without a name.
+  pub name: Option<String>,
+  pub access_flags: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct Annotation {
+  /// Field descriptor of the annotation interface.
+  pub type_name: String,
+  pub element_value_pairs: Vec<(String, ElementValue)>,
+}
+
+#[derive(Clone, Debug)]
+pub enum ElementValue {
+  /// A primitive or `String` constant, `tag` tells which one.
+  Const {
+    tag: u8,
+    const_value_index: u16,
+  },
+  Enum {
+    type_name: String,
+    const_name: String,
+  },
+  /// Return descriptor of a class literal.
+  Class(String),
+  Annotation(Annotation),
+  Array(Vec<ElementValue>),
+}
+
+#[derive(Clone, Debug)]
+pub struct TypeAnnotation {
+  pub target_type: u8,
+  pub target_info: TargetInfo,
+  /// Pairs of `type_path_kind` and `type_argument_index`.
+  pub target_path: Vec<(u8, u8)>,
+  pub annotation: Annotation,
+}
+
+#[derive(Clone, Debug)]
+pub enum TargetInfo {
+  TypeParameter {
+    type_parameter_index: u8,
+  },
+  Supertype {
+    supertype_index: u16,
+  },
+  TypeParameterBound {
+    type_parameter_index: u8,
+    bound_index: u8,
+  },
+  Empty,
+  FormalParameter {
+    formal_parameter_index: u8,
+  },
+  Throws {
+    throws_type_index: u16,
+  },
+  /// Triples of `start_pc`, `length` and `index`.
+  LocalVar(Vec<(u16, u16, u16)>),
+  Catch {
+    exception_table_index: u16,
+  },
+  Offset {
+    offset: u16,
+  },
+  TypeArgument {
+    offset: u16,
+    type_argument_index: u8,
+  },
+}
@@ -1,78 +1,863 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, error::Error, fmt, io, rc::Rc};
 
 use super::{
-  attribute_info::{AttributeInfo, Code},
+  descriptor::{BaseType, FieldType},
+  field::FieldInfo,
+  loader::ClassLoader,
   method::MethodInfo,
-  native::*,
-  Class,
+  native::{self, java},
+  registry::Registry,
+  Class, ClassFormatError, Reader, Selected, ACC_ABSTRACT, ACC_FINAL,
+  ACC_INTERFACE, ACC_PRIVATE, ACC_PUBLIC, ACC_STATIC,
 };
 
+/// Why a class could not be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+  /// No loader has the class.
+  NotFound(String),
+  /// A loader failed while looking for the class.
+  Io {
+    class_name: String,
+    error: io::Error,
+  },
+  Format(ClassFormatError),
+  /// The class file found for `class_name` defines `found` instead.
+  WrongName {
+    class_name: String,
+    found: String,
+  },
+  /// The class is its own superclass or superinterface.
+  Circularity(String),
+  /// The class was already defined.
+  Duplicate(String),
+}
+
+impl LoadError {
+  /// The Java error this is reported as.
+  pub fn exception_class(&self) -> &'static str {
+    match self {
+      Self::NotFound(..) | Self::Io { .. } | Self::WrongName { .. } => {
+        "java/lang/NoClassDefFoundError"
+      }
+      Self::Format(..) => "java/lang/ClassFormatError",
+      Self::Circularity(..) => "java/lang/ClassCircularityError",
+      Self::Duplicate(..) => "java/lang/LinkageError",
+    }
+  }
+}
+
+impl fmt::Display for LoadError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::NotFound(class_name) | Self::Circularity(class_name) => {
+        write!(f, "{class_name}")
+      }
+      Self::Io { class_name, error } => write!(f, "{class_name} ({error})"),
+      Self::Format(error) => write!(f, "{error}"),
+      Self::WrongName { class_name, found } => {
+        write!(f, "{class_name} (wrong name: {found})")
+      }
+      Self::Duplicate(class_name) => {
+        write!(f, "duplicate class definition for {class_name}")
+      }
+    }
+  }
+}
+
+impl Error for LoadError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      Self::Io { error, .. } => Some(error),
+      Self::Format(error) => Some(error),
+      _ => None,
+    }
+  }
+}
+
 #[derive(Default)]
 pub struct Context {
-  classes: HashMap<String, Class>,
+  classes: HashMap<String, Rc<Class>>,
+  /// Every class added so far, by [Class::id].
+  by_id: Vec<Rc<Class>>,
+  /// Consulted in order for classes that are not built in.
+  loaders: Vec<Box<dyn ClassLoader>>,
+  /// Classes whose superclass and interfaces are being loaded.
+  linking: Vec<String>,
+  /// Bound to the native methods of each class added.
+  natives: Registry,
 }
 
 impl Context {
   pub fn new() -> Self {
-    let mut this = Self::default();
-
-    // TODO: refactor this
-    let mut java_lang_system = Class::default();
-    java_lang_system.methods.push(MethodInfo {
-      access_flags: 0,
-      name: String::from("currentTimeMillis"),
-      descriptor: String::from("()J"),
-      attributes: vec![AttributeInfo::Code(Code::native(
-        java::lang::system::current_time_millis,
-        0,
-      ))],
-    });
+    let mut this = Self {
+      natives: native::registry(),
+      ..Self::default()
+    };
 
-    let mut java_lang_math = Class::default();
-    java_lang_math.methods.push(MethodInfo {
-      access_flags: 0,
-      name: String::from("sqrt"),
-      descriptor: String::from("(D)D"),
-      attributes: vec![AttributeInfo::Code(Code::native(
-        java::lang::math::sqrt,
-        1,
-      ))],
+    let java_lang_object = builtin_class("java/lang/Object", "");
+
+    let mut java_lang_class =
+      builtin_class("java/lang/Class", "java/lang/Object");
+    java_lang_class.access_flags = ACC_PUBLIC | ACC_FINAL;
+    java_lang_class.interfaces = vec![String::from("java/io/Serializable")];
+    java_lang_class
+      .fields
+      .push(builtin_field("name", "Ljava/lang/String;"));
+
+    let mut java_lang_system =
+      builtin_class("java/lang/System", "java/lang/Object");
+    java_lang_system.fields.extend([
+      static_field("out", "Ljava/io/PrintStream;"),
+      static_field("err", "Ljava/io/PrintStream;"),
+    ]);
+
+    let mut java_io_print_stream =
+      builtin_class("java/io/PrintStream", "java/lang/Object");
+    java_io_print_stream.fields.push(builtin_field("fd", "I"));
+
+    let java_lang_math = builtin_class("java/lang/Math", "java/lang/Object");
+
+    let mut java_lang_number =
+      builtin_class("java/lang/Number", "java/lang/Object");
+    java_lang_number.access_flags = ACC_PUBLIC | ACC_ABSTRACT;
+    java_lang_number.interfaces = vec![String::from("java/io/Serializable")];
+    java_lang_number.methods.extend(
+      [
+        ("intValue", "()I"),
+        ("longValue", "()J"),
+        ("floatValue", "()F"),
+        ("doubleValue", "()D"),
+      ]
+      .map(|(name, descriptor)| abstract_method(name, descriptor)),
+    );
+
+    let boxes = java::lang::boxed::CLASSES.map(|(class_name, base_type)| {
+      let numeric = !matches!(base_type, BaseType::Boolean | BaseType::Char);
+      let mut class = if numeric {
+        builtin_class(class_name, "java/lang/Number")
+      } else {
+        builtin_class(class_name, "java/lang/Object")
+      };
+      class.access_flags = ACC_PUBLIC | ACC_FINAL;
+      if !numeric {
+        class.interfaces = vec![String::from("java/io/Serializable")];
+      }
+      let value = FieldType::Base(base_type).to_string();
+      class.fields.push(builtin_field("value", &value));
+      if base_type == BaseType::Boolean {
+        class.fields.extend([
+          static_field("TRUE", "Ljava/lang/Boolean;"),
+          static_field("FALSE", "Ljava/lang/Boolean;"),
+        ]);
+      }
+      class
     });
 
-    this
-      .classes
-      .insert(String::from("java/lang/System"), java_lang_system);
-    this
-      .classes
-      .insert(String::from("java/lang/Math"), java_lang_math);
+    let mut java_lang_string =
+      builtin_class("java/lang/String", "java/lang/Object");
+    java_lang_string.access_flags = ACC_PUBLIC | ACC_FINAL;
+    java_lang_string.interfaces = vec![
+      String::from("java/io/Serializable"),
+      String::from("java/lang/Comparable"),
+    ];
+    java_lang_string
+      .fields
+      .extend([builtin_field("value", "[B"), builtin_field("coder", "B")]);
+
+    let mut java_lang_string_builder =
+      builtin_class("java/lang/StringBuilder", "java/lang/Object");
+    java_lang_string_builder.access_flags = ACC_PUBLIC | ACC_FINAL;
+    java_lang_string_builder.interfaces =
+      vec![String::from("java/io/Serializable")];
+    java_lang_string_builder
+      .fields
+      .extend([builtin_field("value", "[C"), builtin_field("count", "I")]);
+
+    let mut java_lang_throwable =
+      builtin_class("java/lang/Throwable", "java/lang/Object");
+    java_lang_throwable.fields.extend([
+      builtin_field("detailMessage", "Ljava/lang/String;"),
+      builtin_field("cause", "Ljava/lang/Throwable;"),
+      builtin_field("stackTrace", "[Ljava/lang/StackTraceElement;"),
+    ]);
+
+    let mut java_lang_stack_trace_element =
+      builtin_class("java/lang/StackTraceElement", "java/lang/Object");
+    java_lang_stack_trace_element.access_flags = ACC_PUBLIC | ACC_FINAL;
+    java_lang_stack_trace_element.fields.extend([
+      builtin_field("declaringClass", "Ljava/lang/String;"),
+      builtin_field("methodName", "Ljava/lang/String;"),
+      builtin_field("fileName", "Ljava/lang/String;"),
+      builtin_field("lineNumber", "I"),
+    ]);
+
+    // Superclasses come first.
+    let exceptions = [
+      ("java/lang/Exception", "java/lang/Throwable"),
+      ("java/lang/Error", "java/lang/Throwable"),
+      ("java/lang/RuntimeException", "java/lang/Exception"),
+      (
+        "java/lang/ArithmeticException",
+        "java/lang/RuntimeException",
+      ),
+      (
+        "java/lang/ArrayStoreException",
+        "java/lang/RuntimeException",
+      ),
+      ("java/lang/ClassCastException", "java/lang/RuntimeException"),
+      (
+        "java/lang/IllegalArgumentException",
+        "java/lang/RuntimeException",
+      ),
+      (
+        "java/lang/IllegalStateException",
+        "java/lang/RuntimeException",
+      ),
+      (
+        "java/lang/IndexOutOfBoundsException",
+        "java/lang/RuntimeException",
+      ),
+      (
+        "java/lang/ArrayIndexOutOfBoundsException",
+        "java/lang/IndexOutOfBoundsException",
+      ),
+      (
+        "java/lang/StringIndexOutOfBoundsException",
+        "java/lang/IndexOutOfBoundsException",
+      ),
+      (
+        "java/lang/NegativeArraySizeException",
+        "java/lang/RuntimeException",
+      ),
+      (
+        "java/lang/NullPointerException",
+        "java/lang/RuntimeException",
+      ),
+      (
+        "java/lang/NumberFormatException",
+        "java/lang/IllegalArgumentException",
+      ),
+      (
+        "java/lang/UnsupportedOperationException",
+        "java/lang/RuntimeException",
+      ),
+      (
+        "java/util/IllegalFormatException",
+        "java/lang/IllegalArgumentException",
+      ),
+      (
+        "java/util/IllegalFormatArgumentIndexException",
+        "java/util/IllegalFormatException",
+      ),
+      (
+        "java/util/IllegalFormatConversionException",
+        "java/util/IllegalFormatException",
+      ),
+      (
+        "java/util/MissingFormatArgumentException",
+        "java/util/IllegalFormatException",
+      ),
+      (
+        "java/util/UnknownFormatConversionException",
+        "java/util/IllegalFormatException",
+      ),
+      ("java/lang/LinkageError", "java/lang/Error"),
+      ("java/lang/BootstrapMethodError", "java/lang/LinkageError"),
+      ("java/lang/ClassCircularityError", "java/lang/LinkageError"),
+      ("java/lang/ClassFormatError", "java/lang/LinkageError"),
+      (
+        "java/lang/ExceptionInInitializerError",
+        "java/lang/LinkageError",
+      ),
+      (
+        "java/lang/IncompatibleClassChangeError",
+        "java/lang/LinkageError",
+      ),
+      (
+        "java/lang/AbstractMethodError",
+        "java/lang/IncompatibleClassChangeError",
+      ),
+      (
+        "java/lang/InstantiationError",
+        "java/lang/IncompatibleClassChangeError",
+      ),
+      (
+        "java/lang/NoSuchFieldError",
+        "java/lang/IncompatibleClassChangeError",
+      ),
+      (
+        "java/lang/NoSuchMethodError",
+        "java/lang/IncompatibleClassChangeError",
+      ),
+      ("java/lang/NoClassDefFoundError", "java/lang/LinkageError"),
+      ("java/lang/UnsatisfiedLinkError", "java/lang/LinkageError"),
+      ("java/lang/VerifyError", "java/lang/LinkageError"),
+      ("java/lang/VirtualMachineError", "java/lang/Error"),
+      ("java/lang/InternalError", "java/lang/VirtualMachineError"),
+      (
+        "java/lang/OutOfMemoryError",
+        "java/lang/VirtualMachineError",
+      ),
+      (
+        "java/lang/StackOverflowError",
+        "java/lang/VirtualMachineError",
+      ),
+    ]
+    .map(|(this_class, super_class)| builtin_class(this_class, super_class));
+
+    let interfaces = [
+      builtin_interface("java/io/Serializable", &[]),
+      builtin_interface("java/lang/AutoCloseable", &[("close", "()V")]),
+      builtin_interface("java/lang/Cloneable", &[]),
+      builtin_interface(
+        "java/lang/Comparable",
+        &[("compareTo", "(Ljava/lang/Object;)I")],
+      ),
+      builtin_interface(
+        "java/lang/Iterable",
+        &[("iterator", "()Ljava/util/Iterator;")],
+      ),
+      builtin_interface("java/lang/Runnable", &[("run", "()V")]),
+      builtin_interface(
+        "java/util/Comparator",
+        &[("compare", "(Ljava/lang/Object;Ljava/lang/Object;)I")],
+      ),
+      builtin_interface(
+        "java/util/Iterator",
+        &[("hasNext", "()Z"), ("next", "()Ljava/lang/Object;")],
+      ),
+    ];
+
+    let array = Class {
+      interfaces: vec![
+        String::from("java/lang/Cloneable"),
+        String::from("java/io/Serializable"),
+      ],
+      ..builtin_class(native::array::CLASS, "java/lang/Object")
+    };
+
+    // Interfaces come before the classes that implement them.
+    for mut class in std::iter::once(java_lang_object)
+      .chain(interfaces)
+      .chain([
+        array,
+        java_lang_class,
+        java_lang_system,
+        java_io_print_stream,
+        java_lang_math,
+        java_lang_number,
+      ])
+      .chain(boxes)
+      .chain([
+        java_lang_string,
+        java_lang_string_builder,
+        java_lang_throwable,
+        java_lang_stack_trace_element,
+      ])
+      .chain(exceptions)
+    {
+      this.natives.declare(&mut class);
+      this
+        .add_class(class)
+        .expect("Built-in classes are distinct.");
+    }
     this
   }
 }
 
+fn builtin_class(this_class: &str, super_class: &str) -> Class {
+  Class {
+    this_class: String::from(this_class),
+    super_class: String::from(super_class),
+    ..Class::default()
+  }
+}
+
+fn builtin_field(name: &str, descriptor: &str) -> FieldInfo {
+  FieldInfo {
+    access_flags: ACC_PRIVATE,
+    name: String::from(name),
+    descriptor: String::from(descriptor),
+    attributes: Vec::new(),
+  }
+}
+
+/// A public abstract method.
+fn abstract_method(name: &str, descriptor: &str) -> MethodInfo {
+  MethodInfo::new(
+    ACC_PUBLIC | ACC_ABSTRACT,
+    String::from(name),
+    String::from(descriptor),
+    Vec::new(),
+  )
+  .expect("Built-in methods have valid descriptors.")
+}
+
+/// A public static final field.
+fn static_field(name: &str, descriptor: &str) -> FieldInfo {
+  FieldInfo {
+    access_flags: ACC_PUBLIC | ACC_STATIC | ACC_FINAL,
+    ..builtin_field(name, descriptor)
+  }
+}
+
+fn builtin_interface(this_class: &str, methods: &[(&str, &str)]) -> Class {
+  let methods = methods
+    .iter()
+    .map(|&(name, descriptor)| abstract_method(name, descriptor))
+    .collect();
+
+  Class {
+    access_flags: ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT,
+    methods,
+    ..builtin_class(this_class, "java/lang/Object")
+  }
+}
+
 impl Context {
-  pub fn lookup_class(&self, class_name: &str) -> Option<&Class> {
-    self.classes.get(class_name)
+  /// Adds a loader for classes that are not built in.
+  pub fn add_loader(&mut self, loader: impl ClassLoader + 'static) {
+    self.loaders.push(Box::new(loader));
+  }
+
+  /// The natives bound to the methods of each class added from now on,
+  /// where more can be registered for the classes loaders supply.
+  pub fn natives_mut(&mut self) -> &mut Registry {
+    &mut self.natives
+  }
+
+  /// Finds `class_name`, loading and linking it on first reference.
+  pub fn lookup_class(
+    &mut self,
+    class_name: &str,
+  ) -> Result<Rc<Class>, LoadError> {
+    if let Some(class) = self.classes.get(class_name) {
+      return Ok(Rc::clone(class));
+    }
+    if class_name.starts_with('[') {
+      return Ok(Rc::clone(&self.classes[native::array::CLASS]));
+    }
+
+    // The built-in classes come first, so no loader gets to define anything
+    // in their packages.
+    if class_name.starts_with("java/") {
+      return Err(LoadError::NotFound(class_name.to_string()));
+    }
+
+    let class = self.load_class(class_name)?;
+    self.link_class(class)
+  }
+
+  /// Reads `class_name` from the first loader that has it.
+  fn load_class(&self, class_name: &str) -> Result<Class, LoadError> {
+    for loader in &self.loaders {
+      let bytes =
+        loader
+          .find_class(class_name)
+          .map_err(|error| LoadError::Io {
+            class_name: class_name.to_string(),
+            error,
+          })?;
+      let Some(bytes) = bytes else {
+        continue;
+      };
+
+      let class = Reader::new(&bytes[..])
+        .read_class()
+        .map_err(|error| LoadError::Format(error.in_class(class_name)))?;
+
+      if class.this_class != class_name {
+        return Err(LoadError::WrongName {
+          class_name: class_name.to_string(),
+          found: class.this_class,
+        });
+      }
+
+      return Ok(class);
+    }
+
+    Err(LoadError::NotFound(class_name.to_string()))
+  }
+
+  /// Loads the superclass and interfaces of `class` before adding it.
+  fn link_class(&mut self, class: Class) -> Result<Rc<Class>, LoadError> {
+    if self.linking.contains(&class.this_class) {
+      return Err(LoadError::Circularity(class.this_class));
+    }
+
+    let mut supertypes = class.interfaces.clone();
+    if !class.super_class.is_empty() {
+      supertypes.insert(0, class.super_class.clone());
+    }
+
+    self.linking.push(class.this_class.clone());
+    let linked = supertypes
+      .iter()
+      .try_for_each(|supertype| self.lookup_class(supertype).map(drop));
+    self.linking.pop();
+    linked?;
+
+    self.add_class(class)
   }
 
-  pub fn add_class(&mut self, class: Class) {
+  /// Prepares `class`, whose supertypes must already be present, and adds it.
+  pub fn add_class(
+    &mut self,
+    mut class: Class,
+  ) -> Result<Rc<Class>, LoadError> {
     let class_name = class.this_class.clone();
+    let superclass = self.classes.get(&class.super_class).cloned();
+    let superinterfaces = class
+      .interfaces
+      .iter()
+      .filter_map(|interface| self.classes.get(interface).cloned())
+      .collect();
 
     match self.classes.entry(class_name) {
-      std::collections::hash_map::Entry::Occupied(_) => todo!(),
+      std::collections::hash_map::Entry::Occupied(e) => {
+        Err(LoadError::Duplicate(e.key().clone()))
+      }
       std::collections::hash_map::Entry::Vacant(e) => {
-        e.insert(class);
+        class.id = self.by_id.len();
+        self.natives.bind(&mut class);
+        class.prepare(superclass.as_deref());
+        class.superclass = superclass;
+        class.superinterfaces = superinterfaces;
+        class.vtable = vtable(&self.by_id, &class);
+        class.itable = itable(&class);
+
+        let class = Rc::new(class);
+        self.by_id.push(Rc::clone(&class));
+        Ok(Rc::clone(e.insert(class)))
       }
     }
   }
 
+  /// The class numbered `id`.
+  pub fn class_by_id(&self, id: usize) -> Rc<Class> {
+    Rc::clone(&self.by_id[id])
+  }
+
+  /// Finds a method in `class_name` (JVMS §5.4.3.3), or in the interface
+  /// `class_name` (JVMS §5.4.3.4).
   pub fn lookup_method(
-    &self,
+    &mut self,
     class_name: &str,
     method_name: &str,
     descriptor: &str,
-  ) -> Option<&MethodInfo> {
+  ) -> Result<Option<(Rc<Class>, usize)>, LoadError> {
     let class = self.lookup_class(class_name)?;
 
-    class.lookup_method_with_descriptor(method_name, descriptor)
+    if class.is_interface() {
+      if let Some(method) = class.method_index(method_name, descriptor) {
+        return Ok(Some((class, method)));
+      }
+
+      let object = self.lookup_class("java/lang/Object")?;
+      if let Some(method) = object.method_index(method_name, descriptor) {
+        let method_info = &object.methods[method];
+        if method_info.is_public() && !method_info.is_static() {
+          return Ok(Some((object, method)));
+        }
+      }
+    } else {
+      let mut superclass = Some(Rc::clone(&class));
+      while let Some(current) = superclass {
+        if let Some(method) = current.method_index(method_name, descriptor) {
+          return Ok(Some((current, method)));
+        }
+        superclass = current.superclass.clone();
+      }
+    }
+
+    let candidates = maximally_specific(&class, method_name, descriptor);
+    let mut defaults = candidates
+      .iter()
+      .filter(|(interface, method)| !interface.methods[*method].is_abstract());
+    match (defaults.next(), defaults.next()) {
+      (Some(default), None) => Ok(Some(default.clone())),
+      _ => Ok(candidates.into_iter().next()),
+    }
+  }
+
+  /// Finds a field in `class_name`, its superinterfaces or its superclasses,
+  /// in that order (JVMS §5.4.3.2).
+  pub fn lookup_field(
+    &mut self,
+    class_name: &str,
+    field_name: &str,
+    descriptor: &str,
+  ) -> Result<Option<(Rc<Class>, usize)>, LoadError> {
+    let class = self.lookup_class(class_name)?;
+    Ok(find_field(&class, field_name, descriptor))
+  }
+}
+
+fn find_field(
+  class: &Rc<Class>,
+  field_name: &str,
+  descriptor: &str,
+) -> Option<(Rc<Class>, usize)> {
+  if let Some(field) = class.field_index(field_name, descriptor) {
+    return Some((Rc::clone(class), field));
+  }
+
+  class
+    .superinterfaces
+    .iter()
+    .chain(&class.superclass)
+    .find_map(|supertype| find_field(supertype, field_name, descriptor))
+}
+
+/// The maximally-specific superinterface methods of `class`: those no
+/// other superinterface overrides (JVMS §5.4.3.3).
+fn maximally_specific(
+  class: &Class,
+  method_name: &str,
+  descriptor: &str,
+) -> Vec<(Rc<Class>, usize)> {
+  let candidates = class
+    .all_interfaces()
+    .into_iter()
+    .filter_map(|interface| {
+      let method = interface.method_index(method_name, descriptor)?;
+      let method_info = &interface.methods[method];
+      let inherited = !method_info.is_static() && !method_info.is_private();
+      inherited.then_some((interface, method))
+    })
+    .collect::<Vec<_>>();
+
+  candidates
+    .iter()
+    .filter(|(interface, _)| {
+      !candidates
+        .iter()
+        .any(|(other, _)| other.is_subinterface_of(interface))
+    })
+    .cloned()
+    .collect()
+}
+
+/// The itable of `class`, selecting a method for each interface method it
+/// has to implement.
+fn itable(class: &Class) -> HashMap<usize, Vec<Selected>> {
+  if class.is_interface() {
+    return HashMap::new();
+  }
+
+  class
+    .all_interfaces()
+    .iter()
+    .map(|interface| {
+      let selected = interface
+        .methods
+        .iter()
+        .map(|method| select(class, method))
+        .collect();
+      (interface.id, selected)
+    })
+    .collect()
+}
+
+/// The method that runs when `method` of an interface is invoked on an
+/// instance of `class` (JVMS §6.5.invokeinterface).
+fn select(class: &Class, method: &MethodInfo) -> Selected {
+  if method.is_static() || method.is_private() {
+    return Selected::Abstract;
+  }
+
+  let overrides = |class: &Class| {
+    class.methods.iter().position(|own| {
+      own.name == method.name
+        && own.descriptor == method.descriptor
+        && !own.is_static()
+        && !own.is_private()
+    })
+  };
+
+  if let Some(index) = overrides(class) {
+    return Selected::Method(class.id, index);
+  }
+  let mut superclass = class.superclass.as_deref();
+  while let Some(current) = superclass {
+    if let Some(index) = overrides(current) {
+      return Selected::Method(current.id, index);
+    }
+    superclass = current.superclass.as_deref();
+  }
+
+  let candidates = maximally_specific(class, &method.name, &method.descriptor);
+  let defaults = candidates
+    .iter()
+    .filter(|(interface, method)| !interface.methods[*method].is_abstract())
+    .collect::<Vec<_>>();
+  match defaults[..] {
+    [(interface, method)] => Selected::Method(interface.id, *method),
+    [] => Selected::Abstract,
+    _ => Selected::Conflict,
+  }
+}
+
+/// The vtable of `class`: that of its superclass with the slots it overrides
+/// replaced, then a new slot for each other instance method (JVMS §5.4.5).
+fn vtable(by_id: &[Rc<Class>], class: &Class) -> Vec<(usize, usize)> {
+  let inherited = class
+    .superclass
+    .as_ref()
+    .map_or(&[][..], |superclass| &superclass.vtable[..]);
+  let mut vtable = inherited.to_vec();
+
+  for (index, method) in class.methods.iter().enumerate() {
+    if method.is_static() || method.is_private() || method.name == "<init>" {
+      continue;
+    }
+
+    let mut overrides = false;
+    for (slot, &(owner, inherited)) in inherited.iter().enumerate() {
+      let owner = &by_id[owner];
+      let inherited = &owner.methods[inherited];
+      if inherited.name == method.name
+        && inherited.descriptor == method.descriptor
+        && (inherited.is_public()
+          || inherited.is_protected()
+          || owner.package() == class.package())
+      {
+        vtable[slot] = (class.id, index);
+        overrides = true;
+      }
+    }
+    if !overrides {
+      vtable.push((class.id, index));
+    }
+  }
+
+  vtable
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::RefCell, io, rc::Rc};
+
+  use super::{Context, LoadError};
+  use crate::class::{
+    loader::{ClassLoader, MemoryLoader},
+    Class,
+  };
+
+  /// The compiled `Dispatch` test program, where `Puppy` extends `Dog`,
+  /// which extends `Animal` and implements `Named`.
+  const CLASSES: [(&str, &[u8]); 5] = [
+    (
+      "Dispatch$Puppy",
+      include_bytes!("../../tests/conformance/Dispatch$Puppy.class"),
+    ),
+    (
+      "Dispatch$Dog",
+      include_bytes!("../../tests/conformance/Dispatch$Dog.class"),
+    ),
+    (
+      "Dispatch$Animal",
+      include_bytes!("../../tests/conformance/Dispatch$Animal.class"),
+    ),
+    (
+      "Dispatch$Named",
+      include_bytes!("../../tests/conformance/Dispatch$Named.class"),
+    ),
+    (
+      "Dispatch$Square",
+      include_bytes!("../../tests/conformance/Dispatch$Square.class"),
+    ),
+  ];
+
+  /// A [MemoryLoader] that records the classes it is asked for.
+  struct Recording {
+    loader: MemoryLoader,
+    requests: Rc<RefCell<Vec<String>>>,
+  }
+
+  impl ClassLoader for Recording {
+    fn find_class(&self, class_name: &str) -> io::Result<Option<Vec<u8>>> {
+      self.requests.borrow_mut().push(class_name.to_string());
+      self.loader.find_class(class_name)
+    }
+  }
+
+  /// A context loading [CLASSES], with the requests made of its loader.
+  fn context() -> (Context, Rc<RefCell<Vec<String>>>) {
+    let mut loader = MemoryLoader::default();
+    for (class_name, bytes) in CLASSES {
+      loader.insert(class_name, bytes.to_vec());
+    }
+    loader.insert("java/lang/Evil", CLASSES[0].1.to_vec());
+    loader.insert("java/lang/String", CLASSES[0].1.to_vec());
+
+    let requests = Rc::default();
+    let mut context = Context::new();
+    context.add_loader(Recording {
+      loader,
+      requests: Rc::clone(&requests),
+    });
+    (context, requests)
+  }
+
+  #[test]
+  fn loads_supertypes_first_and_nothing_else() {
+    let (mut context, requests) = context();
+    let puppy = context.lookup_class("Dispatch$Puppy").unwrap();
+    assert_eq!(
+      *requests.borrow(),
+      [
+        "Dispatch$Puppy",
+        "Dispatch$Dog",
+        "Dispatch$Animal",
+        "Dispatch$Named"
+      ]
+    );
+
+    let dog = puppy.superclass.as_ref().unwrap();
+    assert_eq!(dog.this_class, "Dispatch$Dog");
+    assert_eq!(dog.superinterfaces[0].this_class, "Dispatch$Named");
+    assert!(dog.id < puppy.id);
+    assert!(!context.classes.contains_key("Dispatch$Square"));
+  }
+
+  #[test]
+  fn caches_loaded_classes() {
+    let (mut context, requests) = context();
+    let first = context.lookup_class("Dispatch$Dog").unwrap();
+    let asked = requests.borrow().len();
+
+    let second = context.lookup_class("Dispatch$Dog").unwrap();
+    assert!(Rc::ptr_eq(&first, &second));
+    assert!(Rc::ptr_eq(&first, &context.class_by_id(first.id)));
+    assert_eq!(requests.borrow().len(), asked);
+  }
+
+  #[test]
+  fn duplicate_class() {
+    let (mut context, _) = context();
+    context.lookup_class("Dispatch$Named").unwrap();
+    let duplicate = Class {
+      this_class: String::from("Dispatch$Named"),
+      super_class: String::from("java/lang/Object"),
+      ..Class::default()
+    };
+    assert!(matches!(
+      context.add_class(duplicate),
+      Err(LoadError::Duplicate(class_name)) if class_name == "Dispatch$Named"
+    ));
+  }
+
+  #[test]
+  fn cannot_shadow_builtins() {
+    let (mut context, requests) = context();
+    let string = context.lookup_class("java/lang/String").unwrap();
+    assert_eq!(string.fields[0].descriptor, "[B");
+    assert!(matches!(
+      context.lookup_class("java/lang/Evil"),
+      Err(LoadError::NotFound(class_name)) if class_name == "java/lang/Evil"
+    ));
+    assert!(requests.borrow().is_empty());
   }
 }
@@ -0,0 +1,292 @@
+//! Field and method descriptors (JVMS §4.3).
+
+use std::{error::Error, fmt};
+
+use crate::value::MistValue;
+
+/// A primitive type, named by one character in a descriptor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BaseType {
+  Byte,
+  Char,
+  Double,
+  Float,
+  Int,
+  Long,
+  Short,
+  Boolean,
+}
+
+/// The type of a field, parameter or local variable.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FieldType {
+  Base(BaseType),
+  /// Binary name of a class or interface, such as `java/lang/String`.
+  Object(String),
+  /// Array of the component type.
+  Array(Box<FieldType>),
+}
+
+/// The parameter and return types of a method.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MethodDescriptor {
+  pub params: Vec<FieldType>,
+  /// None for `void`.
+  pub ret: Option<FieldType>,
+}
+
+/// A descriptor that does not follow the grammar.
+#[derive(Debug)]
+pub struct DescriptorError {
+  pub descriptor: String,
+}
+
+impl fmt::Display for DescriptorError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Invalid descriptor '{}'", self.descriptor)
+  }
+}
+
+impl Error for DescriptorError {}
+
+impl BaseType {
+  fn from_char(chr: u8) -> Option<Self> {
+    let base_type = match chr {
+      b'B' => Self::Byte,
+      b'C' => Self::Char,
+      b'D' => Self::Double,
+      b'F' => Self::Float,
+      b'I' => Self::Int,
+      b'J' => Self::Long,
+      b'S' => Self::Short,
+      b'Z' => Self::Boolean,
+      _ => return None,
+    };
+    Some(base_type)
+  }
+
+  fn as_char(self) -> char {
+    match self {
+      Self::Byte => 'B',
+      Self::Char => 'C',
+      Self::Double => 'D',
+      Self::Float => 'F',
+      Self::Int => 'I',
+      Self::Long => 'J',
+      Self::Short => 'S',
+      Self::Boolean => 'Z',
+    }
+  }
+
+  /// The keyword Java source uses for the type.
+  pub fn java_name(self) -> &'static str {
+    match self {
+      Self::Byte => "byte",
+      Self::Char => "char",
+      Self::Double => "double",
+      Self::Float => "float",
+      Self::Int => "int",
+      Self::Long => "long",
+      Self::Short => "short",
+      Self::Boolean => "boolean",
+    }
+  }
+}
+
+impl FieldType {
+  pub fn parse(descriptor: &str) -> Result<Self, DescriptorError> {
+    let mut rest = descriptor.as_bytes();
+    match Self::read(&mut rest) {
+      Some(field_type) if rest.is_empty() => Ok(field_type),
+      _ => Err(DescriptorError {
+        descriptor: descriptor.to_string(),
+      }),
+    }
+  }
+
+  /// Reads one field type off the front of `rest`.
+  fn read(rest: &mut &[u8]) -> Option<Self> {
+    let (&first, tail) = rest.split_first()?;
+    *rest = tail;
+
+    let field_type = match first {
+      b'L' => {
+        let end = rest.iter().position(|&chr| chr == b';')?;
+        let class_name = std::str::from_utf8(&rest[..end]).ok()?;
+        if class_name.is_empty() {
+          return None;
+        }
+        *rest = &rest[end + 1..];
+        Self::Object(class_name.to_string())
+      }
+      b'[' => Self::Array(Box::new(Self::read(rest)?)),
+      chr => Self::Base(BaseType::from_char(chr)?),
+    };
+    Some(field_type)
+  }
+
+  /// Number of local variables a value of this type takes up.
+  pub fn slots(&self) -> usize {
+    match self {
+      Self::Base(BaseType::Long | BaseType::Double) => 2,
+      _ => 1,
+    }
+  }
+
+  /// The initial value of a field of this type, in the form it takes on the
+  /// operand stack.
+  pub fn default_value(&self) -> MistValue {
+    match self {
+      Self::Base(BaseType::Long) => MistValue::Long(0),
+      Self::Base(BaseType::Float) => MistValue::Float(0.0),
+      Self::Base(BaseType::Double) => MistValue::Double(0.0),
+      Self::Base(_) => MistValue::Integer(0),
+      Self::Object(_) | Self::Array(_) => MistValue::ObjectReference(0),
+    }
+  }
+
+  /// The name `Class.getName` gives the type, such as `java.lang.String`
+  /// or `[Ljava.lang.String;`.
+  pub fn class_name(&self) -> String {
+    match self {
+      Self::Base(base_type) => base_type.java_name().to_string(),
+      Self::Object(class_name) => class_name.replace('/', "."),
+      Self::Array(_) => self.to_string().replace('/', "."),
+    }
+  }
+
+  /// The type as Java source spells it, such as `java.lang.String[]`.
+  pub fn java_name(&self) -> String {
+    match self {
+      Self::Base(base_type) => base_type.java_name().to_string(),
+      Self::Object(class_name) => class_name.replace('/', "."),
+      Self::Array(component) => format!("{}[]", component.java_name()),
+    }
+  }
+}
+
+impl fmt::Display for FieldType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Base(base_type) => write!(f, "{}", base_type.as_char()),
+      Self::Object(class_name) => write!(f, "L{class_name};"),
+      Self::Array(component) => write!(f, "[{component}"),
+    }
+  }
+}
+
+impl MethodDescriptor {
+  pub fn parse(descriptor: &str) -> Result<Self, DescriptorError> {
+    let error = || DescriptorError {
+      descriptor: descriptor.to_string(),
+    };
+
+    let mut rest =
+      descriptor.as_bytes().strip_prefix(b"(").ok_or_else(error)?;
+    let mut params = Vec::new();
+    while rest.first() != Some(&b')') {
+      params.push(FieldType::read(&mut rest).ok_or_else(error)?);
+    }
+    rest = &rest[1..];
+
+    let ret = match rest {
+      b"V" => None,
+      _ => match FieldType::read(&mut rest) {
+        Some(ret) if rest.is_empty() => Some(ret),
+        _ => return Err(error()),
+      },
+    };
+    Ok(Self { params, ret })
+  }
+
+  /// Number of local variables the parameters take up, not counting `this`.
+  pub fn argument_slots(&self) -> usize {
+    self.params.iter().map(FieldType::slots).sum()
+  }
+}
+
+impl fmt::Display for MethodDescriptor {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "(")?;
+    for param in &self.params {
+      write!(f, "{param}")?;
+    }
+    match &self.ret {
+      Some(ret) => write!(f, "){ret}"),
+      None => write!(f, ")V"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{BaseType, FieldType, MethodDescriptor};
+
+  fn object(class_name: &str) -> FieldType {
+    FieldType::Object(class_name.to_string())
+  }
+
+  fn array(component: FieldType) -> FieldType {
+    FieldType::Array(Box::new(component))
+  }
+
+  #[test]
+  fn nested_arrays() {
+    assert_eq!(
+      FieldType::parse("[[I").unwrap(),
+      array(array(FieldType::Base(BaseType::Int)))
+    );
+    let strings = FieldType::parse("[[[Ljava/lang/String;").unwrap();
+    assert_eq!(strings, array(array(array(object("java/lang/String")))));
+    assert_eq!(strings.class_name(), "[[[Ljava.lang.String;");
+    assert_eq!(strings.java_name(), "java.lang.String[][][]");
+  }
+
+  #[test]
+  fn slots() {
+    let method = MethodDescriptor::parse("(JIDLjava/lang/Object;[J)V").unwrap();
+    let slots = method
+      .params
+      .iter()
+      .map(FieldType::slots)
+      .collect::<Vec<_>>();
+    assert_eq!(slots, [2, 1, 2, 1, 1]);
+    assert_eq!(method.argument_slots(), 7);
+    assert_eq!(method.ret, None);
+  }
+
+  #[test]
+  fn malformed_field_types() {
+    for descriptor in
+      ["", "[", "L;", "Ljava/lang/String", "V", "Q", "II", "[I;"]
+    {
+      assert!(FieldType::parse(descriptor).is_err(), "{descriptor}");
+    }
+  }
+
+  #[test]
+  fn malformed_method_descriptors() {
+    for descriptor in [
+      "", "I", "(I", "(L;)V", "([)V", "(V)V", "()", "()VV", "()II", "(I)V;",
+    ] {
+      let error = MethodDescriptor::parse(descriptor)
+        .expect_err("the descriptor is malformed");
+      assert_eq!(
+        error.to_string(),
+        format!("Invalid descriptor '{descriptor}'")
+      );
+    }
+  }
+
+  #[test]
+  fn display_round_trips() {
+    for descriptor in ["Z", "[B", "Ljava/lang/String;", "[[Ljava/util/Map;"] {
+      let field_type = FieldType::parse(descriptor).unwrap();
+      assert_eq!(field_type.to_string(), descriptor);
+    }
+    for descriptor in ["()V", "(IJ)D", "([Ljava/lang/String;)V", "(CSF)[[Z"] {
+      let method = MethodDescriptor::parse(descriptor).unwrap();
+      assert_eq!(method.to_string(), descriptor);
+    }
+  }
+}
@@ -0,0 +1,91 @@
+use std::{error::Error, fmt, io};
+
+/// Why a `.class` file could not be read.
+///
+/// Offsets are the position in the file, in bytes, at which the problem was
+/// detected.
+#[derive(Debug)]
+pub enum ClassFormatError {
+  /// The underlying reader failed, usually because the file is truncated.
+  Io { offset: usize, error: io::Error },
+  /// The file does not start with `0xCAFEBABE`.
+  BadMagic { magic: u32 },
+  /// A constant pool entry has an unknown tag.
+  UnknownTag { offset: usize, index: u16, tag: u8 },
+  /// A `CONSTANT_Utf8` entry does not hold a valid string.
+  InvalidUtf8 { offset: usize, index: u16 },
+  /// A constant pool index is out of range or points to the wrong entry.
+  BadPoolIndex {
+    offset: usize,
+    index: u16,
+    expected: &'static str,
+  },
+  /// A field or method descriptor does not follow the grammar.
+  BadDescriptor { offset: usize, descriptor: String },
+  /// The contents of an attribute do not match its name or length.
+  BadAttribute { offset: usize, name: String },
+  /// One of the errors above, found while reading `class_name`.
+  InClass {
+    class_name: String,
+    error: Box<ClassFormatError>,
+  },
+}
+
+impl ClassFormatError {
+  /// Attaches the name of the offending class, unless it is already known.
+  pub fn in_class(self, class_name: &str) -> Self {
+    match self {
+      Self::InClass { .. } => self,
+      error => Self::InClass {
+        class_name: class_name.to_string(),
+        error: Box::new(error),
+      },
+    }
+  }
+}
+
+impl fmt::Display for ClassFormatError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Io { offset, error } => {
+        write!(f, "Could not read byte {offset}: {error}")
+      }
+      Self::BadMagic { magic } => {
+        write!(f, "Bad magic number {magic:#010x}, not a .class file")
+      }
+      Self::UnknownTag { offset, index, tag } => write!(
+        f,
+        "Unknown tag {tag} for constant pool entry #{index} at byte {offset}"
+      ),
+      Self::InvalidUtf8 { offset, index } => write!(
+        f,
+        "Constant pool entry #{index} at byte {offset} is not valid UTF-8"
+      ),
+      Self::BadPoolIndex {
+        offset,
+        index,
+        expected,
+      } => write!(
+        f,
+        "Constant pool index #{index} used at byte {offset} is not a {expected}"
+      ),
+      Self::BadDescriptor { offset, descriptor } => {
+        write!(f, "Malformed descriptor '{descriptor}' at byte {offset}")
+      }
+      Self::BadAttribute { offset, name } => {
+        write!(f, "Malformed attribute '{name}' at byte {offset}")
+      }
+      Self::InClass { class_name, error } => write!(f, "{class_name}: {error}"),
+    }
+  }
+}
+
+impl Error for ClassFormatError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      Self::Io { error, .. } => Some(error),
+      Self::InClass { error, .. } => Some(error),
+      _ => None,
+    }
+  }
+}
@@ -1,4 +1,4 @@
-use super::attribute_info::AttributeInfo;
+use super::{attribute_info::AttributeInfo, descriptor::FieldType, ACC_STATIC};
 
 #[derive(Clone, Debug)]
 pub struct FieldInfo {
@@ -7,3 +7,26 @@ pub struct FieldInfo {
   pub descriptor: String,
   pub attributes: Vec<AttributeInfo>,
 }
+
+impl FieldInfo {
+  pub fn is_static(&self) -> bool {
+    self.access_flags & ACC_STATIC != 0
+  }
+
+  /// The parsed descriptor.
+  pub fn field_type(&self) -> FieldType {
+    FieldType::parse(&self.descriptor)
+      .expect("Descriptors are checked when the class is read.")
+  }
+
+  /// Pool index of the initial value of a constant field.
+  pub fn constant_value(&self) -> Option<u16> {
+    self
+      .attributes
+      .iter()
+      .find_map(|attribute| match attribute {
+        AttributeInfo::ConstantValue(index) => Some(*index),
+        _ => None,
+      })
+  }
+}
@@ -0,0 +1,73 @@
+use std::{collections::HashMap, fs, io, path::PathBuf};
+
+use crate::classpath::zip::ZipArchive;
+
+/// A source of class files consulted by [super::Context] when a class is
+/// referenced for the first time.
+pub trait ClassLoader {
+  /// The class file of `class_name` (`java/lang/Object`), [None] if this
+  /// loader does not have it.
+  fn find_class(&self, class_name: &str) -> io::Result<Option<Vec<u8>>>;
+}
+
+/// Loads classes from a directory whose subdirectories follow the package
+/// structure.
+#[derive(Debug)]
+pub struct DirectoryLoader {
+  directory: PathBuf,
+}
+
+impl DirectoryLoader {
+  pub fn new(directory: impl Into<PathBuf>) -> Self {
+    Self {
+      directory: directory.into(),
+    }
+  }
+}
+
+impl ClassLoader for DirectoryLoader {
+  fn find_class(&self, class_name: &str) -> io::Result<Option<Vec<u8>>> {
+    let path = self.directory.join(format!("{class_name}.class"));
+    match fs::read(path) {
+      Ok(bytes) => Ok(Some(bytes)),
+      Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+      Err(error) => Err(error),
+    }
+  }
+}
+
+/// Loads classes from a `.jar` or `.zip` file.
+#[derive(Debug)]
+pub struct JarLoader {
+  archive: ZipArchive,
+}
+
+impl JarLoader {
+  pub fn new(archive: ZipArchive) -> Self {
+    Self { archive }
+  }
+}
+
+impl ClassLoader for JarLoader {
+  fn find_class(&self, class_name: &str) -> io::Result<Option<Vec<u8>>> {
+    self.archive.read(&format!("{class_name}.class"))
+  }
+}
+
+/// Loads classes from class files kept in memory.
+#[derive(Debug, Default)]
+pub struct MemoryLoader {
+  classes: HashMap<String, Vec<u8>>,
+}
+
+impl MemoryLoader {
+  pub fn insert(&mut self, class_name: impl Into<String>, bytes: Vec<u8>) {
+    self.classes.insert(class_name.into(), bytes);
+  }
+}
+
+impl ClassLoader for MemoryLoader {
+  fn find_class(&self, class_name: &str) -> io::Result<Option<Vec<u8>>> {
+    Ok(self.classes.get(class_name).cloned())
+  }
+}
@@ -1,9 +1,78 @@
-use super::attribute_info::AttributeInfo;
+use super::{
+  attribute_info::{AttributeInfo, Code},
+  descriptor::{DescriptorError, MethodDescriptor},
+  ACC_ABSTRACT, ACC_NATIVE, ACC_PRIVATE, ACC_PROTECTED, ACC_PUBLIC, ACC_STATIC,
+};
 
 #[derive(Clone, Debug)]
 pub struct MethodInfo {
   pub access_flags: u16,
   pub name: String,
   pub descriptor: String,
+  /// `descriptor`, parsed when the method is read.
+  pub method_descriptor: MethodDescriptor,
   pub attributes: Vec<AttributeInfo>,
 }
+
+impl MethodInfo {
+  pub fn new(
+    access_flags: u16,
+    name: String,
+    descriptor: String,
+    attributes: Vec<AttributeInfo>,
+  ) -> Result<Self, DescriptorError> {
+    Ok(Self {
+      access_flags,
+      name,
+      method_descriptor: MethodDescriptor::parse(&descriptor)?,
+      descriptor,
+      attributes,
+    })
+  }
+
+  pub fn is_public(&self) -> bool {
+    self.access_flags & ACC_PUBLIC != 0
+  }
+
+  pub fn is_private(&self) -> bool {
+    self.access_flags & ACC_PRIVATE != 0
+  }
+
+  pub fn is_protected(&self) -> bool {
+    self.access_flags & ACC_PROTECTED != 0
+  }
+
+  pub fn is_static(&self) -> bool {
+    self.access_flags & ACC_STATIC != 0
+  }
+
+  pub fn is_abstract(&self) -> bool {
+    self.access_flags & ACC_ABSTRACT != 0
+  }
+
+  pub fn is_native(&self) -> bool {
+    self.access_flags & ACC_NATIVE != 0
+  }
+
+  pub fn code(&self) -> Option<&Code> {
+    self
+      .attributes
+      .iter()
+      .find_map(|attribute| match attribute {
+        AttributeInfo::Code(code) => Some(code),
+        _ => None,
+      })
+  }
+
+  /// Number of arguments taken from the caller's operand stack, including
+  /// `this` for instance methods.
+  pub fn argument_count(&self) -> usize {
+    self.method_descriptor.params.len() + usize::from(!self.is_static())
+  }
+
+  /// Number of local variables the arguments take up, where longs and
+  /// doubles take two.
+  pub fn argument_slots(&self) -> usize {
+    self.method_descriptor.argument_slots() + usize::from(!self.is_static())
+  }
+}
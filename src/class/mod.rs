@@ -1,21 +1,32 @@
 pub use context::Context;
+pub use error::ClassFormatError;
 
 pub mod attribute_info;
 pub mod context;
+pub mod descriptor;
+pub mod error;
 pub mod field;
+pub mod loader;
 pub mod method;
 pub mod native;
 pub mod pool;
+pub mod registry;
 
-use std::io::Read;
-
-use crate::class::pool::Entry;
+use std::{
+  cell::{Cell, RefCell},
+  collections::HashMap,
+  io::Read,
+  rc::Rc,
+};
 
 use self::{
-  attribute_info::{AttributeInfo, Code},
+  attribute_info::{AttributeInfo, BootstrapMethod, Code},
+  descriptor::{FieldType, MethodDescriptor},
   field::FieldInfo,
   method::MethodInfo,
+  pool::{ConstantPool, PoolError},
 };
+use crate::value::MistValue;
 
 /// Declared public; may be accessed from outside its package.
 pub const ACC_PUBLIC: u16 = 0x0001;
@@ -43,22 +54,148 @@ pub const ACC_SYNTHETIC: u16 = 0x1000;
 pub const ACC_ANNOTATION: u16 = 0x2000;
 /// Declared as an enum type.
 pub const ACC_ENUM: u16 = 0x4000;
+/// Declared native; implemented in a language other than Java.
+pub const ACC_NATIVE: u16 = 0x0100;
+
+/// Where a class is in the initialization procedure (JVMS §5.5).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InitState {
+  #[default]
+  Uninitialized,
+  /// `<clinit>` is running; further requests proceed as if it was done.
+  BeingInitialized,
+  Initialized,
+  /// Initialization failed, so every later attempt does too.
+  Erroneous,
+}
+
+/// The method an interface call runs for a given receiver class
+/// (JVMS §6.5.invokeinterface).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Selected {
+  /// A method, by [Class::id] and index into [Class::methods].
+  Method(usize, usize),
+  /// There is no implementation to run.
+  Abstract,
+  /// Several default methods are maximally specific.
+  Conflict,
+}
 
 #[derive(Clone, Debug, Default)]
 pub struct Class {
   pub minor_version: u16,
   pub major_version: u16,
-  pub constant_pool: Vec<pool::Entry>,
+  pub constant_pool: ConstantPool,
   pub access_flags: u16,
   pub this_class: String,
   pub super_class: String,
-  pub interfaces: Vec<u16>,
+  pub interfaces: Vec<String>,
   pub fields: Vec<FieldInfo>,
   pub methods: Vec<MethodInfo>,
   pub attributes: Vec<AttributeInfo>,
+  pub superclass: Option<Rc<Class>>,
+  /// The classes named by [Class::interfaces].
+  pub superinterfaces: Vec<Rc<Class>>,
+  /// Numbers the class within its [Context], for [crate::heap::Object].
+  pub id: usize,
+  /// Where each field lives: in [Class::statics] for static fields, in the
+  /// memory of an instance otherwise. Indexed like [Class::fields].
+  pub slots: Vec<usize>,
+  /// Initial memory of an instance, inherited fields first.
+  pub layout: Vec<MistValue>,
+  /// The instance method selected for each virtual slot, as its
+  /// [Class::id] and index into [Class::methods].
+  pub vtable: Vec<(usize, usize)>,
+  /// What `invokeinterface` selects for each method of each interface the
+  /// class implements, by [Class::id] of the interface and then indexed like
+  /// its [Class::methods].
+  pub itable: HashMap<usize, Vec<Selected>>,
+  pub state: Cell<InitState>,
+  pub statics: RefCell<Vec<MistValue>>,
 }
 
 impl Class {
+  /// Lays out the fields after those of `superclass`, and creates the
+  /// static ones with their default values (JVMS §5.4.2).
+  pub fn prepare(&mut self, superclass: Option<&Class>) {
+    let mut statics = Vec::new();
+    let mut layout = superclass
+      .map(|superclass| superclass.layout.clone())
+      .unwrap_or_default();
+
+    self.slots = self
+      .fields
+      .iter()
+      .map(|field| {
+        let memory = if field.is_static() {
+          &mut statics
+        } else {
+          &mut layout
+        };
+        memory.push(field.field_type().default_value());
+        memory.len() - 1
+      })
+      .collect();
+
+    self.statics = RefCell::new(statics);
+    self.layout = layout;
+  }
+
+  pub fn is_interface(&self) -> bool {
+    self.access_flags & ACC_INTERFACE != 0
+  }
+
+  pub fn is_abstract(&self) -> bool {
+    self.access_flags & ACC_ABSTRACT != 0
+  }
+
+  /// The runtime package, such as `java/lang` for `java/lang/Object`.
+  pub fn package(&self) -> &str {
+    self
+      .this_class
+      .rsplit_once('/')
+      .map_or("", |(package, _)| package)
+  }
+
+  /// Every interface the class implements, directly or through its
+  /// superclasses and superinterfaces.
+  pub fn all_interfaces(&self) -> Vec<Rc<Class>> {
+    let mut interfaces: Vec<Rc<Class>> = self
+      .superclass
+      .as_ref()
+      .map(|superclass| superclass.all_interfaces())
+      .unwrap_or_default();
+
+    for interface in &self.superinterfaces {
+      let inherited = interface.all_interfaces();
+      for interface in std::iter::once(interface).chain(&inherited) {
+        if !interfaces.iter().any(|known| known.id == interface.id) {
+          interfaces.push(Rc::clone(interface));
+        }
+      }
+    }
+    interfaces
+  }
+
+  /// Whether `other` is a proper superinterface of this class or interface.
+  pub fn is_subinterface_of(&self, other: &Class) -> bool {
+    self.superinterfaces.iter().any(|interface| {
+      interface.id == other.id || interface.is_subinterface_of(other)
+    })
+  }
+
+  /// Whether `other` is a proper superclass of this class.
+  pub fn is_subclass_of(&self, other: &Class) -> bool {
+    let mut superclass = self.superclass.as_deref();
+    while let Some(class) = superclass {
+      if class.id == other.id {
+        return true;
+      }
+      superclass = class.superclass.as_deref();
+    }
+    false
+  }
+
   pub fn find_method(&self, method_name: &str) -> Option<&MethodInfo> {
     self
       .methods
@@ -75,58 +212,95 @@ impl Class {
       method.name == method_name && method.descriptor == descriptor
     })
   }
+
+  pub fn method_index(
+    &self,
+    method_name: &str,
+    descriptor: &str,
+  ) -> Option<usize> {
+    self.methods.iter().position(|method| {
+      method.name == method_name && method.descriptor == descriptor
+    })
+  }
+
+  pub fn field_index(
+    &self,
+    field_name: &str,
+    descriptor: &str,
+  ) -> Option<usize> {
+    self.fields.iter().position(|field| {
+      field.name == field_name && field.descriptor == descriptor
+    })
+  }
+
+  /// The bootstrap methods of the `invokedynamic` call sites in the class.
+  pub fn bootstrap_methods(&self) -> &[BootstrapMethod] {
+    self
+      .attributes
+      .iter()
+      .find_map(|attribute| match attribute {
+        AttributeInfo::BootstrapMethods(methods) => Some(methods.as_slice()),
+        _ => None,
+      })
+      .unwrap_or_default()
+  }
+
+  /// Name of the file the class was compiled from, without directories.
+  pub fn source_file(&self) -> Option<&str> {
+    self
+      .attributes
+      .iter()
+      .find_map(|attribute| match attribute {
+        AttributeInfo::SourceFile(source_file) => Some(source_file.as_str()),
+        _ => None,
+      })
+  }
 }
 
 pub struct Reader<R: Read> {
-  buf: R,
+  buf: Counted<R>,
 }
 
 impl<R: Read> Reader<R> {
   pub fn new(buf: R) -> Self {
-    Self { buf }
+    Self {
+      buf: Counted {
+        inner: buf,
+        offset: 0,
+      },
+    }
   }
 }
 
 impl<R: Read> Reader<R> {
-  pub fn read_class(&mut self) -> std::io::Result<Class> {
+  pub fn read_class(&mut self) -> Result<Class, ClassFormatError> {
     let (minor_version, major_version, constant_pool_count) =
       self.read_header()?;
 
-    let constant_pool = self.read_constant_pool(constant_pool_count)?;
+    let constant_pool =
+      ConstantPool::new(self.read_constant_pool(constant_pool_count)?);
 
     let (access_flags, this_class, super_class, interfaces_count) =
       self.read_type_info()?;
 
-    let Entry::ClassInfo { name_index } =
-      constant_pool[this_class as usize].clone()
-    else {
-      panic!();
-    };
-    let Entry::Utf8Info { bytes: this_class } =
-      constant_pool[name_index as usize].clone()
-    else {
-      panic!();
-    };
+    let this_class = self.class_name(&constant_pool, this_class)?;
+    let in_class = |error: ClassFormatError| error.in_class(&this_class);
 
-    let Entry::ClassInfo { name_index } =
-      constant_pool[super_class as usize].clone()
-    else {
-      panic!();
-    };
-    let Entry::Utf8Info { bytes: super_class } =
-      constant_pool[name_index as usize].clone()
-    else {
-      panic!();
+    // Only java/lang/Object has no superclass.
+    let super_class = match super_class {
+      0 => String::new(),
+      index => self.class_name(&constant_pool, index).map_err(in_class)?,
     };
 
-    let interfaces = self.read_interfaces(interfaces_count)?;
+    let interfaces = self
+      .read_interfaces(&constant_pool, interfaces_count)
+      .map_err(in_class)?;
 
-    let fields = self.read_fields(&constant_pool)?;
+    let fields = self.read_fields(&constant_pool).map_err(in_class)?;
 
-    let methods = self.read_methods(&constant_pool)?;
+    let methods = self.read_methods(&constant_pool).map_err(in_class)?;
 
-    // TODO: read attributes
-    let (_attributes_count, attributes) = (0, Vec::new());
+    let attributes = self.read_attributes(&constant_pool).map_err(in_class)?;
 
     let class = Class {
       minor_version,
@@ -139,16 +313,17 @@ impl<R: Read> Reader<R> {
       fields,
       methods,
       attributes,
+      ..Class::default()
     };
 
     Ok(class)
   }
 
-  pub fn read_header(&mut self) -> std::io::Result<(u16, u16, u16)> {
+  pub fn read_header(&mut self) -> Result<(u16, u16, u16), ClassFormatError> {
     let magic = self.buf.read_u32()?;
 
     if magic != 0xCAFEBABE {
-      panic!("Is not a .class file.");
+      return Err(ClassFormatError::BadMagic { magic });
     }
 
     let minor_version = self.buf.read_u16()?;
@@ -161,14 +336,12 @@ impl<R: Read> Reader<R> {
   pub fn read_constant_pool(
     &mut self,
     constant_pool_count: u16,
-  ) -> std::io::Result<Vec<pool::Entry>> {
-    let constant_pool_count = constant_pool_count - 1;
+  ) -> Result<Vec<pool::Entry>, ClassFormatError> {
+    let constant_pool_count = constant_pool_count.saturating_sub(1);
     let mut constant_pool_counter = 0;
     let mut constant_pool_entries = Vec::new();
-    constant_pool_entries.resize(
-      (constant_pool_count + 1) as usize,
-      pool::Entry::IntegerInfo { bytes: 0 },
-    );
+    constant_pool_entries
+      .resize((constant_pool_count + 1) as usize, pool::Entry::Unusable);
 
     while constant_pool_counter < constant_pool_count {
       constant_pool_counter += 1;
@@ -179,10 +352,13 @@ impl<R: Read> Reader<R> {
         pool::UTF_8 => {
           let length = self.buf.read_u16()?;
 
-          let mut buf = vec![0u8; length as usize];
-          self.buf.read_exact(&mut buf[..])?;
-          let bytes =
-            String::from_utf8(buf).expect("To be a valid UTF-8 String.");
+          let buf = self.buf.read_bytes(length as usize)?;
+          let bytes = pool::decode_modified_utf8(&buf).ok_or(
+            ClassFormatError::InvalidUtf8 {
+              offset: self.buf.offset,
+              index: constant_pool_counter,
+            },
+          )?;
 
           pool::Entry::Utf8Info { bytes }
         }
@@ -208,10 +384,27 @@ impl<R: Read> Reader<R> {
             name_and_type_index,
           }
         }
+        pool::INTERFACE_METHOD_REF => {
+          let class_index = self.buf.read_u16()?;
+          let name_and_type_index = self.buf.read_u16()?;
+
+          pool::Entry::InterfaceMethodRefInfo {
+            class_index,
+            name_and_type_index,
+          }
+        }
+        pool::STRING => {
+          let string_index = self.buf.read_u16()?;
+          pool::Entry::StringInfo { string_index }
+        }
         pool::INTEGER => {
           let integer = self.buf.read_u32()?;
           pool::Entry::IntegerInfo { bytes: integer }
         }
+        pool::FLOAT => {
+          let float = self.buf.read_u32()?;
+          pool::Entry::FloatInfo { bytes: float }
+        }
         pool::FIELD_REF => {
           let class_index = self.buf.read_u16()?;
           let name_and_type_index = self.buf.read_u16()?;
@@ -241,7 +434,52 @@ impl<R: Read> Reader<R> {
             low_bytes,
           }
         }
-        other => todo!("{other:x}"),
+        pool::METHOD_HANDLE => {
+          let reference_kind = self.buf.read_u8()?;
+          let reference_index = self.buf.read_u16()?;
+
+          pool::Entry::MethodHandleInfo {
+            reference_kind,
+            reference_index,
+          }
+        }
+        pool::METHOD_TYPE => {
+          let descriptor_index = self.buf.read_u16()?;
+          pool::Entry::MethodTypeInfo { descriptor_index }
+        }
+        pool::DYNAMIC => {
+          let bootstrap_method_attr_index = self.buf.read_u16()?;
+          let name_and_type_index = self.buf.read_u16()?;
+
+          pool::Entry::DynamicInfo {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+          }
+        }
+        pool::INVOKE_DYNAMIC => {
+          let bootstrap_method_attr_index = self.buf.read_u16()?;
+          let name_and_type_index = self.buf.read_u16()?;
+
+          pool::Entry::InvokeDynamicInfo {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+          }
+        }
+        pool::MODULE => {
+          let name_index = self.buf.read_u16()?;
+          pool::Entry::ModuleInfo { name_index }
+        }
+        pool::PACKAGE => {
+          let name_index = self.buf.read_u16()?;
+          pool::Entry::PackageInfo { name_index }
+        }
+        tag => {
+          return Err(ClassFormatError::UnknownTag {
+            offset: self.buf.offset - 1,
+            index: constant_pool_counter,
+            tag,
+          })
+        }
       };
       // println!("{constant_pool_counter} = {item:?}");
       constant_pool_entries[constant_pool_counter as usize] = item;
@@ -253,19 +491,22 @@ impl<R: Read> Reader<R> {
     Ok(constant_pool_entries)
   }
 
-  pub fn read_type_info(&mut self) -> std::io::Result<(u16, u16, u16, u16)> {
+  pub fn read_type_info(
+    &mut self,
+  ) -> Result<(u16, u16, u16, u16), ClassFormatError> {
     self.buf.read_4_u16()
   }
 
   pub fn read_interfaces(
     &mut self,
+    constant_pool: &ConstantPool,
     interfaces_count: u16,
-  ) -> std::io::Result<Vec<u16>> {
+  ) -> Result<Vec<String>, ClassFormatError> {
     let mut interfaces = Vec::with_capacity(interfaces_count as usize);
 
     for _ in 0..interfaces_count {
       let interface = self.buf.read_u16()?;
-      interfaces.push(interface);
+      interfaces.push(self.class_name(constant_pool, interface)?);
     }
 
     Ok(interfaces)
@@ -273,74 +514,41 @@ impl<R: Read> Reader<R> {
 
   pub fn read_fields(
     &mut self,
-    constant_pool: &[pool::Entry],
-  ) -> std::io::Result<Vec<FieldInfo>> {
+    constant_pool: &ConstantPool,
+  ) -> Result<Vec<FieldInfo>, ClassFormatError> {
     let fields_count = self.buf.read_u16()?;
     let mut fields = Vec::with_capacity(fields_count as usize);
 
     for _ in 0..fields_count {
-      let (access_flags, name_index, descriptor_index, attributes_count) =
-        self.buf.read_4_u16()?;
-
-      let attributes = Vec::with_capacity(attributes_count as usize);
+      let access_flags = self.buf.read_u16()?;
+      let name_index = self.buf.read_u16()?;
+      let name = self.utf8(constant_pool, name_index)?;
 
-      let Entry::Utf8Info { bytes: name } =
-        constant_pool[name_index as usize].clone()
-      else {
-        panic!();
-      };
+      let descriptor_index = self.buf.read_u16()?;
+      let descriptor = self.utf8(constant_pool, descriptor_index)?;
+      if FieldType::parse(&descriptor).is_err() {
+        return Err(self.bad_descriptor(descriptor));
+      }
 
-      let Entry::Utf8Info { bytes: descriptor } =
-        constant_pool[descriptor_index as usize].clone()
-      else {
-        panic!();
-      };
+      let attributes = self.read_attributes(constant_pool)?;
 
-      let mut field_info = FieldInfo {
+      let field_info = FieldInfo {
         access_flags,
         name,
         descriptor,
         attributes,
       };
 
-      // TODO: check if this is right
-      for _ in 0..attributes_count {
-        let attribute = self.read_field_attribute(constant_pool)?;
-        field_info.attributes.push(attribute);
-      }
-
       fields.push(field_info);
     }
 
     Ok(fields)
   }
 
-  pub fn read_field_attribute(
-    &mut self,
-    constant_pool: &[pool::Entry],
-  ) -> std::io::Result<AttributeInfo> {
-    // let attribute_name_index = self.buf.read_u16()?;
-    // let attribute_length = self.buf.read_u32()?;
-
-    // // println!("{:?}", constant_pool[attribute_name_index as usize]);
-
-    // let mut buf = vec![0; attribute_length as usize];
-    // self.buf.read_exact(&mut buf[..])?;
-
-    // let attribute_info = AttributeInfo {
-    //   attribute_name_index,
-    //   attribute_length,
-    //   info: attribute_info::Info::Bytes(buf),
-    // };
-
-    // Ok(attribute_info)
-    self.read_method_attribute(constant_pool)
-  }
-
   pub fn read_methods(
     &mut self,
-    constant_pool: &[pool::Entry],
-  ) -> std::io::Result<Vec<MethodInfo>> {
+    constant_pool: &ConstantPool,
+  ) -> Result<Vec<MethodInfo>, ClassFormatError> {
     let methods_count = self.buf.read_u16()?;
 
     let mut methods = Vec::with_capacity(methods_count as usize);
@@ -348,32 +556,21 @@ impl<R: Read> Reader<R> {
     for _ in 0..methods_count {
       let access_flags = self.buf.read_u16()?;
       let name_index = self.buf.read_u16()?;
-      let Entry::Utf8Info { bytes: name } =
-        constant_pool[name_index as usize].clone()
-      else {
-        panic!()
-      };
+      let name = self.utf8(constant_pool, name_index)?;
 
       let descriptor_index = self.buf.read_u16()?;
-      let Entry::Utf8Info { bytes: descriptor } =
-        constant_pool[descriptor_index as usize].clone()
-      else {
-        panic!();
+      let descriptor = self.utf8(constant_pool, descriptor_index)?;
+      let Ok(method_descriptor) = MethodDescriptor::parse(&descriptor) else {
+        return Err(self.bad_descriptor(descriptor));
       };
 
-      let attributes_count = self.buf.read_u16()?;
-      let mut attributes = Vec::with_capacity(attributes_count as usize);
-
-      // TODO: check if this is right
-      for _ in 0..attributes_count {
-        let attribute = self.read_method_attribute(constant_pool)?;
-        attributes.push(attribute);
-      }
+      let attributes = self.read_attributes(constant_pool)?;
 
       let method_info = MethodInfo {
         access_flags,
         name,
         descriptor,
+        method_descriptor,
         attributes,
       };
 
@@ -383,122 +580,719 @@ impl<R: Read> Reader<R> {
     Ok(methods)
   }
 
-  pub fn read_method_attribute(
+  /// Reads `attributes_count` followed by that many attributes.
+  pub fn read_attributes(
+    &mut self,
+    constant_pool: &ConstantPool,
+  ) -> Result<Vec<AttributeInfo>, ClassFormatError> {
+    let attributes_count = self.buf.read_u16()?;
+
+    let mut attributes = Vec::with_capacity(attributes_count as usize);
+    for _ in 0..attributes_count {
+      attributes.push(self.read_attribute(constant_pool)?);
+    }
+
+    Ok(attributes)
+  }
+
+  pub fn read_attribute(
     &mut self,
-    constant_pool: &[pool::Entry],
-  ) -> std::io::Result<AttributeInfo> {
+    constant_pool: &ConstantPool,
+  ) -> Result<AttributeInfo, ClassFormatError> {
     let attribute_name_index = self.buf.read_u16()?;
     let attribute_length = self.buf.read_u32()?;
 
-    let cp_entry = &constant_pool[attribute_name_index as usize];
-
-    // TODO: refactor this messy code.
-    if let Entry::Utf8Info { bytes } = cp_entry {
-      match bytes.as_ref() {
-        "LineNumberTable" => {
-          let line_number_table_length = self.buf.read_u16()?;
-
-          let mut line_number_table =
-            Vec::with_capacity(line_number_table_length as usize);
-          for _ in 0..line_number_table_length {
-            line_number_table.push(attribute_info::LineNumberTableInfo {
-              start_pc: self.buf.read_u16()?,
-              line_number: self.buf.read_u16()?,
-            });
-          }
+    let name = self.utf8(constant_pool, attribute_name_index)?;
+
+    // The contents are parsed from their own buffer, so that a malformed
+    // attribute can never throw the rest of the class file out of step.
+    let offset = self.buf.offset;
+    let info = self.buf.read_bytes(attribute_length as usize)?;
+    let mut reader = Reader {
+      buf: Counted {
+        inner: &info[..],
+        offset,
+      },
+    };
 
-          let line_number_table = attribute_info::LineNumberTable {
-            attribute_name_index,
-            attribute_length,
-            line_number_table,
-          };
-
-          Ok(attribute_info::AttributeInfo::LineNumberTable(
-            line_number_table,
-          ))
-        }
-        "Code" => {
-          let max_stack = self.buf.read_u16()?;
-          let max_local = self.buf.read_u16()?;
-          let code_length = self.buf.read_u32()?;
-
-          let mut code = vec![0; code_length as usize];
-          self.buf.read_exact(&mut code[..])?;
-
-          let exception_table_length = self.buf.read_u16()?;
-
-          let mut exception_table = Vec::new();
-          for _ in 0..exception_table_length {
-            let (start_pc, end_pc, handler_pc, catch_type) =
-              self.buf.read_4_u16()?;
-            exception_table.push(attribute_info::ExceptionTableInfo {
-              start_pc,
-              end_pc,
-              handler_pc,
-              catch_type,
-            });
-          }
+    let bad_attribute = || ClassFormatError::BadAttribute {
+      offset,
+      name: name.clone(),
+    };
+
+    let attribute = reader
+      .read_attribute_info(
+        &name,
+        attribute_name_index,
+        attribute_length,
+        constant_pool,
+      )
+      .map_err(|error| match error {
+        ClassFormatError::Io { .. } => bad_attribute(),
+        error => error,
+      })?;
+
+    match attribute {
+      Some(_) if reader.buf.offset != offset + info.len() => {
+        Err(bad_attribute())
+      }
+      Some(attribute) => Ok(attribute),
+      None => Ok(AttributeInfo::Bytes { name, info }),
+    }
+  }
+
+  /// Parses the contents of the attribute called `name`, [None] when it is
+  /// not one this reader interprets.
+  fn read_attribute_info(
+    &mut self,
+    name: &str,
+    attribute_name_index: u16,
+    attribute_length: u32,
+    constant_pool: &ConstantPool,
+  ) -> Result<Option<AttributeInfo>, ClassFormatError> {
+    let attribute = match name {
+      "LineNumberTable" => {
+        let line_number_table_length = self.buf.read_u16()?;
+
+        let mut line_number_table =
+          Vec::with_capacity(line_number_table_length as usize);
+        for _ in 0..line_number_table_length {
+          line_number_table.push(attribute_info::LineNumberTableInfo {
+            start_pc: self.buf.read_u16()?,
+            line_number: self.buf.read_u16()?,
+          });
+        }
+
+        let line_number_table = attribute_info::LineNumberTable {
+          attribute_name_index,
+          attribute_length,
+          line_number_table,
+        };
 
-          let attributes_count = self.buf.read_u16()?;
+        AttributeInfo::LineNumberTable(line_number_table)
+      }
+      "Code" => {
+        let max_stack = self.buf.read_u16()?;
+        let max_local = self.buf.read_u16()?;
+        let code_length = self.buf.read_u32()?;
+
+        let code = self.buf.read_bytes(code_length as usize)?;
+
+        let exception_table_length = self.buf.read_u16()?;
+
+        let mut exception_table = Vec::new();
+        for _ in 0..exception_table_length {
+          let (start_pc, end_pc, handler_pc, catch_type) =
+            self.buf.read_4_u16()?;
+          exception_table.push(attribute_info::ExceptionTableInfo {
+            start_pc,
+            end_pc,
+            handler_pc,
+            catch_type,
+          });
+        }
 
-          let mut attributes = Vec::with_capacity(attributes_count as usize);
-          for _ in 0..attributes_count {
-            let attribute = self.read_method_attribute(constant_pool)?;
-            attributes.push(attribute);
+        let attributes = self.read_attributes(constant_pool)?;
+
+        let code = Code {
+          native: None,
+          max_stack,
+          max_local,
+          code,
+          exception_table,
+          attributes,
+        };
+
+        AttributeInfo::Code(code)
+      }
+      "SourceFile" => {
+        let sourcefile_index = self.buf.read_u16()?;
+        AttributeInfo::SourceFile(self.utf8(constant_pool, sourcefile_index)?)
+      }
+      "ConstantValue" => AttributeInfo::ConstantValue(self.buf.read_u16()?),
+      "Exceptions" => {
+        AttributeInfo::Exceptions(self.read_class_names(constant_pool)?)
+      }
+      "InnerClasses" => {
+        let number_of_classes = self.buf.read_u16()?;
+
+        let mut classes = Vec::with_capacity(number_of_classes as usize);
+        for _ in 0..number_of_classes {
+          let (inner, outer, inner_name, inner_class_access_flags) =
+            self.buf.read_4_u16()?;
+          classes.push(attribute_info::InnerClass {
+            inner_class: self.class_name(constant_pool, inner)?,
+            outer_class: self
+              .optional(outer, |index| self.class_name(constant_pool, index))?,
+            inner_name: self
+              .optional(inner_name, |index| self.utf8(constant_pool, index))?,
+            inner_class_access_flags,
+          });
+        }
+
+        AttributeInfo::InnerClasses(classes)
+      }
+      "EnclosingMethod" => {
+        let class_index = self.buf.read_u16()?;
+        let method_index = self.buf.read_u16()?;
+
+        let class = self.class_name(constant_pool, class_index)?;
+        let method = self.optional(method_index, |index| {
+          let (name, descriptor) = constant_pool
+            .name_and_type(index)
+            .map_err(|error| self.bad_pool_index(error))?;
+          Ok((name.to_string(), descriptor.to_string()))
+        })?;
+
+        AttributeInfo::EnclosingMethod(attribute_info::EnclosingMethod {
+          class,
+          method,
+        })
+      }
+      "Signature" => {
+        let signature_index = self.buf.read_u16()?;
+        AttributeInfo::Signature(self.utf8(constant_pool, signature_index)?)
+      }
+      "LocalVariableTable" => AttributeInfo::LocalVariableTable(
+        self.read_local_variables(constant_pool)?,
+      ),
+      "LocalVariableTypeTable" => AttributeInfo::LocalVariableTypeTable(
+        self.read_local_variables(constant_pool)?,
+      ),
+      "StackMapTable" => {
+        let number_of_entries = self.buf.read_u16()?;
+
+        let mut entries = Vec::with_capacity(number_of_entries as usize);
+        for _ in 0..number_of_entries {
+          entries.push(self.read_stack_map_frame(constant_pool)?);
+        }
+
+        AttributeInfo::StackMapTable(entries)
+      }
+      "BootstrapMethods" => {
+        let num_bootstrap_methods = self.buf.read_u16()?;
+
+        let mut bootstrap_methods =
+          Vec::with_capacity(num_bootstrap_methods as usize);
+        for _ in 0..num_bootstrap_methods {
+          let bootstrap_method_ref = self.buf.read_u16()?;
+          let num_bootstrap_arguments = self.buf.read_u16()?;
+
+          let mut bootstrap_arguments =
+            Vec::with_capacity(num_bootstrap_arguments as usize);
+          for _ in 0..num_bootstrap_arguments {
+            bootstrap_arguments.push(self.buf.read_u16()?);
           }
 
-          let code = Code {
-            native: None,
-            max_stack,
-            max_local,
-            code,
-            exception_table,
-            attributes,
-          };
+          bootstrap_methods.push(attribute_info::BootstrapMethod {
+            bootstrap_method_ref,
+            bootstrap_arguments,
+          });
+        }
+
+        AttributeInfo::BootstrapMethods(bootstrap_methods)
+      }
+      "NestHost" => {
+        let host_class_index = self.buf.read_u16()?;
+        AttributeInfo::NestHost(
+          self.class_name(constant_pool, host_class_index)?,
+        )
+      }
+      "NestMembers" => {
+        AttributeInfo::NestMembers(self.read_class_names(constant_pool)?)
+      }
+      "Record" => {
+        let components_count = self.buf.read_u16()?;
 
-          Ok(attribute_info::AttributeInfo::Code(code))
+        let mut components = Vec::with_capacity(components_count as usize);
+        for _ in 0..components_count {
+          let name_index = self.buf.read_u16()?;
+          let descriptor_index = self.buf.read_u16()?;
+          components.push(attribute_info::RecordComponent {
+            name: self.utf8(constant_pool, name_index)?,
+            descriptor: self.utf8(constant_pool, descriptor_index)?,
+            attributes: self.read_attributes(constant_pool)?,
+          });
         }
-        other => unimplemented!("{other:?}"),
+
+        AttributeInfo::Record(components)
       }
-    } else {
-      panic!("Attribute name index '{attribute_name_index}' needs to point to a Utf-8 entry.");
+      "PermittedSubclasses" => AttributeInfo::PermittedSubclasses(
+        self.read_class_names(constant_pool)?,
+      ),
+      "MethodParameters" => {
+        let parameters_count = self.buf.read_u8()?;
+
+        let mut parameters = Vec::with_capacity(parameters_count as usize);
+        for _ in 0..parameters_count {
+          let name_index = self.buf.read_u16()?;
+          let access_flags = self.buf.read_u16()?;
+          parameters.push(attribute_info::MethodParameter {
+            name: self
+              .optional(name_index, |index| self.utf8(constant_pool, index))?,
+            access_flags,
+          });
+        }
+
+        AttributeInfo::MethodParameters(parameters)
+      }
+      "RuntimeVisibleAnnotations" => AttributeInfo::RuntimeVisibleAnnotations(
+        self.read_annotations(constant_pool)?,
+      ),
+      "RuntimeInvisibleAnnotations" => {
+        AttributeInfo::RuntimeInvisibleAnnotations(
+          self.read_annotations(constant_pool)?,
+        )
+      }
+      "RuntimeVisibleParameterAnnotations" => {
+        AttributeInfo::RuntimeVisibleParameterAnnotations(
+          self.read_parameter_annotations(constant_pool)?,
+        )
+      }
+      "RuntimeInvisibleParameterAnnotations" => {
+        AttributeInfo::RuntimeInvisibleParameterAnnotations(
+          self.read_parameter_annotations(constant_pool)?,
+        )
+      }
+      "RuntimeVisibleTypeAnnotations" => {
+        AttributeInfo::RuntimeVisibleTypeAnnotations(
+          self.read_type_annotations(constant_pool)?,
+        )
+      }
+      "RuntimeInvisibleTypeAnnotations" => {
+        AttributeInfo::RuntimeInvisibleTypeAnnotations(
+          self.read_type_annotations(constant_pool)?,
+        )
+      }
+      "AnnotationDefault" => AttributeInfo::AnnotationDefault(
+        self.read_element_value(constant_pool)?,
+      ),
+      "Synthetic" => AttributeInfo::Synthetic,
+      "Deprecated" => AttributeInfo::Deprecated,
+      _ => return Ok(None),
+    };
+
+    Ok(Some(attribute))
+  }
+
+  /// Reads a count followed by that many `CONSTANT_Class` indices.
+  fn read_class_names(
+    &mut self,
+    constant_pool: &ConstantPool,
+  ) -> Result<Vec<String>, ClassFormatError> {
+    let number_of_classes = self.buf.read_u16()?;
+
+    let mut classes = Vec::with_capacity(number_of_classes as usize);
+    for _ in 0..number_of_classes {
+      let class_index = self.buf.read_u16()?;
+      classes.push(self.class_name(constant_pool, class_index)?);
+    }
+
+    Ok(classes)
+  }
+
+  fn read_local_variables(
+    &mut self,
+    constant_pool: &ConstantPool,
+  ) -> Result<Vec<attribute_info::LocalVariable>, ClassFormatError> {
+    let table_length = self.buf.read_u16()?;
+
+    let mut table = Vec::with_capacity(table_length as usize);
+    for _ in 0..table_length {
+      let (start_pc, length, name_index, descriptor_index) =
+        self.buf.read_4_u16()?;
+      let index = self.buf.read_u16()?;
+      table.push(attribute_info::LocalVariable {
+        start_pc,
+        length,
+        name: self.utf8(constant_pool, name_index)?,
+        descriptor: self.utf8(constant_pool, descriptor_index)?,
+        index,
+      });
+    }
+
+    Ok(table)
+  }
+
+  fn read_stack_map_frame(
+    &mut self,
+    constant_pool: &ConstantPool,
+  ) -> Result<attribute_info::StackMapFrame, ClassFormatError> {
+    use attribute_info::StackMapFrame;
+
+    let frame_type = self.buf.read_u8()?;
+
+    let frame = match frame_type {
+      0..=63 => StackMapFrame::Same {
+        offset_delta: frame_type as u16,
+      },
+      64..=127 => StackMapFrame::SameLocals1StackItem {
+        offset_delta: frame_type as u16 - 64,
+        stack: self.read_verification_type(constant_pool)?,
+      },
+      247 => StackMapFrame::SameLocals1StackItem {
+        offset_delta: self.buf.read_u16()?,
+        stack: self.read_verification_type(constant_pool)?,
+      },
+      248..=250 => StackMapFrame::Chop {
+        offset_delta: self.buf.read_u16()?,
+        k: 251 - frame_type,
+      },
+      251 => StackMapFrame::Same {
+        offset_delta: self.buf.read_u16()?,
+      },
+      252..=254 => {
+        let offset_delta = self.buf.read_u16()?;
+
+        let mut locals = Vec::with_capacity((frame_type - 251) as usize);
+        for _ in 251..frame_type {
+          locals.push(self.read_verification_type(constant_pool)?);
+        }
+
+        StackMapFrame::Append {
+          offset_delta,
+          locals,
+        }
+      }
+      255 => {
+        let offset_delta = self.buf.read_u16()?;
+
+        let number_of_locals = self.buf.read_u16()?;
+        let mut locals = Vec::with_capacity(number_of_locals as usize);
+        for _ in 0..number_of_locals {
+          locals.push(self.read_verification_type(constant_pool)?);
+        }
+
+        let number_of_stack_items = self.buf.read_u16()?;
+        let mut stack = Vec::with_capacity(number_of_stack_items as usize);
+        for _ in 0..number_of_stack_items {
+          stack.push(self.read_verification_type(constant_pool)?);
+        }
+
+        StackMapFrame::Full {
+          offset_delta,
+          locals,
+          stack,
+        }
+      }
+      _ => return Err(self.malformed()),
+    };
+
+    Ok(frame)
+  }
+
+  fn read_verification_type(
+    &mut self,
+    constant_pool: &ConstantPool,
+  ) -> Result<attribute_info::VerificationType, ClassFormatError> {
+    use attribute_info::VerificationType;
+
+    let verification_type = match self.buf.read_u8()? {
+      0 => VerificationType::Top,
+      1 => VerificationType::Integer,
+      2 => VerificationType::Float,
+      3 => VerificationType::Double,
+      4 => VerificationType::Long,
+      5 => VerificationType::Null,
+      6 => VerificationType::UninitializedThis,
+      7 => {
+        let cpool_index = self.buf.read_u16()?;
+        VerificationType::Object(self.class_name(constant_pool, cpool_index)?)
+      }
+      8 => VerificationType::Uninitialized(self.buf.read_u16()?),
+      _ => return Err(self.malformed()),
+    };
+
+    Ok(verification_type)
+  }
+
+  fn read_annotations(
+    &mut self,
+    constant_pool: &ConstantPool,
+  ) -> Result<Vec<attribute_info::Annotation>, ClassFormatError> {
+    let num_annotations = self.buf.read_u16()?;
+
+    let mut annotations = Vec::with_capacity(num_annotations as usize);
+    for _ in 0..num_annotations {
+      annotations.push(self.read_annotation(constant_pool)?);
+    }
+
+    Ok(annotations)
+  }
+
+  fn read_parameter_annotations(
+    &mut self,
+    constant_pool: &ConstantPool,
+  ) -> Result<Vec<Vec<attribute_info::Annotation>>, ClassFormatError> {
+    let num_parameters = self.buf.read_u8()?;
+
+    let mut parameter_annotations = Vec::with_capacity(num_parameters as usize);
+    for _ in 0..num_parameters {
+      parameter_annotations.push(self.read_annotations(constant_pool)?);
+    }
+
+    Ok(parameter_annotations)
+  }
+
+  fn read_annotation(
+    &mut self,
+    constant_pool: &ConstantPool,
+  ) -> Result<attribute_info::Annotation, ClassFormatError> {
+    let type_index = self.buf.read_u16()?;
+    let type_name = self.utf8(constant_pool, type_index)?;
+
+    let num_element_value_pairs = self.buf.read_u16()?;
+    let mut element_value_pairs =
+      Vec::with_capacity(num_element_value_pairs as usize);
+    for _ in 0..num_element_value_pairs {
+      let element_name_index = self.buf.read_u16()?;
+      let element_name = self.utf8(constant_pool, element_name_index)?;
+      let value = self.read_element_value(constant_pool)?;
+      element_value_pairs.push((element_name, value));
+    }
+
+    Ok(attribute_info::Annotation {
+      type_name,
+      element_value_pairs,
+    })
+  }
+
+  fn read_element_value(
+    &mut self,
+    constant_pool: &ConstantPool,
+  ) -> Result<attribute_info::ElementValue, ClassFormatError> {
+    use attribute_info::ElementValue;
+
+    let tag = self.buf.read_u8()?;
+
+    let element_value = match tag {
+      b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' | b's' => {
+        ElementValue::Const {
+          tag,
+          const_value_index: self.buf.read_u16()?,
+        }
+      }
+      b'e' => {
+        let type_name_index = self.buf.read_u16()?;
+        let const_name_index = self.buf.read_u16()?;
+        ElementValue::Enum {
+          type_name: self.utf8(constant_pool, type_name_index)?,
+          const_name: self.utf8(constant_pool, const_name_index)?,
+        }
+      }
+      b'c' => {
+        let class_info_index = self.buf.read_u16()?;
+        ElementValue::Class(self.utf8(constant_pool, class_info_index)?)
+      }
+      b'@' => ElementValue::Annotation(self.read_annotation(constant_pool)?),
+      b'[' => {
+        let num_values = self.buf.read_u16()?;
+
+        let mut values = Vec::with_capacity(num_values as usize);
+        for _ in 0..num_values {
+          values.push(self.read_element_value(constant_pool)?);
+        }
+
+        ElementValue::Array(values)
+      }
+      _ => return Err(self.malformed()),
+    };
+
+    Ok(element_value)
+  }
+
+  fn read_type_annotations(
+    &mut self,
+    constant_pool: &ConstantPool,
+  ) -> Result<Vec<attribute_info::TypeAnnotation>, ClassFormatError> {
+    use attribute_info::TargetInfo;
+
+    let num_annotations = self.buf.read_u16()?;
+
+    let mut annotations = Vec::with_capacity(num_annotations as usize);
+    for _ in 0..num_annotations {
+      let target_type = self.buf.read_u8()?;
+
+      let target_info = match target_type {
+        0x00 | 0x01 => TargetInfo::TypeParameter {
+          type_parameter_index: self.buf.read_u8()?,
+        },
+        0x10 => TargetInfo::Supertype {
+          supertype_index: self.buf.read_u16()?,
+        },
+        0x11 | 0x12 => TargetInfo::TypeParameterBound {
+          type_parameter_index: self.buf.read_u8()?,
+          bound_index: self.buf.read_u8()?,
+        },
+        0x13..=0x15 => TargetInfo::Empty,
+        0x16 => TargetInfo::FormalParameter {
+          formal_parameter_index: self.buf.read_u8()?,
+        },
+        0x17 => TargetInfo::Throws {
+          throws_type_index: self.buf.read_u16()?,
+        },
+        0x40 | 0x41 => {
+          let table_length = self.buf.read_u16()?;
+
+          let mut table = Vec::with_capacity(table_length as usize);
+          for _ in 0..table_length {
+            let start_pc = self.buf.read_u16()?;
+            let length = self.buf.read_u16()?;
+            let index = self.buf.read_u16()?;
+            table.push((start_pc, length, index));
+          }
+
+          TargetInfo::LocalVar(table)
+        }
+        0x42 => TargetInfo::Catch {
+          exception_table_index: self.buf.read_u16()?,
+        },
+        0x43..=0x46 => TargetInfo::Offset {
+          offset: self.buf.read_u16()?,
+        },
+        0x47..=0x4B => TargetInfo::TypeArgument {
+          offset: self.buf.read_u16()?,
+          type_argument_index: self.buf.read_u8()?,
+        },
+        _ => return Err(self.malformed()),
+      };
+
+      let path_length = self.buf.read_u8()?;
+      let mut target_path = Vec::with_capacity(path_length as usize);
+      for _ in 0..path_length {
+        let type_path_kind = self.buf.read_u8()?;
+        let type_argument_index = self.buf.read_u8()?;
+        target_path.push((type_path_kind, type_argument_index));
+      }
+
+      annotations.push(attribute_info::TypeAnnotation {
+        target_type,
+        target_info,
+        target_path,
+        annotation: self.read_annotation(constant_pool)?,
+      });
+    }
+
+    Ok(annotations)
+  }
+
+  /// Reads the entry at `index` with `read`, unless `index` is 0.
+  fn optional<T>(
+    &self,
+    index: u16,
+    read: impl FnOnce(u16) -> Result<T, ClassFormatError>,
+  ) -> Result<Option<T>, ClassFormatError> {
+    match index {
+      0 => Ok(None),
+      index => read(index).map(Some),
+    }
+  }
+
+  /// An error for contents that make no sense. [Reader::read_attribute]
+  /// replaces it with the name of the attribute being read.
+  fn malformed(&self) -> ClassFormatError {
+    ClassFormatError::Io {
+      offset: self.buf.offset,
+      error: std::io::ErrorKind::InvalidData.into(),
+    }
+  }
+
+  /// The string stored at `index`, which must be a `CONSTANT_Utf8`.
+  fn utf8(
+    &self,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<String, ClassFormatError> {
+    constant_pool
+      .utf8(index)
+      .map(String::from)
+      .map_err(|error| self.bad_pool_index(error))
+  }
+
+  /// The name of the class stored at `index`, which must be a
+  /// `CONSTANT_Class`.
+  fn class_name(
+    &self,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<String, ClassFormatError> {
+    constant_pool
+      .class_name(index)
+      .map(String::from)
+      .map_err(|error| self.bad_pool_index(error))
+  }
+
+  fn bad_pool_index(&self, error: PoolError) -> ClassFormatError {
+    ClassFormatError::BadPoolIndex {
+      offset: self.buf.offset,
+      index: error.index,
+      expected: error.expected,
+    }
+  }
+
+  fn bad_descriptor(&self, descriptor: String) -> ClassFormatError {
+    ClassFormatError::BadDescriptor {
+      offset: self.buf.offset,
+      descriptor,
     }
   }
 }
 
+/// A reader that keeps track of how many bytes were consumed.
+struct Counted<R> {
+  inner: R,
+  offset: usize,
+}
+
+impl<R: Read> Counted<R> {
+  fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), ClassFormatError> {
+    self
+      .inner
+      .read_exact(buf)
+      .map_err(|error| ClassFormatError::Io {
+        offset: self.offset,
+        error,
+      })?;
+    self.offset += buf.len();
+    Ok(())
+  }
+}
+
 trait ClassReaderUtils {
-  fn read_u8(&mut self) -> std::io::Result<u8>;
+  fn read_u8(&mut self) -> Result<u8, ClassFormatError>;
+
+  fn read_u16(&mut self) -> Result<u16, ClassFormatError>;
 
-  fn read_u16(&mut self) -> std::io::Result<u16>;
+  fn read_u32(&mut self) -> Result<u32, ClassFormatError>;
 
-  fn read_u32(&mut self) -> std::io::Result<u32>;
+  fn read_4_u16(&mut self) -> Result<(u16, u16, u16, u16), ClassFormatError>;
 
-  fn read_4_u16(&mut self) -> std::io::Result<(u16, u16, u16, u16)>;
+  fn read_bytes(&mut self, length: usize) -> Result<Vec<u8>, ClassFormatError>;
 }
 
-impl<R> ClassReaderUtils for R
+impl<R> ClassReaderUtils for Counted<R>
 where
   R: Read,
 {
-  fn read_u8(&mut self) -> std::io::Result<u8> {
+  fn read_u8(&mut self) -> Result<u8, ClassFormatError> {
     let mut buf = [0u8; 1];
     self.read_exact(&mut buf)?;
     Ok(u8::from_be_bytes(buf))
   }
 
-  fn read_u16(&mut self) -> std::io::Result<u16> {
+  fn read_u16(&mut self) -> Result<u16, ClassFormatError> {
     let mut buf = [0u8; 2];
     self.read_exact(&mut buf)?;
     Ok(u16::from_be_bytes(buf))
   }
 
-  fn read_u32(&mut self) -> std::io::Result<u32> {
+  fn read_u32(&mut self) -> Result<u32, ClassFormatError> {
     let mut buf = [0u8; 4];
     self.read_exact(&mut buf)?;
     Ok(u32::from_be_bytes(buf))
   }
 
-  fn read_4_u16(&mut self) -> std::io::Result<(u16, u16, u16, u16)> {
+  fn read_4_u16(&mut self) -> Result<(u16, u16, u16, u16), ClassFormatError> {
     let mut buf = [0u8; 8];
     self.read_exact(&mut buf)?;
     let [a, b, c, d, e, f, g, h] = buf;
@@ -509,4 +1303,115 @@ where
       u16::from_be_bytes([g, h]),
     ))
   }
+
+  fn read_bytes(&mut self, length: usize) -> Result<Vec<u8>, ClassFormatError> {
+    // Lengths come from the file, so the buffer only grows as the bytes
+    // actually arrive rather than being allocated up front.
+    let mut buf = Vec::new();
+    let read = (&mut self.inner)
+      .take(length as u64)
+      .read_to_end(&mut buf)
+      .map_err(|error| ClassFormatError::Io {
+        offset: self.offset,
+        error,
+      })?;
+    if read < length {
+      return Err(ClassFormatError::Io {
+        offset: self.offset,
+        error: std::io::ErrorKind::UnexpectedEof.into(),
+      });
+    }
+    self.offset += read;
+    Ok(buf)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::ErrorKind;
+
+  use super::{pool::Entry, ClassFormatError, Reader};
+
+  const LOOPS: &[u8] = include_bytes!("../../tests/conformance/Loops.class");
+  const CATEGORY2: &[u8] =
+    include_bytes!("../../tests/conformance/Category2.class");
+
+  fn read(bytes: &[u8]) -> ClassFormatError {
+    Reader::new(bytes)
+      .read_class()
+      .expect_err("the class is malformed")
+  }
+
+  /// The offset and kind of an I/O error found while reading `Loops`.
+  fn io_error_in_loops(error: ClassFormatError) -> (usize, ErrorKind) {
+    let ClassFormatError::InClass { class_name, error } = error else {
+      panic!("{error:?} does not name the class");
+    };
+    assert_eq!(class_name, "Loops");
+    let ClassFormatError::Io { offset, error } = *error else {
+      panic!("{error:?} is not an I/O error");
+    };
+    (offset, error.kind())
+  }
+
+  #[test]
+  fn reads_valid_class() {
+    let class = Reader::new(LOOPS).read_class().expect("the class is valid");
+    assert_eq!(class.this_class, "Loops");
+    assert_eq!(class.source_file(), Some("Loops.java"));
+
+    let main = class
+      .method_index("main", "([Ljava/lang/String;)I")
+      .unwrap();
+    let main = &class.methods[main];
+    assert_eq!(main.method_descriptor.to_string(), main.descriptor);
+    assert_eq!((main.argument_count(), main.argument_slots()), (1, 1));
+  }
+
+  #[test]
+  fn rejects_unusable_pool_entries() {
+    let class = Reader::new(CATEGORY2)
+      .read_class()
+      .expect("the class is valid");
+    let pool = &class.constant_pool;
+    assert!(pool.get(0).is_err());
+
+    let long = (1..u16::MAX)
+      .find(|&index| matches!(pool.get(index), Ok(Entry::LongInfo { .. })))
+      .expect("Category2 has a long constant");
+    assert!(pool.long(long).is_ok());
+    assert!(pool.get(long + 1).is_err());
+    assert!(pool.integer(long + 1).is_err());
+  }
+
+  #[test]
+  fn truncated_before_class_name() {
+    let ClassFormatError::Io { offset, error } = read(&LOOPS[..20]) else {
+      panic!("a truncated constant pool is an I/O error");
+    };
+    assert!(offset <= 20);
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+  }
+
+  #[test]
+  fn truncated_attribute() {
+    // The file ends with the SourceFile attribute: a u16 name index, a u32
+    // length of 2 and a u16 index of the file name.
+    let end = LOOPS.len() - 1;
+    assert_eq!(
+      io_error_in_loops(read(&LOOPS[..end])),
+      (LOOPS.len() - 2, ErrorKind::UnexpectedEof)
+    );
+  }
+
+  #[test]
+  fn oversized_attribute_length() {
+    let mut bytes = LOOPS.to_vec();
+    let length = bytes.len() - 6;
+    bytes[length..length + 4].copy_from_slice(&0xFFFF_FFF0u32.to_be_bytes());
+    assert_eq!(
+      io_error_in_loops(read(&bytes)),
+      (LOOPS.len() - 2, ErrorKind::UnexpectedEof)
+    );
+  }
 }
@@ -1,25 +1,2635 @@
+use self::java::{io, lang};
+use super::registry::Registry;
+
+/// Every native the runtime implements.
+pub fn registry() -> Registry {
+  let mut registry = Registry::default();
+  array::register(&mut registry);
+  io::print_stream::register(&mut registry);
+  lang::object::register(&mut registry);
+  lang::class::register(&mut registry);
+  lang::boxed::register(&mut registry);
+  lang::string::register(&mut registry);
+  lang::string_builder::register(&mut registry);
+  lang::system::register(&mut registry);
+  lang::math::register(&mut registry);
+  lang::throwable::register(&mut registry);
+  lang::stack_trace_element::register(&mut registry);
+  registry
+}
+
+/// The methods arrays have beyond those of `java.lang.Object`.
+pub mod array {
+  use crate::{class::registry::Registry, run::RuntimeContext};
+
+  /// The class of every array, whatever its type.
+  pub const CLASS: &str = "[";
+
+  pub fn register(registry: &mut Registry) {
+    registry.register_instance(CLASS, "clone", "()Ljava/lang/Object;", clone);
+  }
+
+  /// A shallow copy of the array.
+  fn clone(rt: &mut RuntimeContext, (this,): (usize,)) -> usize {
+    let elements = rt.heap().array(this).clone();
+    rt.heap().alloc_array(elements)
+  }
+}
+
 pub mod java {
+  pub mod io {
+    pub mod print_stream {
+      use crate::{
+        class::{
+          descriptor::{BaseType, FieldType},
+          native::java::{lang::number, util::formatter},
+          registry::Registry,
+        },
+        heap::Array,
+        run::{RuntimeContext, RuntimeError},
+        value::MistValue,
+      };
+
+      const CLASS: &str = "java/io/PrintStream";
+
+      /// Offset of the file descriptor the stream writes to.
+      pub const FD: usize = 0;
+
+      pub const STDOUT: i32 = 1;
+      pub const STDERR: i32 = 2;
+
+      pub fn register(registry: &mut Registry) {
+        for (name, end) in [("print", ""), ("println", "\n")] {
+          for base_type in [
+            BaseType::Boolean,
+            BaseType::Char,
+            BaseType::Int,
+            BaseType::Long,
+            BaseType::Float,
+            BaseType::Double,
+          ] {
+            registry.register_instance(
+              CLASS,
+              name,
+              &format!("({})V", FieldType::Base(base_type)),
+              move |rt: &mut RuntimeContext,
+                    (this, value): (usize, MistValue)| {
+                let text = number::to_string(value, base_type);
+                write(rt, this, &(text + end));
+              },
+            );
+          }
+          registry.register_instance(
+            CLASS,
+            name,
+            "([C)V",
+            move |rt: &mut RuntimeContext, (this, chars): (usize, usize)| {
+              print_chars(rt, this, chars, end)
+            },
+          );
+          for descriptor in ["(Ljava/lang/String;)V", "(Ljava/lang/Object;)V"] {
+            registry.register_instance(
+              CLASS,
+              name,
+              descriptor,
+              move |rt: &mut RuntimeContext, (this, object): (usize, usize)| {
+                let text = rt.string_of(object)?;
+                write(rt, this, &(text + end));
+                Ok(())
+              },
+            );
+          }
+        }
+        registry.register_instance(CLASS, "println", "()V", println);
+        for name in ["printf", "format"] {
+          registry.register_instance(
+            CLASS,
+            name,
+            "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/io/PrintStream;",
+            printf,
+          );
+        }
+        registry.register_instance(CLASS, "flush", "()V", flush);
+      }
+
+      fn write(rt: &mut RuntimeContext, this: usize, text: &str) {
+        let fd = rt.heap().get_field(this, FD).into();
+        rt.write(fd, text);
+      }
+
+      fn print_chars(
+        rt: &mut RuntimeContext,
+        this: usize,
+        chars: usize,
+        end: &str,
+      ) -> Result<(), RuntimeError> {
+        if chars == 0 {
+          return Err(RuntimeError::new("java/lang/NullPointerException", ""));
+        }
+        let Array::Char(units) = rt.heap().array(chars) else {
+          panic!("print(char[]) takes a char[].");
+        };
+        let text = char::decode_utf16(units.iter().copied())
+          .map(|chr| chr.unwrap_or(char::REPLACEMENT_CHARACTER))
+          .collect::<String>();
+        write(rt, this, &(text + end));
+        Ok(())
+      }
+
+      fn println(rt: &mut RuntimeContext, (this,): (usize,)) {
+        write(rt, this, "\n");
+      }
+
+      /// `printf` and `format`, which return the stream.
+      fn printf(
+        rt: &mut RuntimeContext,
+        (this, format, args): (usize, usize, usize),
+      ) -> Result<usize, RuntimeError> {
+        let text = formatter::format_arguments(rt, format, args)?;
+        write(rt, this, &text);
+        Ok(this)
+      }
+
+      fn flush(rt: &mut RuntimeContext, _: (usize,)) {
+        rt.flush();
+      }
+    }
+  }
+
   pub mod lang {
+    pub mod object {
+      use crate::{
+        class::registry::Registry,
+        run::{RuntimeContext, RuntimeError},
+      };
+
+      const CLASS: &str = "java/lang/Object";
+
+      pub fn register(registry: &mut Registry) {
+        registry.register_instance(CLASS, "<init>", "()V", |_: (usize,)| {});
+        registry.register_instance(CLASS, "hashCode", "()I", hash_code);
+        registry.register_instance(
+          CLASS,
+          "equals",
+          "(Ljava/lang/Object;)Z",
+          |(this, other): (usize, usize)| this == other,
+        );
+        registry.register_instance(
+          CLASS,
+          "toString",
+          "()Ljava/lang/String;",
+          to_string,
+        );
+        registry.register_instance(
+          CLASS,
+          "getClass",
+          "()Ljava/lang/Class;",
+          |rt: &mut RuntimeContext, (this,): (usize,)| {
+            let field_type = rt.type_of(this);
+            rt.mirror(field_type)
+          },
+        );
+      }
+
+      /// The identity hash code, which is the address of the object.
+      fn hash_code((this,): (usize,)) -> i32 {
+        this as i32
+      }
+
+      /// `getClass().getName() + "@" + Integer.toHexString(hashCode())`.
+      fn to_string(
+        rt: &mut RuntimeContext,
+        (this,): (usize,),
+      ) -> Result<usize, RuntimeError> {
+        let hash: i32 = rt
+          .call_virtual(this, "hashCode", "()I", &[])?
+          .map_or(0, i32::from);
+        let string = format!("{}@{hash:x}", rt.type_of(this).class_name());
+        Ok(rt.new_string(&string))
+      }
+    }
+    pub mod class {
+      use crate::{
+        class::{descriptor::FieldType, registry::Registry},
+        run::{RuntimeContext, RuntimeError},
+      };
+
+      pub const CLASS: &str = "java/lang/Class";
+
+      /// Offset of the name `getName` returns, such as `java.lang.String`
+      /// or `[I`.
+      pub const NAME: usize = 0;
+
+      pub fn register(registry: &mut Registry) {
+        registry.register_instance(
+          CLASS,
+          "getName",
+          "()Ljava/lang/String;",
+          |rt: &mut RuntimeContext, (this,): (usize,)| {
+            rt.heap().get_field(this, NAME)
+          },
+        );
+        registry.register_instance(CLASS, "isArray", "()Z", is_array);
+        registry.register_instance(CLASS, "isInterface", "()Z", is_interface);
+        registry.register_instance(
+          CLASS,
+          "toString",
+          "()Ljava/lang/String;",
+          to_string,
+        );
+      }
+
+      /// The type the mirror `this` stands for.
+      fn field_type(rt: &mut RuntimeContext, this: usize) -> FieldType {
+        let name = rt.heap().get_field(this, NAME);
+        let name = rt.string(name.into()).replace('.', "/");
+        if name.starts_with('[') {
+          FieldType::parse(&name).expect("Mirrors name valid array types.")
+        } else {
+          FieldType::Object(name)
+        }
+      }
+
+      fn is_array(rt: &mut RuntimeContext, (this,): (usize,)) -> bool {
+        matches!(field_type(rt, this), FieldType::Array(_))
+      }
+
+      fn is_interface(
+        rt: &mut RuntimeContext,
+        (this,): (usize,),
+      ) -> Result<bool, RuntimeError> {
+        let FieldType::Object(class_name) = field_type(rt, this) else {
+          return Ok(false);
+        };
+        Ok(rt.lookup_class(&class_name)?.is_interface())
+      }
+
+      /// "class " or "interface " before the name.
+      fn to_string(
+        rt: &mut RuntimeContext,
+        (this,): (usize,),
+      ) -> Result<usize, RuntimeError> {
+        let kind = if is_interface(rt, (this,))? {
+          "interface"
+        } else {
+          "class"
+        };
+        let name = rt.heap().get_field(this, NAME);
+        let string = format!("{kind} {}", rt.string(name.into()));
+        Ok(rt.new_string(&string))
+      }
+    }
+
+    /// The decimal forms `String.valueOf` gives primitive values.
+    pub mod number {
+      use std::{fmt::LowerExp, str::FromStr};
+
+      use crate::{class::descriptor::BaseType, value::MistValue};
+
+      /// `value`, of type `base_type` in the form it takes on the operand
+      /// stack.
+      pub fn to_string(value: MistValue, base_type: BaseType) -> String {
+        match base_type {
+          BaseType::Boolean => (i32::from(value) != 0).to_string(),
+          BaseType::Char => char_string(i32::from(value) as u16),
+          BaseType::Byte | BaseType::Short | BaseType::Int => {
+            i32::from(value).to_string()
+          }
+          BaseType::Long => i64::from(value).to_string(),
+          BaseType::Float => float_string(value.into()),
+          BaseType::Double => double_string(value.into()),
+        }
+      }
+
+      /// The code unit `unit`, which is replaced if it is half a surrogate
+      /// pair.
+      pub fn char_string(unit: u16) -> String {
+        char::decode_utf16([unit])
+          .map(|chr| chr.unwrap_or(char::REPLACEMENT_CHARACTER))
+          .collect()
+      }
+
+      /// `Float.toString`.
+      pub fn float_string(value: f32) -> String {
+        if value.is_nan() {
+          return String::from("NaN");
+        }
+        let sign = if value.is_sign_negative() { "-" } else { "" };
+        if value.is_infinite() {
+          return format!("{sign}Infinity");
+        }
+        let (digits, exponent) = digits(value.abs());
+        format!("{sign}{}", decimal(&digits, exponent))
+      }
+
+      /// `Double.toString`.
+      pub fn double_string(value: f64) -> String {
+        if value.is_nan() {
+          return String::from("NaN");
+        }
+        let sign = if value.is_sign_negative() { "-" } else { "" };
+        if value.is_infinite() {
+          return format!("{sign}Infinity");
+        }
+        let (digits, exponent) = digits(value.abs());
+        format!("{sign}{}", decimal(&digits, exponent))
+      }
+
+      /// The decimal digits Java picks for a finite, positive float or
+      /// double: of the fewest that read back as the value, but at least two,
+      /// the closest to it, with ties going to the even digit. Yields them
+      /// with the power of ten the first of them is worth.
+      pub fn digits<T>(value: T) -> (String, i32)
+      where
+        T: LowerExp + FromStr + PartialEq,
+      {
+        let shortest = format!("{value:e}");
+        let closest = format!("{value:.*e}", precision(&shortest));
+        if closest.parse().ok() == Some(value) {
+          split(&closest)
+        } else {
+          split(&shortest)
+        }
+      }
+
+      /// The number of digits after the point Java keeps for the shortest
+      /// scientific notation of a number.
+      fn precision(shortest: &str) -> usize {
+        let mantissa = shortest.split('e').next().unwrap_or_default();
+        mantissa.len().saturating_sub(2).max(1)
+      }
+
+      /// Splits a number in scientific notation into its significant digits
+      /// and exponent.
+      fn split(scientific: &str) -> (String, i32) {
+        let (mantissa, exponent) = scientific
+          .split_once('e')
+          .expect("Scientific notation has an exponent.");
+        let exponent = exponent.parse().expect("The exponent is a number.");
+        let digits = mantissa.replace('.', "");
+        let significant = digits.trim_end_matches('0');
+        if significant.is_empty() {
+          (String::from("0"), exponent)
+        } else {
+          (significant.to_string(), exponent)
+        }
+      }
+
+      /// Lays out `digits`, the first of which is worth 10^`exponent`, as
+      /// Java does: plainly from 10^-3 up to 10^7 and as `d.dddE±n` outside
+      /// that, always with a digit after the point.
+      fn decimal(digits: &str, exponent: i32) -> String {
+        if !(-3..7).contains(&exponent) {
+          let (first, rest) = digits.split_at(1);
+          let rest = if rest.is_empty() { "0" } else { rest };
+          return format!("{first}.{rest}E{exponent}");
+        }
+        if exponent < 0 {
+          let zeros = "0".repeat((-exponent - 1) as usize);
+          return format!("0.{zeros}{digits}");
+        }
+
+        let point = exponent as usize + 1;
+        let digits = format!("{digits:0<point$}");
+        let (integer, fraction) = digits.split_at(point);
+        let fraction = if fraction.is_empty() { "0" } else { fraction };
+        format!("{integer}.{fraction}")
+      }
+    }
+
+    /// The wrapper classes of the primitive types, such as
+    /// java.lang.Integer.
+    pub mod boxed {
+      use super::number;
+      use crate::{
+        class::{
+          descriptor::{BaseType, FieldType},
+          registry::Registry,
+        },
+        run::{RuntimeContext, RuntimeError},
+        value::MistValue,
+      };
+
+      /// Offset of the wrapped value.
+      pub const VALUE: usize = 0;
+
+      /// Each wrapper class with the primitive type it wraps.
+      pub const CLASSES: [(&str, BaseType); 8] = [
+        ("java/lang/Boolean", BaseType::Boolean),
+        ("java/lang/Character", BaseType::Char),
+        ("java/lang/Byte", BaseType::Byte),
+        ("java/lang/Short", BaseType::Short),
+        ("java/lang/Integer", BaseType::Int),
+        ("java/lang/Long", BaseType::Long),
+        ("java/lang/Float", BaseType::Float),
+        ("java/lang/Double", BaseType::Double),
+      ];
+
+      /// The primitive types `Number` converts between.
+      const NUMERIC: [BaseType; 6] = [
+        BaseType::Byte,
+        BaseType::Short,
+        BaseType::Int,
+        BaseType::Long,
+        BaseType::Float,
+        BaseType::Double,
+      ];
+
+      pub fn register(registry: &mut Registry) {
+        for (name, to) in [
+          ("byteValue", BaseType::Byte),
+          ("shortValue", BaseType::Short),
+        ] {
+          registry.register_instance(
+            "java/lang/Number",
+            name,
+            &format!("(){}", FieldType::Base(to)),
+            move |rt: &mut RuntimeContext, (this,): (usize,)| {
+              narrow(rt, this, to)
+            },
+          );
+        }
+
+        for (class_name, base_type) in CLASSES {
+          let value = FieldType::Base(base_type);
+          registry.register_static(
+            class_name,
+            "valueOf",
+            &format!("({value})L{class_name};"),
+            move |rt: &mut RuntimeContext, (value,): (MistValue,)| {
+              value_of(rt, class_name, base_type, value)
+            },
+          );
+          registry.register_instance(
+            class_name,
+            "toString",
+            "()Ljava/lang/String;",
+            move |rt: &mut RuntimeContext, (this,): (usize,)| {
+              let value = rt.heap().get_field(this, VALUE);
+              let string = number::to_string(value, base_type);
+              rt.new_string(&string)
+            },
+          );
+          registry.register_instance(
+            class_name,
+            "hashCode",
+            "()I",
+            move |rt: &mut RuntimeContext, (this,): (usize,)| {
+              hash_code(rt, this, base_type)
+            },
+          );
+          registry.register_instance(
+            class_name,
+            "equals",
+            "(Ljava/lang/Object;)Z",
+            move |rt: &mut RuntimeContext, (this, other): (usize, usize)| {
+              equals(rt, this, other, base_type)
+            },
+          );
+
+          if base_type == BaseType::Boolean {
+            registry.register_static(
+              class_name,
+              "<clinit>",
+              "()V",
+              move |rt: &mut RuntimeContext, (): ()| {
+                for (field_name, value) in [("TRUE", 1), ("FALSE", 0)] {
+                  let value = MistValue::Integer(value);
+                  let object = value_of(rt, class_name, base_type, value);
+                  let object = MistValue::ObjectReference(object);
+                  rt.set_static(class_name, field_name, object);
+                }
+              },
+            );
+          }
+
+          if NUMERIC.contains(&base_type) {
+            for to in NUMERIC {
+              registry.register_instance(
+                class_name,
+                &format!("{}Value", to.java_name()),
+                &format!("(){}", FieldType::Base(to)),
+                move |rt: &mut RuntimeContext, (this,): (usize,)| {
+                  convert(rt, this, to)
+                },
+              );
+            }
+          } else {
+            registry.register_instance(
+              class_name,
+              &format!("{}Value", base_type.java_name()),
+              &format!("(){value}"),
+              |rt: &mut RuntimeContext, (this,): (usize,)| {
+                rt.heap().get_field(this, VALUE)
+              },
+            );
+          }
+        }
+      }
+
+      /// A wrapper of `value`, which is the same object every time for
+      /// booleans, bytes, chars up to 127, and shorts, ints and longs from
+      /// -128 to 127, as JLS §5.1.7 requires.
+      fn value_of(
+        rt: &mut RuntimeContext,
+        class_name: &str,
+        base_type: BaseType,
+        value: MistValue,
+      ) -> usize {
+        let key = match base_type {
+          BaseType::Boolean | BaseType::Byte => Some(i32::from(value).into()),
+          BaseType::Char => {
+            Some(i32::from(value).into()).filter(|&key| key <= 127)
+          }
+          BaseType::Short | BaseType::Int => Some(i32::from(value).into())
+            .filter(|key| (-128..=127).contains(key)),
+          BaseType::Long => {
+            Some(i64::from(value)).filter(|key| (-128..=127).contains(key))
+          }
+          BaseType::Float | BaseType::Double => None,
+        };
+        if let Some(&object) =
+          key.and_then(|key| rt.boxes().get(&(base_type, key)))
+        {
+          return object;
+        }
+
+        let object = rt.new_object(class_name);
+        rt.heap().put_field(object, VALUE, value);
+        if let Some(key) = key {
+          rt.boxes().insert((base_type, key), object);
+        }
+        object
+      }
+
+      /// The primitive type and value `objectref` wraps, if it is an
+      /// instance of a wrapper class.
+      pub fn unwrap(
+        rt: &mut RuntimeContext,
+        objectref: usize,
+      ) -> Option<(BaseType, MistValue)> {
+        let (_, base_type) = CLASSES
+          .into_iter()
+          .find(|&(class_name, ..)| rt.is_instance(objectref, class_name))?;
+        Some((base_type, rt.heap().get_field(objectref, VALUE)))
+      }
+
+      /// `byteValue`, `intValue` and the like of a `java.lang.Number`, which
+      /// convert as the primitive casts do.
+      fn convert(
+        rt: &mut RuntimeContext,
+        this: usize,
+        to: BaseType,
+      ) -> MistValue {
+        let (int, long, float, double) = match rt.heap().get_field(this, VALUE)
+        {
+          MistValue::Long(value) => {
+            (value as i32, value, value as f32, value as f64)
+          }
+          MistValue::Float(value) => {
+            (value as i32, value as i64, value, value.into())
+          }
+          MistValue::Double(value) => {
+            (value as i32, value as i64, value as f32, value)
+          }
+          value => {
+            let value = i32::from(value);
+            (value, value.into(), value as f32, value.into())
+          }
+        };
+        match to {
+          BaseType::Byte => MistValue::Integer(int as i8 as i32),
+          BaseType::Short => MistValue::Integer(int as i16 as i32),
+          BaseType::Long => MistValue::Long(long),
+          BaseType::Float => MistValue::Float(float),
+          BaseType::Double => MistValue::Double(double),
+          _ => MistValue::Integer(int),
+        }
+      }
+
+      /// `Number.byteValue` and `shortValue`, which narrow `intValue`.
+      fn narrow(
+        rt: &mut RuntimeContext,
+        this: usize,
+        to: BaseType,
+      ) -> Result<MistValue, RuntimeError> {
+        let int: i32 = rt
+          .call_virtual(this, "intValue", "()I", &[])?
+          .map_or(0, i32::from);
+        let value = match to {
+          BaseType::Byte => int as i8 as i32,
+          _ => int as i16 as i32,
+        };
+        Ok(MistValue::Integer(value))
+      }
+
+      fn hash_code(
+        rt: &mut RuntimeContext,
+        this: usize,
+        base_type: BaseType,
+      ) -> i32 {
+        let value = rt.heap().get_field(this, VALUE);
+        let bits = bits(value, base_type);
+        match base_type {
+          BaseType::Boolean if bits != 0 => 1231,
+          BaseType::Boolean => 1237,
+          BaseType::Long | BaseType::Double => (bits ^ bits >> 32) as i32,
+          _ => bits as i32,
+        }
+      }
+
+      /// Whether `other` wraps the same type and value, where floats and
+      /// doubles compare by their bits.
+      fn equals(
+        rt: &mut RuntimeContext,
+        this: usize,
+        other: usize,
+        base_type: BaseType,
+      ) -> bool {
+        let value = rt.heap().get_field(this, VALUE);
+        other != 0
+          && unwrap(rt, other).is_some_and(|(other_type, other)| {
+            other_type == base_type
+              && bits(other, base_type) == bits(value, base_type)
+          })
+      }
+
+      /// The value as the bits `hashCode` and `equals` look at, with every
+      /// NaN the same.
+      fn bits(value: MistValue, base_type: BaseType) -> u64 {
+        match base_type {
+          BaseType::Long => i64::from(value) as u64,
+          BaseType::Float => {
+            let value: f32 = value.into();
+            let value = if value.is_nan() { f32::NAN } else { value };
+            u64::from(value.to_bits())
+          }
+          BaseType::Double => {
+            let value: f64 = value.into();
+            let value = if value.is_nan() { f64::NAN } else { value };
+            value.to_bits()
+          }
+          _ => i32::from(value) as u32 as u64,
+        }
+      }
+    }
+
+    pub mod string {
+      use crate::{
+        class::{
+          descriptor::{BaseType, FieldType},
+          native::java::{lang::number, util::formatter},
+          registry::Registry,
+        },
+        heap::Array,
+        run::{RuntimeContext, RuntimeError},
+        value::MistValue,
+      };
+
+      const CLASS: &str = "java/lang/String";
+
+      /// Offsets of the fields of java.lang.String, in the order the context
+      /// declares them.
+      pub const VALUE: usize = 0;
+      pub const CODER: usize = 1;
+
+      /// Values of `coder`: `value` holds one byte per character, or two.
+      pub const LATIN1: i32 = 0;
+      pub const UTF16: i32 = 1;
+
+      pub fn register(registry: &mut Registry) {
+        registry.register_instance(CLASS, "length", "()I", length);
+        registry.register_instance(CLASS, "isEmpty", "()Z", is_empty);
+        registry.register_instance(CLASS, "charAt", "(I)C", char_at);
+        registry.register_instance(
+          CLASS,
+          "equals",
+          "(Ljava/lang/Object;)Z",
+          equals,
+        );
+        registry.register_instance(CLASS, "hashCode", "()I", hash_code);
+        registry.register_instance(
+          CLASS,
+          "substring",
+          "(I)Ljava/lang/String;",
+          substring,
+        );
+        registry.register_instance(
+          CLASS,
+          "substring",
+          "(II)Ljava/lang/String;",
+          substring_end,
+        );
+        registry.register_instance(CLASS, "indexOf", "(I)I", index_of_char);
+        registry.register_instance(
+          CLASS,
+          "indexOf",
+          "(II)I",
+          index_of_char_from,
+        );
+        registry.register_instance(
+          CLASS,
+          "indexOf",
+          "(Ljava/lang/String;)I",
+          index_of_string,
+        );
+        registry.register_instance(
+          CLASS,
+          "indexOf",
+          "(Ljava/lang/String;I)I",
+          index_of_string_from,
+        );
+        registry.register_instance(
+          CLASS,
+          "concat",
+          "(Ljava/lang/String;)Ljava/lang/String;",
+          concat,
+        );
+        registry.register_instance(
+          CLASS,
+          "compareTo",
+          "(Ljava/lang/String;)I",
+          compare_to,
+        );
+        registry.register_instance(
+          CLASS,
+          "compareTo",
+          "(Ljava/lang/Object;)I",
+          compare_to_object,
+        );
+        registry.register_instance(
+          CLASS,
+          "toString",
+          "()Ljava/lang/String;",
+          |(this,): (usize,)| this,
+        );
+        registry.register_instance(
+          CLASS,
+          "intern",
+          "()Ljava/lang/String;",
+          |rt: &mut RuntimeContext, (this,): (usize,)| rt.intern(this),
+        );
+        registry.register_static(
+          CLASS,
+          "format",
+          "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/String;",
+          format,
+        );
+        registry.register_static(
+          CLASS,
+          "valueOf",
+          "(Ljava/lang/Object;)Ljava/lang/String;",
+          value_of,
+        );
+        registry.register_static(
+          CLASS,
+          "valueOf",
+          "([C)Ljava/lang/String;",
+          value_of_chars,
+        );
+        registry.register_static(
+          CLASS,
+          "valueOf",
+          "(C)Ljava/lang/String;",
+          |rt: &mut RuntimeContext, (unit,): (u16,)| {
+            rt.new_string_utf16(&[unit])
+          },
+        );
+        for base_type in [
+          BaseType::Boolean,
+          BaseType::Int,
+          BaseType::Long,
+          BaseType::Float,
+          BaseType::Double,
+        ] {
+          registry.register_static(
+            CLASS,
+            "valueOf",
+            &format!("({})Ljava/lang/String;", FieldType::Base(base_type)),
+            move |rt: &mut RuntimeContext, (value,): (MistValue,)| {
+              rt.new_string(&number::to_string(value, base_type))
+            },
+          );
+        }
+      }
+
+      /// The code units of a string, read in place from its `value`.
+      #[derive(Clone, Copy)]
+      pub struct Units<'a> {
+        bytes: &'a [i8],
+        coder: i32,
+      }
+
+      impl<'a> Units<'a> {
+        /// The units `bytes` holds one or two bytes at a time, as `coder`
+        /// says.
+        pub fn new(bytes: &'a [i8], coder: i32) -> Self {
+          Self { bytes, coder }
+        }
+
+        pub fn len(&self) -> usize {
+          match self.coder {
+            LATIN1 => self.bytes.len(),
+            _ => self.bytes.len() / 2,
+          }
+        }
+
+        pub fn is_empty(&self) -> bool {
+          self.bytes.is_empty()
+        }
+
+        /// The unit at `index`, if it is in bounds.
+        pub fn get(&self, index: usize) -> Option<u16> {
+          (index < self.len()).then(|| self.unit(index))
+        }
+
+        fn unit(&self, index: usize) -> u16 {
+          match self.coder {
+            LATIN1 => u16::from(self.bytes[index] as u8),
+            _ => u16::from_ne_bytes([
+              self.bytes[2 * index] as u8,
+              self.bytes[2 * index + 1] as u8,
+            ]),
+          }
+        }
+
+        pub fn iter(self) -> impl Iterator<Item = u16> + 'a {
+          (0..self.len()).map(move |index| self.unit(index))
+        }
+
+        /// Where `target` first occurs at or after `from`.
+        fn index_of(&self, target: Units, from: i32) -> i32 {
+          let from = from.max(0) as usize;
+          if from >= self.len() {
+            return if target.is_empty() {
+              self.len() as i32
+            } else {
+              -1
+            };
+          }
+          let Some(last) = self.len().checked_sub(target.len()) else {
+            return -1;
+          };
+          (from..=last)
+            .find(|&at| {
+              (0..target.len()).all(|i| self.unit(at + i) == target.unit(i))
+            })
+            .map_or(-1, |at| at as i32)
+        }
+      }
+
+      /// The code units of the string argument, or a `NullPointerException`
+      /// with `message` if it is null.
+      fn argument<'a>(
+        rt: &'a RuntimeContext,
+        string: usize,
+        message: &str,
+      ) -> Result<Units<'a>, RuntimeError> {
+        match string {
+          0 => {
+            Err(RuntimeError::new("java/lang/NullPointerException", message))
+          }
+          string => Ok(rt.string_units(string)),
+        }
+      }
+
+      /// What `indexOf(String)` says when its argument is null.
+      const STR_IS_NULL: &str =
+        "Cannot invoke \"String.coder()\" because \"str\" is null";
+
+      fn string_index_out_of_bounds(message: String) -> RuntimeError {
+        RuntimeError::new("java/lang/StringIndexOutOfBoundsException", message)
+      }
+
+      fn length(rt: &mut RuntimeContext, (this,): (usize,)) -> i32 {
+        rt.string_units(this).len() as i32
+      }
+
+      fn is_empty(rt: &mut RuntimeContext, (this,): (usize,)) -> bool {
+        rt.string_units(this).is_empty()
+      }
+
+      fn char_at(
+        rt: &mut RuntimeContext,
+        (this, index): (usize, i32),
+      ) -> Result<u16, RuntimeError> {
+        let units = rt.string_units(this);
+        match usize::try_from(index)
+          .ok()
+          .and_then(|index| units.get(index))
+        {
+          Some(unit) => Ok(unit),
+          None => Err(string_index_out_of_bounds(format!(
+            "String index out of range: {index}"
+          ))),
+        }
+      }
+
+      /// Strings are stored as Latin-1 whenever they can be, so equal strings
+      /// have equal coders and bytes.
+      fn equals(
+        rt: &mut RuntimeContext,
+        (this, other): (usize, usize),
+      ) -> bool {
+        if other == 0 || !rt.is_instance(other, CLASS) {
+          return false;
+        }
+        let (this, other) = (rt.string_units(this), rt.string_units(other));
+        this.coder == other.coder && this.bytes == other.bytes
+      }
+
+      /// `s[0]*31^(n-1) + s[1]*31^(n-2) + ... + s[n-1]`, wrapping.
+      fn hash_code(rt: &mut RuntimeContext, (this,): (usize,)) -> i32 {
+        rt.string_units(this).iter().fold(0i32, |hash, unit| {
+          hash.wrapping_mul(31).wrapping_add(unit.into())
+        })
+      }
+
+      fn substring(
+        rt: &mut RuntimeContext,
+        (this, begin): (usize, i32),
+      ) -> Result<usize, RuntimeError> {
+        let length = rt.string_units(this).len() as i32;
+        substring_end(rt, (this, begin, length))
+      }
+
+      fn substring_end(
+        rt: &mut RuntimeContext,
+        (this, begin, end): (usize, i32, i32),
+      ) -> Result<usize, RuntimeError> {
+        let units = rt.string_units(this);
+        let length = units.len() as i32;
+        if begin < 0 || begin > end || end > length {
+          return Err(string_index_out_of_bounds(format!(
+            "begin {begin}, end {end}, length {length}"
+          )));
+        }
+
+        if begin == 0 && end == length {
+          return Ok(this);
+        }
+        let range = begin as usize..end as usize;
+        let units = range.map(|index| units.unit(index)).collect::<Vec<_>>();
+        Ok(rt.new_string_utf16(&units))
+      }
+
+      fn index_of_char(
+        rt: &mut RuntimeContext,
+        (this, chr): (usize, i32),
+      ) -> i32 {
+        index_of_char_from(rt, (this, chr, 0))
+      }
+
+      fn index_of_char_from(
+        rt: &mut RuntimeContext,
+        (this, chr, from): (usize, i32, i32),
+      ) -> i32 {
+        let Some(chr) = char::from_u32(chr as u32) else {
+          return -1;
+        };
+        let bytes = chr
+          .encode_utf16(&mut [0; 2])
+          .iter()
+          .flat_map(|unit| unit.to_ne_bytes())
+          .map(|byte| byte as i8)
+          .collect::<Vec<_>>();
+        let target = Units::new(&bytes, UTF16);
+        rt.string_units(this).index_of(target, from)
+      }
+
+      fn index_of_string(
+        rt: &mut RuntimeContext,
+        (this, string): (usize, usize),
+      ) -> Result<i32, RuntimeError> {
+        index_of_string_from(rt, (this, string, 0))
+      }
+
+      fn index_of_string_from(
+        rt: &mut RuntimeContext,
+        (this, string, from): (usize, usize, i32),
+      ) -> Result<i32, RuntimeError> {
+        let target = argument(rt, string, STR_IS_NULL)?;
+        Ok(rt.string_units(this).index_of(target, from))
+      }
+
+      fn concat(
+        rt: &mut RuntimeContext,
+        (this, string): (usize, usize),
+      ) -> Result<usize, RuntimeError> {
+        let other = argument(
+          rt,
+          string,
+          "Cannot invoke \"String.isEmpty()\" because \"str\" is null",
+        )?;
+
+        if other.is_empty() {
+          return Ok(this);
+        }
+        let units = rt
+          .string_units(this)
+          .iter()
+          .chain(other.iter())
+          .collect::<Vec<_>>();
+        Ok(rt.new_string_utf16(&units))
+      }
+
+      /// `String.valueOf(Object)`: "null", or what `toString` returns.
+      fn value_of(
+        rt: &mut RuntimeContext,
+        (object,): (usize,),
+      ) -> Result<usize, RuntimeError> {
+        if object == 0 {
+          return Ok(rt.new_string("null"));
+        }
+        let string =
+          rt.call_virtual(object, "toString", "()Ljava/lang/String;", &[])?;
+        Ok(string.map_or(0, usize::from))
+      }
+
+      fn value_of_chars(
+        rt: &mut RuntimeContext,
+        (chars,): (usize,),
+      ) -> Result<usize, RuntimeError> {
+        if chars == 0 {
+          return Err(RuntimeError::new(
+            "java/lang/NullPointerException",
+            "Cannot read the array length because \"value\" is null",
+          ));
+        }
+        let Array::Char(units) = rt.heap().array(chars) else {
+          panic!("valueOf(char[]) takes a char[].");
+        };
+        let units = units.clone();
+        Ok(rt.new_string_utf16(&units))
+      }
+
+      /// Compares the code units in order, then the lengths.
+      fn compare_to(
+        rt: &mut RuntimeContext,
+        (this, other): (usize, usize),
+      ) -> Result<i32, RuntimeError> {
+        let other = argument(
+          rt,
+          other,
+          "Cannot read field \"value\" because \"anotherString\" is null",
+        )?;
+        let this = rt.string_units(this);
+
+        let difference =
+          match this.iter().zip(other.iter()).find(|(a, b)| a != b) {
+            Some((a, b)) => i32::from(a) - i32::from(b),
+            None => this.len() as i32 - other.len() as i32,
+          };
+        Ok(difference)
+      }
+
+      /// The `Comparable.compareTo(Object)` bridge, which casts its argument.
+      fn compare_to_object(
+        rt: &mut RuntimeContext,
+        (this, other): (usize, usize),
+      ) -> Result<i32, RuntimeError> {
+        if other != 0 && !rt.is_instance(other, CLASS) {
+          return Err(RuntimeError::new(
+            "java/lang/ClassCastException",
+            format!(
+              "class {} cannot be cast to class java.lang.String",
+              rt.type_of(other).class_name()
+            ),
+          ));
+        }
+        compare_to(rt, (this, other))
+      }
+
+      /// The static `String.format(String, Object...)`.
+      fn format(
+        rt: &mut RuntimeContext,
+        (format, args): (usize, usize),
+      ) -> Result<usize, RuntimeError> {
+        let string = formatter::format_arguments(rt, format, args)?;
+        Ok(rt.new_string(&string))
+      }
+    }
+
+    pub mod string_builder {
+      use crate::{
+        class::{
+          descriptor::{BaseType, FieldType},
+          native::java::lang::number,
+          registry::Registry,
+        },
+        heap::Array,
+        run::{RuntimeContext, RuntimeError},
+        value::MistValue,
+      };
+
+      const CLASS: &str = "java/lang/StringBuilder";
+      const DESCRIPTOR: &str = "Ljava/lang/StringBuilder;";
+
+      /// Offsets of the fields of java.lang.StringBuilder, in the order the
+      /// context declares them. `value` has room for more than the `count`
+      /// characters in use.
+      pub const VALUE: usize = 0;
+      pub const COUNT: usize = 1;
+
+      /// The capacity of a builder created without contents.
+      const INITIAL_CAPACITY: i32 = 16;
+
+      pub fn register(registry: &mut Registry) {
+        registry.register_instance(CLASS, "<init>", "()V", init);
+        registry.register_instance(CLASS, "<init>", "(I)V", init_capacity);
+        registry.register_instance(
+          CLASS,
+          "<init>",
+          "(Ljava/lang/String;)V",
+          init_string,
+        );
+        registry.register_instance(
+          CLASS,
+          "append",
+          &format!("(Ljava/lang/String;){DESCRIPTOR}"),
+          append_string,
+        );
+        registry.register_instance(
+          CLASS,
+          "append",
+          &format!("(Ljava/lang/Object;){DESCRIPTOR}"),
+          append_object,
+        );
+        registry.register_instance(
+          CLASS,
+          "append",
+          &format!("([C){DESCRIPTOR}"),
+          append_chars,
+        );
+        registry.register_instance(
+          CLASS,
+          "append",
+          &format!("(C){DESCRIPTOR}"),
+          |rt: &mut RuntimeContext, (this, unit): (usize, u16)| {
+            append(rt, this, &[unit])
+          },
+        );
+        for base_type in [
+          BaseType::Boolean,
+          BaseType::Int,
+          BaseType::Long,
+          BaseType::Float,
+          BaseType::Double,
+        ] {
+          registry.register_instance(
+            CLASS,
+            "append",
+            &format!("({}){DESCRIPTOR}", FieldType::Base(base_type)),
+            move |rt: &mut RuntimeContext,
+                  (this, value): (usize, MistValue)| {
+              let string = number::to_string(value, base_type);
+              append(rt, this, &string.encode_utf16().collect::<Vec<_>>())
+            },
+          );
+        }
+        registry.register_instance(CLASS, "length", "()I", length);
+        registry.register_instance(CLASS, "charAt", "(I)C", char_at);
+        registry.register_instance(
+          CLASS,
+          "reverse",
+          &format!("(){DESCRIPTOR}"),
+          reverse,
+        );
+        registry.register_instance(
+          CLASS,
+          "toString",
+          "()Ljava/lang/String;",
+          to_string,
+        );
+      }
+
+      fn init(rt: &mut RuntimeContext, (this,): (usize,)) {
+        init_units(rt, this, INITIAL_CAPACITY as usize, &[]);
+      }
+
+      fn init_capacity(
+        rt: &mut RuntimeContext,
+        (this, capacity): (usize, i32),
+      ) -> Result<(), RuntimeError> {
+        let Ok(capacity) = usize::try_from(capacity) else {
+          return Err(RuntimeError::new(
+            "java/lang/NegativeArraySizeException",
+            capacity.to_string(),
+          ));
+        };
+        init_units(rt, this, capacity, &[]);
+        Ok(())
+      }
+
+      fn init_string(
+        rt: &mut RuntimeContext,
+        (this, string): (usize, usize),
+      ) -> Result<(), RuntimeError> {
+        if string == 0 {
+          return Err(RuntimeError::new(
+            "java/lang/NullPointerException",
+            "Cannot invoke \"String.length()\" because \"str\" is null",
+          ));
+        }
+        let units = rt.string_utf16(string);
+        let capacity = units.len() + INITIAL_CAPACITY as usize;
+        init_units(rt, this, capacity, &units);
+        Ok(())
+      }
+
+      /// Gives the builder `this` room for `capacity` characters and the
+      /// contents `units`.
+      fn init_units(
+        rt: &mut RuntimeContext,
+        this: usize,
+        capacity: usize,
+        units: &[u16],
+      ) {
+        let mut value = units.to_vec();
+        value.resize(capacity.max(units.len()), 0);
+        let value = rt.heap().alloc_array(Array::Char(value));
+        let heap = rt.heap();
+        heap.put_field(this, VALUE, MistValue::ObjectReference(value));
+        heap.put_field(this, COUNT, MistValue::Integer(units.len() as i32));
+      }
+
+      /// The characters in use, and the array they are at the start of.
+      fn contents(rt: &mut RuntimeContext, this: usize) -> (usize, usize) {
+        let heap = rt.heap();
+        let value = heap.get_field(this, VALUE).into();
+        let count = i32::from(heap.get_field(this, COUNT)) as usize;
+        (value, count)
+      }
+
+      /// Adds `units` to the end of the builder, doubling its capacity when
+      /// they do not fit, and returns the builder.
+      fn append(rt: &mut RuntimeContext, this: usize, units: &[u16]) -> usize {
+        let (mut value, count) = contents(rt, this);
+        let end = count + units.len();
+        let capacity = rt.heap().array(value).len();
+        if end > capacity {
+          let Array::Char(old) = rt.heap().array(value) else {
+            panic!("StringBuilder.value is a char[].");
+          };
+          let mut grown = old[..count].to_vec();
+          grown.resize(end.max(capacity * 2 + 2), 0);
+          value = rt.heap().alloc_array(Array::Char(grown));
+          rt.heap()
+            .put_field(this, VALUE, MistValue::ObjectReference(value));
+        }
+
+        let Array::Char(chars) = rt.heap().array_mut(value) else {
+          panic!("StringBuilder.value is a char[].");
+        };
+        chars[count..end].copy_from_slice(units);
+        rt.heap()
+          .put_field(this, COUNT, MistValue::Integer(end as i32));
+        this
+      }
+
+      fn append_string(
+        rt: &mut RuntimeContext,
+        (this, string): (usize, usize),
+      ) -> usize {
+        let units = match string {
+          0 => "null".encode_utf16().collect(),
+          string => rt.string_utf16(string),
+        };
+        append(rt, this, &units)
+      }
+
+      fn append_object(
+        rt: &mut RuntimeContext,
+        (this, object): (usize, usize),
+      ) -> Result<usize, RuntimeError> {
+        let string = rt.string_of(object)?;
+        Ok(append(rt, this, &string.encode_utf16().collect::<Vec<_>>()))
+      }
+
+      fn append_chars(
+        rt: &mut RuntimeContext,
+        (this, chars): (usize, usize),
+      ) -> Result<usize, RuntimeError> {
+        if chars == 0 {
+          return Err(RuntimeError::new(
+            "java/lang/NullPointerException",
+            "Cannot read the array length because \"str\" is null",
+          ));
+        }
+        let Array::Char(units) = rt.heap().array(chars) else {
+          panic!("append(char[]) takes a char[].");
+        };
+        let units = units.clone();
+        Ok(append(rt, this, &units))
+      }
+
+      fn length(rt: &mut RuntimeContext, (this,): (usize,)) -> i32 {
+        contents(rt, this).1 as i32
+      }
+
+      fn char_at(
+        rt: &mut RuntimeContext,
+        (this, index): (usize, i32),
+      ) -> Result<u16, RuntimeError> {
+        let (value, count) = contents(rt, this);
+        match usize::try_from(index) {
+          Ok(offset) if offset < count => {
+            Ok(i32::from(rt.heap().array(value).load(offset)) as u16)
+          }
+          _ => Err(RuntimeError::new(
+            "java/lang/StringIndexOutOfBoundsException",
+            format!("index {index}, length {count}"),
+          )),
+        }
+      }
+
+      /// Reverses the characters, keeping each surrogate pair in order.
+      fn reverse(rt: &mut RuntimeContext, (this,): (usize,)) -> usize {
+        let (value, count) = contents(rt, this);
+        let Array::Char(chars) = rt.heap().array_mut(value) else {
+          panic!("StringBuilder.value is a char[].");
+        };
+        let chars = &mut chars[..count];
+        chars.reverse();
+        let mut index = 1;
+        while index < count {
+          let (low, high) = (chars[index - 1], chars[index]);
+          if (0xdc00..0xe000).contains(&low) && (0xd800..0xdc00).contains(&high)
+          {
+            chars.swap(index - 1, index);
+            index += 1;
+          }
+          index += 1;
+        }
+        this
+      }
+
+      fn to_string(rt: &mut RuntimeContext, (this,): (usize,)) -> usize {
+        let (value, count) = contents(rt, this);
+        let Array::Char(chars) = rt.heap().array(value) else {
+          panic!("StringBuilder.value is a char[].");
+        };
+        let units = chars[..count].to_vec();
+        rt.new_string_utf16(&units)
+      }
+    }
+
     pub mod system {
-      use crate::{local::Local, value::MistValue};
+      use crate::{
+        class::{native::java::io::print_stream, registry::Registry},
+        run::RuntimeContext,
+        value::MistValue,
+      };
       use std::time::SystemTime;
 
-      pub fn current_time_millis(_: &Local) -> Option<MistValue> {
-        SystemTime::now()
-          .duration_since(SystemTime::UNIX_EPOCH)
-          .map(|duration| MistValue::Long(duration.as_millis() as i64))
-          .ok()
+      const CLASS: &str = "java/lang/System";
+
+      pub fn register(registry: &mut Registry) {
+        registry.register_static(CLASS, "<clinit>", "()V", clinit);
+        registry.register_static(
+          CLASS,
+          "currentTimeMillis",
+          "()J",
+          |(): ()| {
+            SystemTime::now()
+              .duration_since(SystemTime::UNIX_EPOCH)
+              .map_or(0, |duration| duration.as_millis() as i64)
+          },
+        );
+      }
+
+      /// Creates `System.out` and `System.err`.
+      fn clinit(rt: &mut RuntimeContext, (): ()) {
+        let streams =
+          [("out", print_stream::STDOUT), ("err", print_stream::STDERR)];
+        for (field_name, fd) in streams {
+          let stream = rt.new_object("java/io/PrintStream");
+          rt.heap()
+            .put_field(stream, print_stream::FD, MistValue::Integer(fd));
+          rt.set_static(CLASS, field_name, MistValue::ObjectReference(stream));
+        }
       }
     }
 
+    /// java.lang.Math.
+    ///
+    /// java.lang.StrictMath is left out: it must give fdlibm's results bit
+    /// for bit, which the host's libm does not promise.
     pub mod math {
-      use crate::{local::Local, value::MistValue};
+      use crate::{
+        class::registry::Registry,
+        run::{RuntimeContext, RuntimeError},
+      };
+
+      pub const CLASS: &str = "java/lang/Math";
+
+      type Unary = fn(f64) -> f64;
+
+      /// The methods that apply a function of one `double`.
+      const UNARY: [(&str, Unary); 21] = [
+        ("sqrt", f64::sqrt),
+        ("cbrt", f64::cbrt),
+        ("exp", f64::exp),
+        ("expm1", f64::exp_m1),
+        ("log", f64::ln),
+        ("log10", f64::log10),
+        ("log1p", f64::ln_1p),
+        ("sin", f64::sin),
+        ("cos", f64::cos),
+        ("tan", f64::tan),
+        ("asin", f64::asin),
+        ("acos", f64::acos),
+        ("atan", f64::atan),
+        ("sinh", f64::sinh),
+        ("cosh", f64::cosh),
+        ("tanh", f64::tanh),
+        ("floor", f64::floor),
+        ("ceil", f64::ceil),
+        ("rint", f64::round_ties_even),
+        ("toRadians", f64::to_radians),
+        ("toDegrees", f64::to_degrees),
+      ];
+
+      pub fn register(registry: &mut Registry) {
+        for (name, f) in UNARY {
+          registry
+            .register_static(CLASS, name, "(D)D", move |(a,): (f64,)| f(a));
+        }
+        registry.register_static(
+          CLASS,
+          "atan2",
+          "(DD)D",
+          |(y, x): (f64, f64)| y.atan2(x),
+        );
+        registry.register_static(
+          CLASS,
+          "hypot",
+          "(DD)D",
+          |(x, y): (f64, f64)| x.hypot(y),
+        );
+        registry.register_static(CLASS, "pow", "(DD)D", pow);
+
+        registry.register_static(CLASS, "abs", "(I)I", |(a,): (i32,)| {
+          a.wrapping_abs()
+        });
+        registry.register_static(CLASS, "abs", "(J)J", |(a,): (i64,)| {
+          a.wrapping_abs()
+        });
+        registry.register_static(CLASS, "abs", "(F)F", |(a,): (f32,)| a.abs());
+        registry.register_static(CLASS, "abs", "(D)D", |(a,): (f64,)| a.abs());
+
+        registry.register_static(
+          CLASS,
+          "min",
+          "(II)I",
+          |(a, b): (i32, i32)| a.min(b),
+        );
+        registry.register_static(
+          CLASS,
+          "min",
+          "(JJ)J",
+          |(a, b): (i64, i64)| a.min(b),
+        );
+        registry.register_static(
+          CLASS,
+          "min",
+          "(FF)F",
+          |(a, b): (f32, f32)| min(a.into(), b.into()) as f32,
+        );
+        registry.register_static(
+          CLASS,
+          "min",
+          "(DD)D",
+          |(a, b): (f64, f64)| min(a, b),
+        );
+        registry.register_static(
+          CLASS,
+          "max",
+          "(II)I",
+          |(a, b): (i32, i32)| a.max(b),
+        );
+        registry.register_static(
+          CLASS,
+          "max",
+          "(JJ)J",
+          |(a, b): (i64, i64)| a.max(b),
+        );
+        registry.register_static(
+          CLASS,
+          "max",
+          "(FF)F",
+          |(a, b): (f32, f32)| max(a.into(), b.into()) as f32,
+        );
+        registry.register_static(
+          CLASS,
+          "max",
+          "(DD)D",
+          |(a, b): (f64, f64)| max(a, b),
+        );
+
+        registry.register_static(CLASS, "signum", "(F)F", |(a,): (f32,)| {
+          signum(a.into()) as f32
+        });
+        registry
+          .register_static(CLASS, "signum", "(D)D", |(a,): (f64,)| signum(a));
+        registry.register_static(CLASS, "round", "(F)I", |(a,): (f32,)| {
+          round(a.into()) as i32
+        });
+        registry.register_static(CLASS, "round", "(D)J", |(a,): (f64,)| {
+          round(a) as i64
+        });
+
+        registry.register_static(
+          CLASS,
+          "floorDiv",
+          "(II)I",
+          |(a, b): (i32, i32)| floor_div(a.into(), b.into()).map(|q| q as i32),
+        );
+        registry.register_static(
+          CLASS,
+          "floorDiv",
+          "(JI)J",
+          |(a, b): (i64, i32)| floor_div(a, b.into()),
+        );
+        registry.register_static(
+          CLASS,
+          "floorDiv",
+          "(JJ)J",
+          |(a, b): (i64, i64)| floor_div(a, b),
+        );
+        registry.register_static(
+          CLASS,
+          "floorMod",
+          "(II)I",
+          |(a, b): (i32, i32)| floor_mod(a.into(), b.into()).map(|m| m as i32),
+        );
+        registry.register_static(
+          CLASS,
+          "floorMod",
+          "(JI)I",
+          |(a, b): (i64, i32)| floor_mod(a, b.into()).map(|m| m as i32),
+        );
+        registry.register_static(
+          CLASS,
+          "floorMod",
+          "(JJ)J",
+          |(a, b): (i64, i64)| floor_mod(a, b),
+        );
+
+        registry.register_static(
+          CLASS,
+          "addExact",
+          "(II)I",
+          |(a, b): (i32, i32)| exact(a.checked_add(b)),
+        );
+        registry.register_static(
+          CLASS,
+          "addExact",
+          "(JJ)J",
+          |(a, b): (i64, i64)| exact(a.checked_add(b)),
+        );
+        registry.register_static(
+          CLASS,
+          "subtractExact",
+          "(II)I",
+          |(a, b): (i32, i32)| exact(a.checked_sub(b)),
+        );
+        registry.register_static(
+          CLASS,
+          "subtractExact",
+          "(JJ)J",
+          |(a, b): (i64, i64)| exact(a.checked_sub(b)),
+        );
+        registry.register_static(
+          CLASS,
+          "multiplyExact",
+          "(II)I",
+          |(a, b): (i32, i32)| exact(a.checked_mul(b)),
+        );
+        registry.register_static(
+          CLASS,
+          "multiplyExact",
+          "(JI)J",
+          |(a, b): (i64, i32)| exact(a.checked_mul(b.into())),
+        );
+        registry.register_static(
+          CLASS,
+          "multiplyExact",
+          "(JJ)J",
+          |(a, b): (i64, i64)| exact(a.checked_mul(b)),
+        );
+        registry.register_static(
+          CLASS,
+          "negateExact",
+          "(I)I",
+          |(a,): (i32,)| exact(a.checked_neg()),
+        );
+        registry.register_static(
+          CLASS,
+          "negateExact",
+          "(J)J",
+          |(a,): (i64,)| exact(a.checked_neg()),
+        );
+        registry.register_static(CLASS, "toIntExact", "(J)I", |(a,): (i64,)| {
+          i32::try_from(a).map_err(|_| overflow("integer"))
+        });
+
+        registry.register_static(
+          CLASS,
+          "random",
+          "()D",
+          |rt: &mut RuntimeContext, (): ()| rt.random().next_double(),
+        );
+      }
+
+      /// Java's `pow`, which differs from IEEE 754 in giving NaN for a NaN
+      /// exponent even when the base is 1, and for a base of ±1 raised to an
+      /// infinite power.
+      fn pow((a, b): (f64, f64)) -> f64 {
+        if b.is_nan() || a.abs() == 1.0 && b.is_infinite() {
+          f64::NAN
+        } else {
+          a.powf(b)
+        }
+      }
+
+      /// The lesser of `a` and `b`, NaN if either is, and -0.0 rather than
+      /// 0.0.
+      fn min(a: f64, b: f64) -> f64 {
+        if a.is_nan() || b.is_nan() {
+          f64::NAN
+        } else if a == b {
+          if a.is_sign_negative() {
+            a
+          } else {
+            b
+          }
+        } else {
+          a.min(b)
+        }
+      }
+
+      /// The greater of `a` and `b`, NaN if either is, and 0.0 rather than
+      /// -0.0.
+      fn max(a: f64, b: f64) -> f64 {
+        if a.is_nan() || b.is_nan() {
+          f64::NAN
+        } else if a == b {
+          if a.is_sign_positive() {
+            a
+          } else {
+            b
+          }
+        } else {
+          a.max(b)
+        }
+      }
+
+      /// -1.0, 0.0 or 1.0, keeping the sign of a zero and passing NaN on.
+      fn signum(a: f64) -> f64 {
+        if a == 0.0 || a.is_nan() {
+          a
+        } else {
+          a.signum()
+        }
+      }
+
+      /// The closest integer, with ties rounding up. NaN rounds to 0, and
+      /// values beyond the range of the result type saturate when cast.
+      fn round(a: f64) -> f64 {
+        if a.is_nan() {
+          return 0.0;
+        }
+        let floor = a.floor();
+        // Exact, as the fraction of a value with one is representable.
+        if a - floor >= 0.5 {
+          floor + 1.0
+        } else {
+          floor
+        }
+      }
+
+      /// The quotient rounded toward negative infinity.
+      fn floor_div(a: i64, b: i64) -> Result<i64, RuntimeError> {
+        if b == 0 {
+          return Err(divide_by_zero());
+        }
+        let quotient = a.wrapping_div(b);
+        if a.wrapping_rem(b) != 0 && (a ^ b) < 0 {
+          Ok(quotient - 1)
+        } else {
+          Ok(quotient)
+        }
+      }
+
+      /// The remainder with the sign of the divisor.
+      fn floor_mod(a: i64, b: i64) -> Result<i64, RuntimeError> {
+        if b == 0 {
+          return Err(divide_by_zero());
+        }
+        let remainder = a.wrapping_rem(b);
+        if remainder != 0 && (remainder ^ b) < 0 {
+          Ok(remainder + b)
+        } else {
+          Ok(remainder)
+        }
+      }
+
+      fn divide_by_zero() -> RuntimeError {
+        RuntimeError::new("java/lang/ArithmeticException", "/ by zero")
+      }
+
+      fn overflow(type_name: &str) -> RuntimeError {
+        RuntimeError::new(
+          "java/lang/ArithmeticException",
+          format!("{type_name} overflow"),
+        )
+      }
+
+      /// The result of a checked operation, or the `ArithmeticException` the
+      /// `*Exact` methods throw when it overflows.
+      fn exact<T: Exact>(result: Option<T>) -> Result<T, RuntimeError> {
+        result.ok_or_else(|| overflow(T::TYPE_NAME))
+      }
+
+      trait Exact {
+        /// How the exception message names the type.
+        const TYPE_NAME: &str;
+      }
+
+      impl Exact for i32 {
+        const TYPE_NAME: &str = "integer";
+      }
+
+      impl Exact for i64 {
+        const TYPE_NAME: &str = "long";
+      }
+    }
+
+    pub mod throwable {
+      use crate::{
+        class::{
+          descriptor::FieldType, native::java::io::print_stream,
+          registry::Registry,
+        },
+        heap::Array,
+        run::RuntimeContext,
+        value::MistValue,
+      };
+
+      const CLASS: &str = "java/lang/Throwable";
+
+      /// Offsets of the fields of java.lang.Throwable, in the order the
+      /// context declares them.
+      pub const DETAIL_MESSAGE: usize = 0;
+      pub const CAUSE: usize = 1;
+      pub const STACK_TRACE: usize = 2;
+
+      pub fn register(registry: &mut Registry) {
+        registry.register_instance(CLASS, "<init>", "()V", init);
+        registry.register_instance(
+          CLASS,
+          "<init>",
+          "(Ljava/lang/String;)V",
+          init_message,
+        );
+        registry.register_instance(
+          CLASS,
+          "<init>",
+          "(Ljava/lang/String;Ljava/lang/Throwable;)V",
+          init_message_cause,
+        );
+        registry.register_instance(
+          CLASS,
+          "<init>",
+          "(Ljava/lang/Throwable;)V",
+          init_cause,
+        );
+        for name in ["getMessage", "getLocalizedMessage"] {
+          registry.register_instance(
+            CLASS,
+            name,
+            "()Ljava/lang/String;",
+            get_message,
+          );
+        }
+        registry.register_instance(
+          CLASS,
+          "getCause",
+          "()Ljava/lang/Throwable;",
+          |rt: &mut RuntimeContext, (this,): (usize,)| {
+            rt.heap().get_field(this, CAUSE)
+          },
+        );
+        registry.register_instance(
+          CLASS,
+          "toString",
+          "()Ljava/lang/String;",
+          |rt: &mut RuntimeContext, (this,): (usize,)| {
+            let string = rt.throwable_string(this);
+            rt.new_string(&string)
+          },
+        );
+        registry.register_instance(
+          CLASS,
+          "fillInStackTrace",
+          "()Ljava/lang/Throwable;",
+          |rt: &mut RuntimeContext, (this,): (usize,)| {
+            rt.fill_in_stack_trace(this);
+            this
+          },
+        );
+        registry.register_instance(
+          CLASS,
+          "getStackTrace",
+          "()[Ljava/lang/StackTraceElement;",
+          get_stack_trace,
+        );
+        registry.register_instance(
+          CLASS,
+          "printStackTrace",
+          "()V",
+          print_stack_trace,
+        );
+      }
+
+      fn init(rt: &mut RuntimeContext, (this,): (usize,)) {
+        rt.fill_in_stack_trace(this);
+      }
+
+      fn init_message(
+        rt: &mut RuntimeContext,
+        (this, message): (usize, MistValue),
+      ) {
+        rt.heap().put_field(this, DETAIL_MESSAGE, message);
+        rt.fill_in_stack_trace(this);
+      }
+
+      fn init_message_cause(
+        rt: &mut RuntimeContext,
+        (this, message, cause): (usize, MistValue, MistValue),
+      ) {
+        rt.heap().put_field(this, DETAIL_MESSAGE, message);
+        rt.heap().put_field(this, CAUSE, cause);
+        rt.fill_in_stack_trace(this);
+      }
+
+      /// `Throwable(Throwable cause)`, whose message is `cause.toString()`.
+      fn init_cause(rt: &mut RuntimeContext, (this, cause): (usize, usize)) {
+        rt.heap()
+          .put_field(this, CAUSE, MistValue::ObjectReference(cause));
+
+        if cause != 0 {
+          let message = rt.throwable_string(cause);
+          let message = rt.new_string(&message);
+          rt.heap().put_field(
+            this,
+            DETAIL_MESSAGE,
+            MistValue::ObjectReference(message),
+          );
+        }
+        rt.fill_in_stack_trace(this);
+      }
+
+      fn get_message(rt: &mut RuntimeContext, (this,): (usize,)) -> MistValue {
+        rt.heap().get_field(this, DETAIL_MESSAGE)
+      }
 
-      pub fn sqrt(local: &Local) -> Option<MistValue> {
-        let MistValue::Double(double) = local.load(0) else {
-          panic!();
+      /// `getStackTrace()`, which returns a copy the caller may modify.
+      fn get_stack_trace(rt: &mut RuntimeContext, (this,): (usize,)) -> usize {
+        let elements = match rt.heap().get_field(this, STACK_TRACE) {
+          MistValue::ObjectReference(0) => Array::new(
+            FieldType::Object(String::from("java/lang/StackTraceElement")),
+            0,
+          ),
+          stack_trace => rt.heap().array(stack_trace.into()).clone(),
         };
-        Some(MistValue::Double(double.sqrt()))
+        rt.heap().alloc_array(elements)
+      }
+
+      fn print_stack_trace(rt: &mut RuntimeContext, (this,): (usize,)) {
+        let mut trace = rt.stack_trace(this);
+        trace.push('\n');
+        rt.write(print_stream::STDERR, &trace);
+      }
+    }
+
+    pub mod stack_trace_element {
+      use crate::{class::registry::Registry, run::RuntimeContext};
+
+      const CLASS: &str = "java/lang/StackTraceElement";
+
+      /// Offsets of the fields of java.lang.StackTraceElement, in the order
+      /// the context declares them.
+      pub const DECLARING_CLASS: usize = 0;
+      pub const METHOD_NAME: usize = 1;
+      pub const FILE_NAME: usize = 2;
+      pub const LINE_NUMBER: usize = 3;
+
+      pub fn register(registry: &mut Registry) {
+        let getters = [
+          ("getClassName", "()Ljava/lang/String;", DECLARING_CLASS),
+          ("getMethodName", "()Ljava/lang/String;", METHOD_NAME),
+          ("getFileName", "()Ljava/lang/String;", FILE_NAME),
+          ("getLineNumber", "()I", LINE_NUMBER),
+        ];
+        for (name, descriptor, field) in getters {
+          registry.register_instance(
+            CLASS,
+            name,
+            descriptor,
+            move |rt: &mut RuntimeContext, (this,): (usize,)| {
+              rt.heap().get_field(this, field)
+            },
+          );
+        }
+        registry.register_instance(
+          CLASS,
+          "toString",
+          "()Ljava/lang/String;",
+          |rt: &mut RuntimeContext, (this,): (usize,)| {
+            let string = rt.stack_trace_element_string(this);
+            rt.new_string(&string)
+          },
+        );
+      }
+    }
+
+    pub mod invoke {
+      /// `StringConcatFactory`, the bootstrap method of the string
+      /// concatenations javac 9 and later compile to `invokedynamic`.
+      pub mod string_concat_factory {
+        use crate::{
+          class::{
+            descriptor::{FieldType, MethodDescriptor},
+            native::java::lang::number,
+            pool::{ConstantPool, Entry},
+          },
+          run::{RuntimeContext, RuntimeError},
+          value::MistValue,
+        };
+
+        pub const CLASS: &str = "java/lang/invoke/StringConcatFactory";
+
+        /// Where a recipe takes the next argument, and the next constant.
+        const TAG_ARG: char = '\u{1}';
+        const TAG_CONST: char = '\u{2}';
+
+        /// What a concatenation call site puts together.
+        #[derive(Debug)]
+        pub struct Recipe {
+          /// The types of the arguments the call site pops.
+          pub params: Vec<FieldType>,
+          parts: Vec<Part>,
+        }
+
+        #[derive(Debug)]
+        enum Part {
+          Literal(String),
+          /// The next argument.
+          Argument,
+        }
+
+        /// Links a call site of type `descriptor` with the bootstrap method
+        /// `method_name`, given the `arguments` of the `BootstrapMethods`
+        /// entry.
+        pub fn bootstrap(
+          constant_pool: &ConstantPool,
+          method_name: &str,
+          descriptor: &str,
+          arguments: &[u16],
+        ) -> Result<Recipe, RuntimeError> {
+          let descriptor = MethodDescriptor::parse(descriptor)
+            .map_err(|error| bootstrap_error(error.to_string()))?;
+          let string = FieldType::Object(String::from("java/lang/String"));
+          if descriptor.ret.as_ref() != Some(&string) {
+            return Err(bootstrap_error(format!(
+              "Concatenation of type {descriptor} does not return a String"
+            )));
+          }
+
+          let (recipe, constants) = match (method_name, arguments) {
+            ("makeConcat", []) => {
+              (TAG_ARG.to_string().repeat(descriptor.params.len()), &[][..])
+            }
+            ("makeConcatWithConstants", [recipe, constants @ ..]) => {
+              (constant_pool.string(*recipe)?.to_string(), constants)
+            }
+            _ => {
+              return Err(bootstrap_error(format!(
+                "{CLASS}.{method_name} with {} arguments is not supported",
+                arguments.len()
+              )))
+            }
+          };
+
+          let mut constants = constants.iter();
+          let mut parts = Vec::new();
+          let mut literal = String::new();
+          for chr in recipe.chars() {
+            match chr {
+              TAG_ARG => {
+                if !literal.is_empty() {
+                  parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(Part::Argument);
+              }
+              TAG_CONST => {
+                let Some(&index) = constants.next() else {
+                  return Err(bootstrap_error(String::from(
+                    "Mismatched number of concat constants",
+                  )));
+                };
+                literal += &constant(constant_pool, index)?;
+              }
+              chr => literal.push(chr),
+            }
+          }
+          if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+          }
+
+          let arguments = parts
+            .iter()
+            .filter(|part| matches!(part, Part::Argument))
+            .count();
+          if arguments != descriptor.params.len() {
+            return Err(bootstrap_error(format!(
+              "Mismatched number of concat arguments: recipe wants \
+               {arguments} arguments, but signature provides {}",
+              descriptor.params.len()
+            )));
+          }
+
+          Ok(Recipe {
+            params: descriptor.params,
+            parts,
+          })
+        }
+
+        impl Recipe {
+          /// Concatenates `args`, which are of the types in
+          /// [Recipe::params].
+          pub fn concat(
+            &self,
+            rt: &mut RuntimeContext,
+            args: &[MistValue],
+          ) -> Result<String, RuntimeError> {
+            let mut args = args.iter().zip(&self.params);
+            let mut text = String::new();
+            for part in &self.parts {
+              match part {
+                Part::Literal(literal) => text += literal,
+                Part::Argument => {
+                  let (&arg, param) =
+                    args.next().expect("The recipe has an argument per param.");
+                  text += &match param {
+                    FieldType::Base(base_type) => {
+                      number::to_string(arg, *base_type)
+                    }
+                    FieldType::Object(_) | FieldType::Array(_) => {
+                      rt.string_of(arg.into())?
+                    }
+                  };
+                }
+              }
+            }
+            Ok(text)
+          }
+        }
+
+        /// A constant a recipe splices in, as the text it contributes.
+        fn constant(
+          constant_pool: &ConstantPool,
+          index: u16,
+        ) -> Result<String, RuntimeError> {
+          let text = match constant_pool.get(index)? {
+            Entry::StringInfo { .. } => {
+              constant_pool.string(index)?.to_string()
+            }
+            Entry::IntegerInfo { .. } => {
+              constant_pool.integer(index)?.to_string()
+            }
+            Entry::LongInfo { .. } => constant_pool.long(index)?.to_string(),
+            Entry::FloatInfo { .. } => {
+              number::float_string(constant_pool.float(index)?)
+            }
+            Entry::DoubleInfo { .. } => {
+              number::double_string(constant_pool.double(index)?)
+            }
+            _ => {
+              return Err(bootstrap_error(format!(
+                "Constant pool entry #{index} cannot be concatenated"
+              )))
+            }
+          };
+          Ok(text)
+        }
+
+        fn bootstrap_error(message: String) -> RuntimeError {
+          RuntimeError::new("java/lang/BootstrapMethodError", message)
+        }
+      }
+    }
+  }
+
+  pub mod util {
+    /// The format strings of `java.util.Formatter`, behind `String.format`
+    /// and `PrintStream.printf`.
+    pub mod formatter {
+      use crate::{
+        class::{
+          descriptor::BaseType,
+          native::java::lang::{boxed, number},
+        },
+        heap::Array,
+        run::{RuntimeContext, RuntimeError},
+        value::MistValue,
+      };
+
+      /// A format specifier, `%[index$][flags][width][.precision]conversion`.
+      #[derive(Default)]
+      struct Specifier {
+        left: bool,
+        alternate: bool,
+        plus: bool,
+        space: bool,
+        zero: bool,
+        group: bool,
+        parenthesis: bool,
+        width: Option<usize>,
+        precision: Option<usize>,
+      }
+
+      /// Formats the string `format` with the `Object[]` `args`.
+      pub fn format_arguments(
+        rt: &mut RuntimeContext,
+        format: usize,
+        args: usize,
+      ) -> Result<String, RuntimeError> {
+        if format == 0 {
+          return Err(RuntimeError::new("java/lang/NullPointerException", ""));
+        }
+        let format = rt.string(format);
+        let args = match args {
+          0 => None,
+          args => {
+            let Array::Reference { elements, .. } = rt.heap().array(args)
+            else {
+              panic!("The arguments are an Object[].");
+            };
+            Some(elements.clone())
+          }
+        };
+        self::format(rt, &format, args.as_deref())
+      }
+
+      /// `String.format(format, args)`, where no `args` at all gives every
+      /// specifier null.
+      pub fn format(
+        rt: &mut RuntimeContext,
+        format: &str,
+        args: Option<&[usize]>,
+      ) -> Result<String, RuntimeError> {
+        let mut out = String::new();
+        let mut rest = format;
+        let mut ordinary = 0;
+
+        while let Some(start) = rest.find('%') {
+          out.push_str(&rest[..start]);
+          let (specifier, index, conversion, len) = parse(&rest[start..])?;
+          let text = &rest[start..start + len];
+          rest = &rest[start + len..];
+
+          let arg = match conversion {
+            'n' | '%' => 0,
+            _ => {
+              let index = index.unwrap_or_else(|| {
+                ordinary += 1;
+                ordinary - 1
+              });
+              match args {
+                None => 0,
+                Some(args) => *args.get(index).ok_or_else(|| {
+                  RuntimeError::new(
+                    "java/util/MissingFormatArgumentException",
+                    format!("Format specifier '{text}'"),
+                  )
+                })?,
+              }
+            }
+          };
+          out += &convert(rt, &specifier, conversion, arg)?;
+        }
+        out.push_str(rest);
+        Ok(out)
+      }
+
+      /// Reads the specifier at the start of `text`, yielding it with its
+      /// explicit argument index, its conversion and its length.
+      fn parse(
+        text: &str,
+      ) -> Result<(Specifier, Option<usize>, char, usize), RuntimeError> {
+        let bytes = text.as_bytes();
+        let mut at = 1;
+        let digits = |at: usize| {
+          bytes[at..]
+            .iter()
+            .take_while(|chr| chr.is_ascii_digit())
+            .count()
+        };
+        let number = |from: usize, to: usize| text[from..to].parse().ok();
+
+        let mut index = None;
+        let len = digits(at);
+        if len > 0 && bytes.get(at + len) == Some(&b'$') {
+          let illegal_index = |message: String| {
+            RuntimeError::new(
+              "java/util/IllegalFormatArgumentIndexException",
+              message,
+            )
+          };
+          index = match text[at..at + len].parse::<i32>() {
+            Ok(0) => {
+              return Err(illegal_index(String::from(
+                "Illegal format argument index = 0",
+              )))
+            }
+            Ok(position) => Some(position as usize - 1),
+            Err(_) => {
+              return Err(illegal_index(String::from(
+                "Format argument index: (not representable as int)",
+              )))
+            }
+          };
+          at += len + 1;
+        }
+
+        let mut specifier = Specifier::default();
+        while let Some(flag) = bytes.get(at) {
+          let flag = match flag {
+            b'-' => &mut specifier.left,
+            b'#' => &mut specifier.alternate,
+            b'+' => &mut specifier.plus,
+            b' ' => &mut specifier.space,
+            b'0' => &mut specifier.zero,
+            b',' => &mut specifier.group,
+            b'(' => &mut specifier.parenthesis,
+            _ => break,
+          };
+          *flag = true;
+          at += 1;
+        }
+
+        let len = digits(at);
+        specifier.width = number(at, at + len);
+        at += len;
+        if bytes.get(at) == Some(&b'.') {
+          let len = digits(at + 1);
+          specifier.precision = number(at + 1, at + 1 + len);
+          at += len + 1;
+        }
+
+        match text[at..].chars().next() {
+          Some(conversion) if conversion.is_ascii_alphabetic() => {
+            Ok((specifier, index, conversion, at + 1))
+          }
+          Some('%') => Ok((specifier, index, '%', at + 1)),
+          // Java names the character after the '%' of a specifier it cannot
+          // make out, whatever went wrong further on.
+          _ => Err(RuntimeError::new(
+            "java/util/UnknownFormatConversionException",
+            format!(
+              "Conversion = '{}'",
+              text[1..].chars().next().unwrap_or('%')
+            ),
+          )),
+        }
+      }
+
+      /// Formats `arg` by one specifier.
+      fn convert(
+        rt: &mut RuntimeContext,
+        specifier: &Specifier,
+        conversion: char,
+        arg: usize,
+      ) -> Result<String, RuntimeError> {
+        let mismatch = |rt: &mut RuntimeContext| {
+          let class_name = rt.type_of(arg).class_name();
+          RuntimeError::new(
+            "java/util/IllegalFormatConversionException",
+            format!("{conversion} != {class_name}"),
+          )
+        };
+        let unwrapped = match arg {
+          0 => None,
+          arg => boxed::unwrap(rt, arg),
+        };
+
+        let text = match conversion.to_ascii_lowercase() {
+          'n' => return Ok(String::from("\n")),
+          '%' => String::from("%"),
+          'b' => match unwrapped {
+            _ if arg == 0 => String::from("false"),
+            Some((BaseType::Boolean, value)) => {
+              number::to_string(value, BaseType::Boolean)
+            }
+            _ => String::from("true"),
+          },
+          's' => rt.string_of(arg)?,
+          'h' if arg == 0 => String::from("null"),
+          'h' => {
+            let hash: i32 = rt
+              .call_virtual(arg, "hashCode", "()I", &[])?
+              .map_or(0, i32::from);
+            format!("{hash:x}")
+          }
+          _ if arg == 0 => String::from("null"),
+          'c' => match unwrapped {
+            Some((BaseType::Char, value)) => {
+              number::char_string(i32::from(value) as u16)
+            }
+            Some((BaseType::Byte | BaseType::Short | BaseType::Int, value)) => {
+              char::from_u32(i32::from(value) as u32)
+                .unwrap_or(char::REPLACEMENT_CHARACTER)
+                .to_string()
+            }
+            _ => return Err(mismatch(rt)),
+          },
+          'd' | 'o' | 'x' => match unwrapped {
+            Some((base_type, value)) if is_integral(base_type) => {
+              integer(specifier, conversion, base_type, value)
+            }
+            _ => return Err(mismatch(rt)),
+          },
+          'e' | 'f' => match unwrapped {
+            Some((BaseType::Float, value)) => {
+              let value: f32 = value.into();
+              floating(specifier, conversion, value.into())
+            }
+            Some((BaseType::Double, value)) => {
+              floating(specifier, conversion, value.into())
+            }
+            _ => return Err(mismatch(rt)),
+          },
+          _ => {
+            return Err(RuntimeError::new(
+              "java/util/UnknownFormatConversionException",
+              format!("Conversion = '{conversion}'"),
+            ))
+          }
+        };
+
+        let text = match specifier.precision {
+          Some(precision)
+            if "bsh".contains(conversion.to_ascii_lowercase()) =>
+          {
+            text.chars().take(precision).collect()
+          }
+          _ => text,
+        };
+        let text = if conversion.is_ascii_uppercase() {
+          text.to_uppercase()
+        } else {
+          text
+        };
+        Ok(pad(specifier, text))
+      }
+
+      fn is_integral(base_type: BaseType) -> bool {
+        matches!(
+          base_type,
+          BaseType::Byte | BaseType::Short | BaseType::Int | BaseType::Long
+        )
+      }
+
+      /// `%d`, `%o` and `%x`, where negative values are shown in octal and
+      /// hexadecimal as the two's complement of the width of their type.
+      fn integer(
+        specifier: &Specifier,
+        conversion: char,
+        base_type: BaseType,
+        value: MistValue,
+      ) -> String {
+        let value = match base_type {
+          BaseType::Long => i64::from(value),
+          _ => i32::from(value).into(),
+        };
+        let unsigned = match base_type {
+          BaseType::Byte => u64::from(value as u8),
+          BaseType::Short => u64::from(value as u16),
+          BaseType::Int => u64::from(value as u32),
+          _ => value as u64,
+        };
+
+        match conversion {
+          'd' => {
+            let digits = value.unsigned_abs().to_string();
+            let digits = if specifier.group {
+              group(&digits)
+            } else {
+              digits
+            };
+            signed(specifier, value < 0, digits)
+          }
+          'o' => {
+            let prefix = if specifier.alternate { "0" } else { "" };
+            zero_pad(specifier, prefix, format!("{unsigned:o}"))
+          }
+          _ => {
+            let prefix = if specifier.alternate { "0x" } else { "" };
+            zero_pad(specifier, prefix, format!("{unsigned:x}"))
+          }
+        }
+      }
+
+      /// `%e` and `%f`, rounded half up from the shortest decimal digits of
+      /// `value`, as Java does.
+      fn floating(
+        specifier: &Specifier,
+        conversion: char,
+        value: f64,
+      ) -> String {
+        if value.is_nan() {
+          return String::from("NaN");
+        }
+        let negative = value.is_sign_negative();
+        if value.is_infinite() {
+          return match (negative, specifier.parenthesis) {
+            (true, true) => String::from("(Infinity)"),
+            (true, false) => String::from("-Infinity"),
+            (false, _) if specifier.plus => String::from("+Infinity"),
+            (false, _) if specifier.space => String::from(" Infinity"),
+            (false, _) => String::from("Infinity"),
+          };
+        }
+
+        let precision = specifier.precision.unwrap_or(6);
+        let (digits, exponent) = number::digits(value.abs());
+
+        let text = if conversion.eq_ignore_ascii_case(&'e') {
+          let (mut digits, mut exponent) =
+            round(&digits, exponent, precision as i32 + 1);
+          if value == 0.0 {
+            exponent = 0;
+          }
+          digits.truncate(precision + 1);
+          let (first, rest) = digits.split_at(1);
+          let point = if rest.is_empty() && !specifier.alternate {
+            ""
+          } else {
+            "."
+          };
+          let sign = if exponent < 0 { '-' } else { '+' };
+          format!("{first}{point}{rest}e{sign}{:02}", exponent.abs())
+        } else {
+          let len = exponent + 1 + precision as i32;
+          let (digits, exponent) = round(&digits, exponent, len);
+          let mut positional = "0".repeat((-exponent).max(0) as usize);
+          positional += &digits;
+          let integer_len = (exponent + 1).max(1) as usize;
+          let width = integer_len + precision;
+          let positional = format!("{positional:0<width$}");
+          let (integer, fraction) =
+            positional[..integer_len + precision].split_at(integer_len);
+          let integer = if specifier.group {
+            group(integer)
+          } else {
+            integer.to_string()
+          };
+          if fraction.is_empty() && !specifier.alternate {
+            integer
+          } else {
+            format!("{integer}.{fraction}")
+          }
+        };
+        signed(specifier, negative, text)
+      }
+
+      /// Rounds the decimal `digits`, the first of which is worth
+      /// 10^`exponent`, half up to `len` digits. Yields the digits and the
+      /// worth of the first, which grows when rounding carries.
+      fn round(digits: &str, exponent: i32, len: i32) -> (String, i32) {
+        let Ok(len) = usize::try_from(len) else {
+          return (String::new(), exponent);
+        };
+        let round_up = digits.as_bytes().get(len).is_some_and(|&d| d >= b'5');
+        let mut kept =
+          format!("{:0<len$}", &digits[..len.min(digits.len())]).into_bytes();
+        if round_up {
+          match kept.iter().rposition(|&digit| digit != b'9') {
+            Some(at) => {
+              kept[at] += 1;
+              kept[at + 1..].fill(b'0');
+            }
+            None => {
+              kept.fill(b'0');
+              kept.insert(0, b'1');
+              let kept = String::from_utf8(kept).expect("Digits are ASCII.");
+              return (kept, exponent + 1);
+            }
+          }
+        }
+        (
+          String::from_utf8(kept).expect("Digits are ASCII."),
+          exponent,
+        )
+      }
+
+      /// Separates the thousands of a string of digits with commas.
+      fn group(digits: &str) -> String {
+        let mut grouped = String::new();
+        for (at, digit) in digits.chars().enumerate() {
+          if at > 0 && (digits.len() - at).is_multiple_of(3) {
+            grouped.push(',');
+          }
+          grouped.push(digit);
+        }
+        grouped
+      }
+
+      /// Puts the sign the flags call for before `digits`, and zeros between
+      /// them if the specifier is zero-padded.
+      fn signed(
+        specifier: &Specifier,
+        negative: bool,
+        digits: String,
+      ) -> String {
+        let (prefix, suffix) = match negative {
+          true if specifier.parenthesis => ("(", ")"),
+          true => ("-", ""),
+          false if specifier.plus => ("+", ""),
+          false if specifier.space => (" ", ""),
+          false => ("", ""),
+        };
+        zero_pad(specifier, prefix, digits + suffix)
+      }
+
+      fn zero_pad(
+        specifier: &Specifier,
+        prefix: &str,
+        digits: String,
+      ) -> String {
+        match specifier.width {
+          Some(width) if specifier.zero => {
+            let width = width.saturating_sub(prefix.len());
+            format!("{prefix}{digits:0>width$}")
+          }
+          _ => format!("{prefix}{digits}"),
+        }
+      }
+
+      /// Pads `text` with spaces to the width of the specifier.
+      fn pad(specifier: &Specifier, text: String) -> String {
+        let width = specifier.width.unwrap_or(0);
+        if specifier.left {
+          format!("{text:<width$}")
+        } else {
+          format!("{text:>width$}")
+        }
+      }
+    }
+
+    /// The linear congruential generator of `java.util.Random`, behind
+    /// `Math.random`.
+    pub mod random {
+      use std::time::SystemTime;
+
+      const MULTIPLIER: u64 = 0x5_DEEC_E66D;
+      const ADDEND: u64 = 0xB;
+      const MASK: u64 = (1 << 48) - 1;
+
+      pub struct Random {
+        seed: u64,
+      }
+
+      impl Random {
+        pub fn new(seed: i64) -> Self {
+          Self {
+            seed: (seed as u64 ^ MULTIPLIER) & MASK,
+          }
+        }
+
+        /// Seeded from the time, as `new Random()` is.
+        pub fn from_time() -> Self {
+          let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as i64);
+          Self::new(nanos)
+        }
+
+        /// The next `bits` pseudorandom bits.
+        fn next(&mut self, bits: u32) -> u64 {
+          self.seed =
+            (self.seed.wrapping_mul(MULTIPLIER).wrapping_add(ADDEND)) & MASK;
+          self.seed >> (48 - bits)
+        }
+
+        /// A value uniformly chosen from `[0.0, 1.0)`.
+        pub fn next_double(&mut self) -> f64 {
+          let bits = (self.next(26) << 27) + self.next(27);
+          bits as f64 * (1.0 / (1u64 << 53) as f64)
+        }
       }
     }
   }
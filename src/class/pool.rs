@@ -1,3 +1,7 @@
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+use super::{native::java::lang::invoke::string_concat_factory::Recipe, Class};
+
 pub const CLASS: u8 = 7;
 pub const FIELD_REF: u8 = 9;
 pub const METHOD_REF: u8 = 10;
@@ -11,7 +15,21 @@ pub const NAME_AND_TYPE: u8 = 12;
 pub const UTF_8: u8 = 1;
 pub const METHOD_HANDLE: u8 = 15;
 pub const METHOD_TYPE: u8 = 16;
+pub const DYNAMIC: u8 = 17;
 pub const INVOKE_DYNAMIC: u8 = 18;
+pub const MODULE: u8 = 19;
+pub const PACKAGE: u8 = 20;
+
+/// Kinds of [Entry::MethodHandleInfo].
+pub const REF_GET_FIELD: u8 = 1;
+pub const REF_GET_STATIC: u8 = 2;
+pub const REF_PUT_FIELD: u8 = 3;
+pub const REF_PUT_STATIC: u8 = 4;
+pub const REF_INVOKE_VIRTUAL: u8 = 5;
+pub const REF_INVOKE_STATIC: u8 = 6;
+pub const REF_INVOKE_SPECIAL: u8 = 7;
+pub const REF_NEW_INVOKE_SPECIAL: u8 = 8;
+pub const REF_INVOKE_INTERFACE: u8 = 9;
 
 #[derive(Clone, Debug)]
 pub enum Entry {
@@ -54,4 +72,325 @@ pub enum Entry {
     index: u16,
     descriptor_index: u16,
   },
+  MethodHandleInfo {
+    reference_kind: u8,
+    reference_index: u16,
+  },
+  MethodTypeInfo {
+    descriptor_index: u16,
+  },
+  DynamicInfo {
+    bootstrap_method_attr_index: u16,
+    name_and_type_index: u16,
+  },
+  InvokeDynamicInfo {
+    bootstrap_method_attr_index: u16,
+    name_and_type_index: u16,
+  },
+  ModuleInfo {
+    name_index: u16,
+  },
+  PackageInfo {
+    name_index: u16,
+  },
+  /// Index 0, and the index after each `Long` or `Double`, which no
+  /// reference may name.
+  Unusable,
+}
+
+/// Decodes the "modified UTF-8" used by `CONSTANT_Utf8` entries, where NUL is
+/// encoded in two bytes and supplementary characters as surrogate pairs.
+///
+/// Unpaired surrogates become U+FFFD, since a [String] cannot hold them.
+pub fn decode_modified_utf8(bytes: &[u8]) -> Option<String> {
+  let mut units = Vec::with_capacity(bytes.len());
+  let mut bytes = bytes.iter().map(|&byte| byte as u16);
+
+  while let Some(x) = bytes.next() {
+    let unit = match x {
+      0x01..=0x7F => x,
+      0xC0..=0xDF => {
+        let y = bytes.next().filter(|y| y & 0xC0 == 0x80)?;
+        (x & 0x1F) << 6 | (y & 0x3F)
+      }
+      0xE0..=0xEF => {
+        let y = bytes.next().filter(|y| y & 0xC0 == 0x80)?;
+        let z = bytes.next().filter(|z| z & 0xC0 == 0x80)?;
+        (x & 0x0F) << 12 | (y & 0x3F) << 6 | (z & 0x3F)
+      }
+      _ => return None,
+    };
+    units.push(unit);
+  }
+
+  Some(String::from_utf16_lossy(&units))
+}
+
+/// A constant pool entry that is missing or has an unexpected kind.
+#[derive(Debug)]
+pub struct PoolError {
+  pub index: u16,
+  pub expected: &'static str,
+}
+
+impl fmt::Display for PoolError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "Constant pool index #{} is not a {}",
+      self.index, self.expected
+    )
+  }
+}
+
+impl std::error::Error for PoolError {}
+
+/// A symbolic reference after it was resolved by the runtime.
+#[derive(Clone, Debug)]
+pub enum Resolved {
+  /// The class a `CONSTANT_Class` names.
+  Class(Rc<Class>),
+  /// The `java.lang.String` object for a `CONSTANT_String`.
+  String(usize),
+  /// The method a `CONSTANT_Methodref` refers to.
+  Method { class: Rc<Class>, method: usize },
+  /// The method a `CONSTANT_Methodref` or `CONSTANT_InterfaceMethodref`
+  /// called on a receiver refers to, and how the receiver picks an override.
+  Virtual {
+    class: Rc<Class>,
+    method: usize,
+    dispatch: Dispatch,
+  },
+  /// The static field a `CONSTANT_Fieldref` refers to, in the class that
+  /// declares it.
+  StaticField { class: Rc<Class>, field: usize },
+  /// Where the instance field a `CONSTANT_Fieldref` refers to lives in an
+  /// object.
+  Field { offset: usize },
+  /// The string concatenation a `CONSTANT_InvokeDynamic` bootstrapped by
+  /// `StringConcatFactory` performs.
+  Concat(Rc<Recipe>),
+}
+
+/// How a call finds the method to run in the class of its receiver.
+#[derive(Clone, Copy, Debug)]
+pub enum Dispatch {
+  /// Runs the resolved method, which nothing can override.
+  Direct,
+  /// Runs the method in this vtable slot.
+  Virtual(usize),
+  /// Runs the method the itable selects for the resolved interface method.
+  Interface,
+}
+
+/// The constant pool of a class, indexed from 1 like in the class file.
+#[derive(Clone, Debug, Default)]
+pub struct ConstantPool {
+  entries: Vec<Entry>,
+  /// Symbolic references already resolved by the runtime.
+  resolved: RefCell<HashMap<u16, Resolved>>,
+}
+
+impl ConstantPool {
+  pub fn new(entries: Vec<Entry>) -> Self {
+    Self {
+      entries,
+      resolved: RefCell::default(),
+    }
+  }
+
+  pub fn get(&self, index: u16) -> Result<&Entry, PoolError> {
+    match self.entries.get(index as usize) {
+      Some(Entry::Unusable) | None => Err(PoolError {
+        index,
+        expected: "valid entry",
+      }),
+      Some(entry) => Ok(entry),
+    }
+  }
+
+  pub fn utf8(&self, index: u16) -> Result<&str, PoolError> {
+    match self.get(index) {
+      Ok(Entry::Utf8Info { bytes }) => Ok(bytes),
+      _ => Err(PoolError {
+        index,
+        expected: "Utf8",
+      }),
+    }
+  }
+
+  pub fn class_name(&self, index: u16) -> Result<&str, PoolError> {
+    match self.get(index) {
+      Ok(Entry::ClassInfo { name_index }) => self.utf8(*name_index),
+      _ => Err(PoolError {
+        index,
+        expected: "Class",
+      }),
+    }
+  }
+
+  /// The name and descriptor of a `CONSTANT_NameAndType`.
+  pub fn name_and_type(&self, index: u16) -> Result<(&str, &str), PoolError> {
+    match self.get(index) {
+      Ok(Entry::NameAndTypeInfo {
+        index,
+        descriptor_index,
+      }) => Ok((self.utf8(*index)?, self.utf8(*descriptor_index)?)),
+      _ => Err(PoolError {
+        index,
+        expected: "NameAndType",
+      }),
+    }
+  }
+
+  /// The class, name and descriptor of a `CONSTANT_Methodref` or
+  /// `CONSTANT_InterfaceMethodref`.
+  pub fn method_ref(
+    &self,
+    index: u16,
+  ) -> Result<(&str, &str, &str), PoolError> {
+    match self.get(index) {
+      Ok(
+        Entry::MethodRefInfo {
+          class_index,
+          name_and_type_index,
+        }
+        | Entry::InterfaceMethodRefInfo {
+          class_index,
+          name_and_type_index,
+        },
+      ) => self.member_ref(*class_index, *name_and_type_index),
+      _ => Err(PoolError {
+        index,
+        expected: "Methodref",
+      }),
+    }
+  }
+
+  /// The class, name and descriptor of a `CONSTANT_Fieldref`.
+  pub fn field_ref(&self, index: u16) -> Result<(&str, &str, &str), PoolError> {
+    match self.get(index) {
+      Ok(Entry::FieldRefInfo {
+        class_index,
+        name_and_type_index,
+      }) => self.member_ref(*class_index, *name_and_type_index),
+      _ => Err(PoolError {
+        index,
+        expected: "Fieldref",
+      }),
+    }
+  }
+
+  fn member_ref(
+    &self,
+    class_index: u16,
+    name_and_type_index: u16,
+  ) -> Result<(&str, &str, &str), PoolError> {
+    let class = self.class_name(class_index)?;
+    let (name, descriptor) = self.name_and_type(name_and_type_index)?;
+    Ok((class, name, descriptor))
+  }
+
+  /// The kind and referenced member of a `CONSTANT_MethodHandle`.
+  pub fn method_handle(&self, index: u16) -> Result<(u8, u16), PoolError> {
+    match self.get(index) {
+      Ok(Entry::MethodHandleInfo {
+        reference_kind,
+        reference_index,
+      }) => Ok((*reference_kind, *reference_index)),
+      _ => Err(PoolError {
+        index,
+        expected: "MethodHandle",
+      }),
+    }
+  }
+
+  /// The bootstrap method index, name and descriptor of a
+  /// `CONSTANT_InvokeDynamic`.
+  pub fn invoke_dynamic(
+    &self,
+    index: u16,
+  ) -> Result<(u16, &str, &str), PoolError> {
+    match self.get(index) {
+      Ok(Entry::InvokeDynamicInfo {
+        bootstrap_method_attr_index,
+        name_and_type_index,
+      }) => {
+        let (name, descriptor) = self.name_and_type(*name_and_type_index)?;
+        Ok((*bootstrap_method_attr_index, name, descriptor))
+      }
+      _ => Err(PoolError {
+        index,
+        expected: "InvokeDynamic",
+      }),
+    }
+  }
+
+  pub fn string(&self, index: u16) -> Result<&str, PoolError> {
+    match self.get(index) {
+      Ok(Entry::StringInfo { string_index }) => self.utf8(*string_index),
+      _ => Err(PoolError {
+        index,
+        expected: "String",
+      }),
+    }
+  }
+
+  pub fn integer(&self, index: u16) -> Result<i32, PoolError> {
+    match self.get(index) {
+      Ok(Entry::IntegerInfo { bytes }) => Ok(*bytes as i32),
+      _ => Err(PoolError {
+        index,
+        expected: "Integer",
+      }),
+    }
+  }
+
+  pub fn float(&self, index: u16) -> Result<f32, PoolError> {
+    match self.get(index) {
+      Ok(Entry::FloatInfo { bytes }) => Ok(f32::from_bits(*bytes)),
+      _ => Err(PoolError {
+        index,
+        expected: "Float",
+      }),
+    }
+  }
+
+  pub fn long(&self, index: u16) -> Result<i64, PoolError> {
+    match self.get(index) {
+      Ok(Entry::LongInfo {
+        high_bytes,
+        low_bytes,
+      }) => Ok(((*high_bytes as u64) << 32 | *low_bytes as u64) as i64),
+      _ => Err(PoolError {
+        index,
+        expected: "Long",
+      }),
+    }
+  }
+
+  pub fn double(&self, index: u16) -> Result<f64, PoolError> {
+    match self.get(index) {
+      Ok(Entry::DoubleInfo {
+        high_bytes,
+        low_bytes,
+      }) => Ok(f64::from_bits(
+        (*high_bytes as u64) << 32 | *low_bytes as u64,
+      )),
+      _ => Err(PoolError {
+        index,
+        expected: "Double",
+      }),
+    }
+  }
+
+  /// What the runtime resolved `index` to, if it already did.
+  pub fn resolved(&self, index: u16) -> Option<Resolved> {
+    self.resolved.borrow().get(&index).cloned()
+  }
+
+  /// Remembers what `index` resolved to, so later executions skip the lookup.
+  pub fn resolve(&self, index: u16, resolved: Resolved) {
+    self.resolved.borrow_mut().insert(index, resolved);
+  }
 }
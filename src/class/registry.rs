@@ -0,0 +1,486 @@
+//! Natives: methods implemented in Rust, registered by class, name and
+//! descriptor, and bound to the methods they implement as classes are added
+//! to the [Context](super::Context).
+
+use std::{collections::HashMap, rc::Rc};
+
+use super::{
+  attribute_info::{AttributeInfo, Code, NativeFn, NativeResult},
+  descriptor::{BaseType, FieldType, MethodDescriptor},
+  method::MethodInfo,
+  Class, ACC_NATIVE, ACC_PUBLIC, ACC_STATIC,
+};
+use crate::{
+  local::Local,
+  run::{RuntimeContext, RuntimeError},
+  value::MistValue,
+};
+
+/// The natives of each class, by binary name.
+#[derive(Default)]
+pub struct Registry {
+  classes: HashMap<String, Vec<Native>>,
+}
+
+struct Native {
+  name: String,
+  descriptor: String,
+  is_static: bool,
+  max_local: u16,
+  code: Rc<NativeFn>,
+}
+
+impl Registry {
+  /// Registers `f` as the static method `name` with `descriptor` of
+  /// `class_name`.
+  ///
+  /// `f` takes the arguments as a tuple, and may take the [RuntimeContext]
+  /// before them:
+  ///
+  /// ```ignore
+  /// registry.register_static(
+  ///   "java/lang/Math",
+  ///   "sqrt",
+  ///   "(D)D",
+  ///   |(a,): (f64,)| a.sqrt(),
+  /// );
+  /// ```
+  ///
+  /// # Panics
+  ///
+  /// If `f` does not take as many arguments as the method, or the types it
+  /// takes and returns do not match `descriptor`.
+  #[track_caller]
+  pub fn register_static<Marker>(
+    &mut self,
+    class_name: &str,
+    name: &str,
+    descriptor: &str,
+    f: impl IntoNative<Marker>,
+  ) {
+    self.register(class_name, name, descriptor, true, f);
+  }
+
+  /// Registers `f` as the instance method `name` with `descriptor` of
+  /// `class_name`, like [Registry::register_static] but with `this` leading
+  /// the arguments.
+  ///
+  /// # Panics
+  ///
+  /// If `f` does not take as many arguments as the method, or the types it
+  /// takes and returns do not match `descriptor`.
+  #[track_caller]
+  pub fn register_instance<Marker>(
+    &mut self,
+    class_name: &str,
+    name: &str,
+    descriptor: &str,
+    f: impl IntoNative<Marker>,
+  ) {
+    self.register(class_name, name, descriptor, false, f);
+  }
+
+  #[track_caller]
+  fn register<Marker>(
+    &mut self,
+    class_name: &str,
+    name: &str,
+    descriptor: &str,
+    is_static: bool,
+    f: impl IntoNative<Marker>,
+  ) {
+    let method_descriptor = MethodDescriptor::parse(descriptor)
+      .expect("Natives are registered with valid descriptors.");
+    let mut params = method_descriptor.params;
+    if !is_static {
+      params.insert(0, FieldType::Object(class_name.to_string()));
+    }
+    assert!(
+      f.arity() == params.len(),
+      "{class_name}.{name}{descriptor} is registered with {} arguments \
+       instead of {}",
+      f.arity(),
+      params.len()
+    );
+    assert!(
+      f.matches(&params, method_descriptor.ret.as_ref()),
+      "{class_name}.{name}{descriptor} is registered with other types"
+    );
+
+    let max_local = params.iter().map(FieldType::slots).sum::<usize>();
+    self
+      .classes
+      .entry(class_name.to_string())
+      .or_default()
+      .push(Native {
+        name: name.to_string(),
+        descriptor: descriptor.to_string(),
+        is_static,
+        max_local: max_local as u16,
+        code: f.into_native(),
+      });
+  }
+
+  /// Declares a public native method in `class` for each native registered
+  /// for it that it does not declare itself, as built-in classes have no
+  /// class file to declare them.
+  pub fn declare(&self, class: &mut Class) {
+    let Some(natives) = self.classes.get(&class.this_class) else {
+      return;
+    };
+    for native in natives {
+      if class
+        .method_index(&native.name, &native.descriptor)
+        .is_some()
+      {
+        continue;
+      }
+      let access_flags = if native.is_static {
+        ACC_PUBLIC | ACC_STATIC | ACC_NATIVE
+      } else {
+        ACC_PUBLIC | ACC_NATIVE
+      };
+      class.methods.push(
+        MethodInfo::new(
+          access_flags,
+          native.name.clone(),
+          native.descriptor.clone(),
+          Vec::new(),
+        )
+        .expect("Natives are registered with valid descriptors."),
+      );
+    }
+  }
+
+  /// Gives each native method of `class` the code registered for it. Those
+  /// without any are left to raise `UnsatisfiedLinkError` when called.
+  pub fn bind(&self, class: &mut Class) {
+    let Some(natives) = self.classes.get(&class.this_class) else {
+      return;
+    };
+    for method in &mut class.methods {
+      if !method.is_native() || method.code().is_some() {
+        continue;
+      }
+      let native = natives.iter().find(|native| {
+        native.name == method.name
+          && native.descriptor == method.descriptor
+          && native.is_static == method.is_static()
+      });
+      if let Some(native) = native {
+        method.attributes.push(AttributeInfo::Code(Code::native(
+          Rc::clone(&native.code),
+          native.max_local,
+        )));
+      }
+    }
+  }
+}
+
+/// A Rust type that a native takes or returns for a Java type.
+pub trait JavaValue: Sized {
+  /// Whether values of `field_type` convert to and from this type.
+  fn accepts(field_type: &FieldType) -> bool;
+
+  /// Converts from the form the value takes in a local variable.
+  fn from_java(value: MistValue) -> Self;
+
+  /// Converts to the form the value takes on the operand stack.
+  fn into_java(self) -> MistValue;
+}
+
+macro_rules! java_value {
+  ($type:ty, $base_type:ident, $from:expr, $into:expr) => {
+    impl JavaValue for $type {
+      fn accepts(field_type: &FieldType) -> bool {
+        *field_type == FieldType::Base(BaseType::$base_type)
+      }
+
+      fn from_java(value: MistValue) -> Self {
+        $from(value)
+      }
+
+      fn into_java(self) -> MistValue {
+        $into(self)
+      }
+    }
+  };
+}
+
+java_value!(
+  bool,
+  Boolean,
+  |value| i32::from(value) != 0,
+  |value: bool| MistValue::Integer(value.into())
+);
+java_value!(i8, Byte, |value| i32::from(value) as i8, |value: i8| {
+  MistValue::Integer(value.into())
+});
+java_value!(i16, Short, |value| i32::from(value) as i16, |value: i16| {
+  MistValue::Integer(value.into())
+});
+java_value!(u16, Char, |value| i32::from(value) as u16, |value: u16| {
+  MistValue::Integer(value.into())
+});
+java_value!(i32, Int, i32::from, MistValue::Integer);
+java_value!(i64, Long, i64::from, MistValue::Long);
+java_value!(f32, Float, f32::from, MistValue::Float);
+java_value!(f64, Double, f64::from, MistValue::Double);
+
+/// A reference, null being 0.
+impl JavaValue for usize {
+  fn accepts(field_type: &FieldType) -> bool {
+    matches!(field_type, FieldType::Object(_) | FieldType::Array(_))
+  }
+
+  fn from_java(value: MistValue) -> Self {
+    value.into()
+  }
+
+  fn into_java(self) -> MistValue {
+    MistValue::ObjectReference(self)
+  }
+}
+
+/// A value of any type, as it is.
+impl JavaValue for MistValue {
+  fn accepts(_: &FieldType) -> bool {
+    true
+  }
+
+  fn from_java(value: MistValue) -> Self {
+    value
+  }
+
+  fn into_java(self) -> MistValue {
+    self
+  }
+}
+
+/// The tuple of arguments a native takes.
+pub trait Arguments: Sized {
+  const LEN: usize;
+
+  /// Whether the tuple takes parameters of the types `params`.
+  fn accepts(params: &[FieldType]) -> bool;
+
+  /// Reads the arguments from the local variables they were passed in.
+  fn read(local: &Local) -> Self;
+}
+
+impl Arguments for () {
+  const LEN: usize = 0;
+
+  fn accepts(params: &[FieldType]) -> bool {
+    params.is_empty()
+  }
+
+  fn read(_: &Local) -> Self {}
+}
+
+macro_rules! arguments {
+  ($($arg:ident),+) => {
+    impl<$($arg: JavaValue),+> Arguments for ($($arg,)+) {
+      const LEN: usize = [$(stringify!($arg)),+].len();
+
+      fn accepts(params: &[FieldType]) -> bool {
+        let mut params = params.iter();
+        $(params.next().is_some_and($arg::accepts) &&)+ params.next().is_none()
+      }
+
+      fn read(local: &Local) -> Self {
+        let mut slot = 0;
+        let mut next = || {
+          let value = local.load(slot);
+          slot += value.category();
+          value
+        };
+        ($($arg::from_java(next()),)+)
+      }
+    }
+  };
+}
+
+arguments!(A);
+arguments!(A, B);
+arguments!(A, B, C);
+arguments!(A, B, C, D);
+
+/// What a native returns: nothing, a [JavaValue], or either of those or a
+/// Java exception.
+pub trait Returns {
+  /// Whether this is what a method returning `ret` returns.
+  fn returns(ret: Option<&FieldType>) -> bool;
+
+  fn into_result(self) -> NativeResult;
+}
+
+impl Returns for () {
+  fn returns(ret: Option<&FieldType>) -> bool {
+    ret.is_none()
+  }
+
+  fn into_result(self) -> NativeResult {
+    Ok(None)
+  }
+}
+
+impl<T: JavaValue> Returns for T {
+  fn returns(ret: Option<&FieldType>) -> bool {
+    ret.is_some_and(T::accepts)
+  }
+
+  fn into_result(self) -> NativeResult {
+    Ok(Some(self.into_java()))
+  }
+}
+
+impl<T: Returns> Returns for Result<T, RuntimeError> {
+  fn returns(ret: Option<&FieldType>) -> bool {
+    T::returns(ret)
+  }
+
+  fn into_result(self) -> NativeResult {
+    self.and_then(T::into_result)
+  }
+}
+
+/// A Rust function that can implement a method. `Marker` tells apart the
+/// functions that take the [RuntimeContext] from those that do not.
+pub trait IntoNative<Marker>: 'static {
+  /// The number of arguments, counting `this`.
+  fn arity(&self) -> usize;
+
+  /// Whether the function takes `params` and returns `ret`.
+  fn matches(&self, params: &[FieldType], ret: Option<&FieldType>) -> bool;
+
+  fn into_native(self) -> Rc<NativeFn>;
+}
+
+/// Marks the natives that take the [RuntimeContext].
+pub struct WithRuntime;
+
+impl<F, A, R> IntoNative<(A, R)> for F
+where
+  F: Fn(A) -> R + 'static,
+  A: Arguments,
+  R: Returns,
+{
+  fn arity(&self) -> usize {
+    A::LEN
+  }
+
+  fn matches(&self, params: &[FieldType], ret: Option<&FieldType>) -> bool {
+    A::accepts(params) && R::returns(ret)
+  }
+
+  fn into_native(self) -> Rc<NativeFn> {
+    Rc::new(move |_, local| self(A::read(local)).into_result())
+  }
+}
+
+impl<F, A, R> IntoNative<(WithRuntime, A, R)> for F
+where
+  F: Fn(&mut RuntimeContext<'_>, A) -> R + 'static,
+  A: Arguments,
+  R: Returns,
+{
+  fn arity(&self) -> usize {
+    A::LEN
+  }
+
+  fn matches(&self, params: &[FieldType], ret: Option<&FieldType>) -> bool {
+    A::accepts(params) && R::returns(ret)
+  }
+
+  fn into_native(self) -> Rc<NativeFn> {
+    Rc::new(move |rt, local| self(rt, A::read(local)).into_result())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Registry;
+  use crate::{
+    class::{loader::DirectoryLoader, Context},
+    run::RuntimeContext,
+    value::MistValue,
+  };
+
+  /// A context that loads `tests/natives`, whose `Natives` class declares
+  /// `twice(I)I`, `greet(Ljava/lang/String;)Ljava/lang/String;` and
+  /// `unbound()V` native.
+  fn context() -> Context {
+    let mut context = Context::new();
+    context.add_loader(DirectoryLoader::new(concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/tests/natives"
+    )));
+    context
+  }
+
+  #[test]
+  fn binds_natives_of_loaded_classes() {
+    let mut context = context();
+    let natives = context.natives_mut();
+    natives.register_static("Natives", "twice", "(I)I", |(x,): (i32,)| x * 2);
+    natives.register_instance(
+      "Natives",
+      "greet",
+      "(Ljava/lang/String;)Ljava/lang/String;",
+      |rt: &mut RuntimeContext, (_, name): (usize, usize)| {
+        let greeting = format!("hello, {}", rt.string(name));
+        rt.new_string(&greeting)
+      },
+    );
+
+    let mut rt = RuntimeContext::new(&mut context);
+    let result = rt.boot("Natives", &[]).ok().flatten();
+    assert!(
+      matches!(result, Some(MistValue::Integer(0b111))),
+      "{result:?}"
+    );
+  }
+
+  #[test]
+  fn unbound_native_is_unsatisfied_link() {
+    let mut context = context();
+    let mut rt = RuntimeContext::new(&mut context);
+    let Err(error) = rt.boot("Natives", &[]) else {
+      panic!("twice(I)I has no native");
+    };
+    let description = rt.describe(error);
+    assert!(
+      description
+        .starts_with("java.lang.UnsatisfiedLinkError: Natives.twice(I)I\n"),
+      "{description}"
+    );
+  }
+
+  #[test]
+  #[should_panic(
+    expected = "Natives.greet(Ljava/lang/String;)Ljava/lang/String; is \
+                registered with 1 arguments instead of 2"
+  )]
+  fn instance_native_without_this() {
+    Registry::default().register_instance(
+      "Natives",
+      "greet",
+      "(Ljava/lang/String;)Ljava/lang/String;",
+      |(name,): (usize,)| name,
+    );
+  }
+
+  #[test]
+  #[should_panic(
+    expected = "Natives.twice(I)I is registered with 2 arguments instead of 1"
+  )]
+  fn static_native_with_this() {
+    Registry::default().register_static(
+      "Natives",
+      "twice",
+      "(I)I",
+      |(_, x): (usize, i32)| x * 2,
+    );
+  }
+}
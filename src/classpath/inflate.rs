@@ -0,0 +1,365 @@
+//! A decoder for raw DEFLATE streams (RFC 1951), as stored in zip archives.
+
+use std::io;
+
+/// Base lengths of the length symbols 257..285.
+const LENGTH_BASE: [u16; 29] = [
+  3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67,
+  83, 99, 115, 131, 163, 195, 227, 258,
+];
+/// Extra bits of the length symbols 257..285.
+const LENGTH_EXTRA: [u8; 29] = [
+  0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5,
+  5, 5, 0,
+];
+/// Base distances of the distance symbols 0..29.
+const DISTANCE_BASE: [u16; 30] = [
+  1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513,
+  769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+/// Extra bits of the distance symbols 0..29.
+const DISTANCE_EXTRA: [u8; 30] = [
+  0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11,
+  11, 12, 12, 13, 13,
+];
+/// Order in which the code length code lengths are stored.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+  16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+const MAX_BITS: usize = 15;
+
+/// Decompresses `input`; `size_hint` is the expected size of the output.
+pub fn inflate(input: &[u8], size_hint: usize) -> io::Result<Vec<u8>> {
+  let mut bits = Bits {
+    input,
+    position: 0,
+    buffer: 0,
+    count: 0,
+  };
+  // The hint comes from the archive, so it is not trusted with more than a
+  // typical compression ratio; the output grows as needed past that.
+  let mut output =
+    Vec::with_capacity(size_hint.min(input.len().saturating_mul(4)));
+
+  loop {
+    let last = bits.read(1)? == 1;
+
+    match bits.read(2)? {
+      0 => stored(&mut bits, &mut output)?,
+      1 => {
+        let (literals, distances) = fixed_codes();
+        codes(&mut bits, &mut output, &literals, &distances)?
+      }
+      2 => {
+        let (literals, distances) = dynamic_codes(&mut bits)?;
+        codes(&mut bits, &mut output, &literals, &distances)?
+      }
+      _ => return Err(invalid("Invalid deflate block type")),
+    }
+
+    if last {
+      break Ok(output);
+    }
+  }
+}
+
+fn invalid(message: &str) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Reads the input bit by bit, least significant bit first.
+struct Bits<'a> {
+  input: &'a [u8],
+  position: usize,
+  buffer: u32,
+  count: u32,
+}
+
+impl Bits<'_> {
+  fn read(&mut self, need: u32) -> io::Result<u32> {
+    while self.count < need {
+      let byte = *self
+        .input
+        .get(self.position)
+        .ok_or_else(|| invalid("Unexpected end of deflate stream"))?;
+      self.position += 1;
+      self.buffer |= (byte as u32) << self.count;
+      self.count += 8;
+    }
+
+    let value = self.buffer & ((1 << need) - 1);
+    self.buffer >>= need;
+    self.count -= need;
+    Ok(value)
+  }
+
+  /// Drops the bits left in the current byte.
+  fn align(&mut self) {
+    self.buffer = 0;
+    self.count = 0;
+  }
+}
+
+/// A canonical Huffman code.
+struct Huffman {
+  /// How many symbols have each code length.
+  counts: [u16; MAX_BITS + 1],
+  /// Symbols ordered by code length, then by value.
+  symbols: Vec<u16>,
+}
+
+impl Huffman {
+  fn new(lengths: &[u8]) -> Self {
+    let mut counts = [0u16; MAX_BITS + 1];
+    for &length in lengths {
+      counts[length as usize] += 1;
+    }
+    counts[0] = 0;
+
+    let mut offsets = [0u16; MAX_BITS + 1];
+    for length in 1..MAX_BITS {
+      offsets[length + 1] = offsets[length] + counts[length];
+    }
+
+    let mut symbols = vec![0; lengths.len()];
+    for (symbol, &length) in lengths.iter().enumerate() {
+      if length != 0 {
+        symbols[offsets[length as usize] as usize] = symbol as u16;
+        offsets[length as usize] += 1;
+      }
+    }
+
+    Self { counts, symbols }
+  }
+
+  fn decode(&self, bits: &mut Bits) -> io::Result<u16> {
+    let mut code = 0i32;
+    let mut first = 0i32;
+    let mut index = 0i32;
+
+    for length in 1..=MAX_BITS {
+      code |= bits.read(1)? as i32;
+      let count = self.counts[length] as i32;
+      if code - count < first {
+        return Ok(self.symbols[(index + (code - first)) as usize]);
+      }
+      index += count;
+      first += count;
+      first <<= 1;
+      code <<= 1;
+    }
+
+    Err(invalid("Invalid Huffman code"))
+  }
+}
+
+fn stored(bits: &mut Bits, output: &mut Vec<u8>) -> io::Result<()> {
+  bits.align();
+
+  let header = bits
+    .input
+    .get(bits.position..bits.position + 4)
+    .ok_or_else(|| invalid("Unexpected end of deflate stream"))?;
+  let length = u16::from_le_bytes([header[0], header[1]]);
+  let complement = u16::from_le_bytes([header[2], header[3]]);
+  if length != !complement {
+    return Err(invalid("Stored block length does not match its complement"));
+  }
+  bits.position += 4;
+
+  let data = bits
+    .input
+    .get(bits.position..bits.position + length as usize)
+    .ok_or_else(|| invalid("Unexpected end of deflate stream"))?;
+  output.extend_from_slice(data);
+  bits.position += length as usize;
+
+  Ok(())
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+  let mut lengths = [0u8; 288];
+  lengths[..144].fill(8);
+  lengths[144..256].fill(9);
+  lengths[256..280].fill(7);
+  lengths[280..].fill(8);
+
+  (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn dynamic_codes(bits: &mut Bits) -> io::Result<(Huffman, Huffman)> {
+  let literals_count = bits.read(5)? as usize + 257;
+  let distances_count = bits.read(5)? as usize + 1;
+  let code_lengths_count = bits.read(4)? as usize + 4;
+
+  if literals_count > 286 || distances_count > 30 {
+    return Err(invalid("Too many length or distance codes"));
+  }
+
+  let mut code_lengths = [0u8; 19];
+  for &index in &CODE_LENGTH_ORDER[..code_lengths_count] {
+    code_lengths[index] = bits.read(3)? as u8;
+  }
+  let code_lengths = Huffman::new(&code_lengths);
+
+  let mut lengths = vec![0u8; literals_count + distances_count];
+  let mut index = 0;
+  while index < lengths.len() {
+    let symbol = code_lengths.decode(bits)?;
+
+    let (length, repeat) = match symbol {
+      0..=15 => (symbol as u8, 1),
+      16 => {
+        let previous = index
+          .checked_sub(1)
+          .map(|previous| lengths[previous])
+          .ok_or_else(|| {
+          invalid("Repeated length with no previous length")
+        })?;
+        (previous, 3 + bits.read(2)? as usize)
+      }
+      17 => (0, 3 + bits.read(3)? as usize),
+      _ => (0, 11 + bits.read(7)? as usize),
+    };
+
+    if index + repeat > lengths.len() {
+      return Err(invalid("Too many code lengths"));
+    }
+    lengths[index..index + repeat].fill(length);
+    index += repeat;
+  }
+
+  let (literals, distances) = lengths.split_at(literals_count);
+  Ok((Huffman::new(literals), Huffman::new(distances)))
+}
+
+fn codes(
+  bits: &mut Bits,
+  output: &mut Vec<u8>,
+  literals: &Huffman,
+  distances: &Huffman,
+) -> io::Result<()> {
+  loop {
+    let symbol = literals.decode(bits)? as usize;
+
+    match symbol {
+      0..=255 => output.push(symbol as u8),
+      256 => break Ok(()),
+      _ => {
+        let symbol = symbol - 257;
+        if symbol >= LENGTH_BASE.len() {
+          return Err(invalid("Invalid length symbol"));
+        }
+        let length = LENGTH_BASE[symbol] as usize
+          + bits.read(LENGTH_EXTRA[symbol] as u32)? as usize;
+
+        let symbol = distances.decode(bits)? as usize;
+        if symbol >= DISTANCE_BASE.len() {
+          return Err(invalid("Invalid distance symbol"));
+        }
+        let distance = DISTANCE_BASE[symbol] as usize
+          + bits.read(DISTANCE_EXTRA[symbol] as u32)? as usize;
+
+        if distance > output.len() {
+          return Err(invalid("Distance goes back past the output"));
+        }
+
+        // The copied range may overlap the bytes being written.
+        let start = output.len() - distance;
+        for index in start..start + length {
+          output.push(output[index]);
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::inflate;
+
+  /// "hello, hello, hello!" in a block with the fixed codes.
+  const FIXED: [u8; 12] = [
+    0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0xd7, 0x51, 0xc8, 0x40, 0xa2, 0x14, 0x01,
+  ];
+
+  /// [dynamic_text] in a block with dynamic codes.
+  const DYNAMIC: [u8; 150] = [
+    0x0d, 0xcd, 0x05, 0x01, 0xc3, 0x30, 0x00, 0x00, 0x41, 0x4b, 0x61, 0x90,
+    0xb3, 0xa6, 0xcc, 0x0c, 0x51, 0xbf, 0x3f, 0x03, 0xf7, 0x2b, 0x92, 0x28,
+    0x52, 0x29, 0x53, 0x59, 0xa9, 0xb2, 0xaa, 0x75, 0x55, 0x37, 0xa6, 0x6e,
+    0x5a, 0xdb, 0xb4, 0x9d, 0x6b, 0xbb, 0xde, 0x77, 0xfd, 0x10, 0xfa, 0x61,
+    0x8c, 0xc3, 0x38, 0x89, 0x71, 0x9a, 0xe5, 0x34, 0x2f, 0x6a, 0x5e, 0x56,
+    0xbd, 0xac, 0x9b, 0x59, 0xb7, 0xdd, 0x6e, 0xfb, 0xe1, 0xf6, 0xe3, 0xf4,
+    0xc7, 0x79, 0x85, 0xf3, 0xba, 0xe3, 0x75, 0x3f, 0xe2, 0x7e, 0x5e, 0xf9,
+    0xbc, 0x9f, 0x7a, 0xbf, 0xac, 0xbf, 0x6c, 0xb2, 0xfd, 0x15, 0xc9, 0x91,
+    0x78, 0x92, 0x40, 0x12, 0x49, 0x04, 0x89, 0x24, 0x51, 0x24, 0x9a, 0xc4,
+    0x90, 0x58, 0x12, 0x47, 0xe2, 0x49, 0x02, 0x49, 0x24, 0x11, 0x24, 0x92,
+    0x44, 0x91, 0x68, 0x12, 0x43, 0x62, 0x49, 0x1c, 0x89, 0x27, 0x09, 0x24,
+    0xf1, 0xcb, 0x22, 0x4b, 0x12, 0x45, 0xa2, 0x49, 0x0c, 0x89, 0x25, 0x71,
+    0x24, 0x9e, 0x24, 0x90, 0xc4, 0x3f,
+  ];
+
+  /// "stored" in a stored block.
+  const STORED: [u8; 11] = [
+    0x01, 0x06, 0x00, 0xf9, 0xff, 0x73, 0x74, 0x6f, 0x72, 0x65, 0x64,
+  ];
+
+  /// "abc0bcd1cde2...", three letters and a digit at a time.
+  fn dynamic_text() -> Vec<u8> {
+    let alphabet = b"abcdefghijklmnopqrstuvwxyz";
+    (0..60)
+      .flat_map(|i| {
+        let letters = &alphabet[i % 26..(i % 26 + 3).min(26)];
+        letters.iter().copied().chain([b'0' + (i % 10) as u8])
+      })
+      .collect()
+  }
+
+  fn error(input: &[u8]) -> String {
+    inflate(input, 0)
+      .expect_err("the stream is invalid")
+      .to_string()
+  }
+
+  #[test]
+  fn fixed_block() {
+    assert_eq!(inflate(&FIXED, 20).unwrap(), b"hello, hello, hello!");
+  }
+
+  #[test]
+  fn dynamic_block() {
+    assert_eq!(inflate(&DYNAMIC, 234).unwrap(), dynamic_text());
+  }
+
+  #[test]
+  fn stored_block() {
+    assert_eq!(inflate(&STORED, 6).unwrap(), b"stored");
+  }
+
+  #[test]
+  fn untrusted_size_hint() {
+    assert_eq!(inflate(&STORED, u32::MAX as usize).unwrap(), b"stored");
+  }
+
+  #[test]
+  fn distance_past_output() {
+    // A fixed block with the literal 'a', then a copy of length 3 from two
+    // bytes back.
+    assert_eq!(
+      error(&[0x4b, 0x04, 0x42]),
+      "Distance goes back past the output"
+    );
+  }
+
+  #[test]
+  fn malformed_streams() {
+    assert_eq!(error(&[0x07]), "Invalid deflate block type");
+    assert_eq!(error(&FIXED[..6]), "Unexpected end of deflate stream");
+    assert_eq!(
+      error(&[0x01, 0x06, 0x00, 0xf9, 0xfe]),
+      "Stored block length does not match its complement"
+    );
+  }
+}
@@ -0,0 +1,103 @@
+pub mod inflate;
+pub mod zip;
+
+use std::{env, ffi::OsStr, io, path::Path};
+
+use crate::class::loader::{ClassLoader, DirectoryLoader, JarLoader};
+
+use self::zip::ZipArchive;
+
+const MANIFEST: &str = "META-INF/MANIFEST.MF";
+
+/// Where to look for classes, in order.
+#[derive(Default)]
+pub struct Classpath {
+  entries: Vec<Box<dyn ClassLoader>>,
+}
+
+impl Classpath {
+  /// Reads a list of paths separated like the `PATH` variable.
+  pub fn parse(classpath: &str) -> io::Result<Self> {
+    let mut this = Self::default();
+    for path in env::split_paths(classpath) {
+      this.push(&path)?;
+    }
+    Ok(this)
+  }
+
+  /// Appends a directory, or a `.jar` or `.zip` file.
+  pub fn push(&mut self, path: &Path) -> io::Result<()> {
+    let is_archive =
+      path
+        .extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|extension| {
+          extension.eq_ignore_ascii_case("jar")
+            || extension.eq_ignore_ascii_case("zip")
+        });
+
+    if is_archive {
+      let archive = ZipArchive::open(path)?;
+      self.entries.push(Box::new(JarLoader::new(archive)));
+    } else {
+      self.entries.push(Box::new(DirectoryLoader::new(path)));
+    }
+
+    Ok(())
+  }
+}
+
+impl ClassLoader for Classpath {
+  fn find_class(&self, class_name: &str) -> io::Result<Option<Vec<u8>>> {
+    for entry in &self.entries {
+      if let Some(bytes) = entry.find_class(class_name)? {
+        return Ok(Some(bytes));
+      }
+    }
+
+    Ok(None)
+  }
+}
+
+/// The `Main-Class` named in the manifest of `jar`, in binary form
+/// (`com/example/App`).
+pub fn main_class(jar: &ZipArchive) -> io::Result<Option<String>> {
+  let Some(manifest) = jar.read(MANIFEST)? else {
+    return Ok(None);
+  };
+  let manifest = String::from_utf8_lossy(&manifest);
+
+  // Long values are wrapped at 72 bytes onto lines starting with a space.
+  let mut headers: Vec<String> = Vec::new();
+  for line in manifest.lines() {
+    match (line.strip_prefix(' '), headers.last_mut()) {
+      (Some(continuation), Some(header)) => header.push_str(continuation),
+      _ => headers.push(line.to_string()),
+    }
+  }
+
+  let main_class = headers.iter().find_map(|header| {
+    let (name, value) = header.split_once(':')?;
+    name
+      .trim()
+      .eq_ignore_ascii_case("Main-Class")
+      .then(|| value.trim().replace('.', "/"))
+  });
+
+  Ok(main_class)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{main_class, zip::ZipArchive};
+
+  #[test]
+  fn main_class_on_continuation_lines() {
+    let jar = include_bytes!("../../tests/jar/app.jar");
+    let archive = ZipArchive::new(jar.to_vec()).unwrap();
+    assert_eq!(
+      main_class(&archive).unwrap().as_deref(),
+      Some("org/example/launcher/application/entrypoint/mainclass/Application")
+    );
+  }
+}
@@ -0,0 +1,171 @@
+//! A reader for zip archives such as `.jar` files.
+
+use std::{collections::HashMap, fs, io, path::Path};
+
+use super::inflate::inflate;
+
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x02014b50;
+const LOCAL_FILE_HEADER: u32 = 0x04034b50;
+
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+
+#[derive(Debug)]
+struct ZipEntry {
+  method: u16,
+  compressed_size: usize,
+  uncompressed_size: usize,
+  local_header_offset: usize,
+}
+
+/// A zip archive kept in memory, with its central directory indexed by name.
+#[derive(Debug)]
+pub struct ZipArchive {
+  bytes: Vec<u8>,
+  entries: HashMap<String, ZipEntry>,
+}
+
+impl ZipArchive {
+  pub fn open(path: &Path) -> io::Result<Self> {
+    Self::new(fs::read(path)?)
+  }
+
+  pub fn new(bytes: Vec<u8>) -> io::Result<Self> {
+    // The end of central directory record is followed by a comment of at
+    // most 65535 bytes, so it is searched for from the end.
+    let end = (0..bytes.len().saturating_sub(21))
+      .rev()
+      .take(22 + u16::MAX as usize)
+      .find(|&offset| {
+        read_u32(&bytes, offset) == Some(END_OF_CENTRAL_DIRECTORY)
+      })
+      .ok_or_else(|| invalid("Not a zip archive"))?;
+
+    let entries_count = read_u16(&bytes, end + 10).unwrap_or(0) as usize;
+    let mut offset = read_u32(&bytes, end + 16).unwrap_or(0) as usize;
+
+    let mut entries = HashMap::with_capacity(entries_count);
+    for _ in 0..entries_count {
+      let field = |at: usize| read_u16(&bytes, offset + at);
+      let wide_field = |at: usize| read_u32(&bytes, offset + at);
+
+      if wide_field(0) != Some(CENTRAL_DIRECTORY_HEADER) {
+        return Err(invalid("Bad central directory header"));
+      }
+
+      let method = field(10).unwrap_or_default();
+      let compressed_size = wide_field(20).unwrap_or_default() as usize;
+      let uncompressed_size = wide_field(24).unwrap_or_default() as usize;
+      let name_length = field(28).unwrap_or_default() as usize;
+      let extra_length = field(30).unwrap_or_default() as usize;
+      let comment_length = field(32).unwrap_or_default() as usize;
+      let local_header_offset = wide_field(42).unwrap_or_default() as usize;
+
+      let name = bytes
+        .get(offset + 46..offset + 46 + name_length)
+        .ok_or_else(|| invalid("Truncated central directory"))?;
+      let name = String::from_utf8_lossy(name).into_owned();
+
+      entries.insert(
+        name,
+        ZipEntry {
+          method,
+          compressed_size,
+          uncompressed_size,
+          local_header_offset,
+        },
+      );
+
+      offset += 46 + name_length + extra_length + comment_length;
+    }
+
+    Ok(Self { bytes, entries })
+  }
+
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.entries.keys().map(String::as_str)
+  }
+
+  /// The uncompressed contents of `name`, [None] if there is no such entry.
+  pub fn read(&self, name: &str) -> io::Result<Option<Vec<u8>>> {
+    let Some(entry) = self.entries.get(name) else {
+      return Ok(None);
+    };
+
+    let offset = entry.local_header_offset;
+    if read_u32(&self.bytes, offset) != Some(LOCAL_FILE_HEADER) {
+      return Err(invalid("Bad local file header"));
+    }
+    let name_length = read_u16(&self.bytes, offset + 26).unwrap_or(0) as usize;
+    let extra_length = read_u16(&self.bytes, offset + 28).unwrap_or(0) as usize;
+
+    let start = offset + 30 + name_length + extra_length;
+    let data = self
+      .bytes
+      .get(start..start + entry.compressed_size)
+      .ok_or_else(|| invalid("Truncated zip entry"))?;
+
+    match entry.method {
+      STORED => Ok(Some(data.to_vec())),
+      DEFLATED => inflate(data, entry.uncompressed_size).map(Some),
+      method => Err(invalid(&format!(
+        "Unsupported compression method {method} for '{name}'"
+      ))),
+    }
+  }
+}
+
+fn invalid(message: &str) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+  let bytes = bytes.get(offset..offset + 2)?;
+  Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+  let bytes = bytes.get(offset..offset + 4)?;
+  Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ZipArchive;
+
+  const PACKAGE: &str = "org/example/launcher/application/entrypoint/mainclass";
+
+  fn archive() -> ZipArchive {
+    let jar = include_bytes!("../../tests/jar/app.jar");
+    ZipArchive::new(jar.to_vec()).unwrap()
+  }
+
+  #[test]
+  fn reads_stored_and_deflated_entries() {
+    let archive = archive();
+    let mut names: Vec<&str> = archive.names().collect();
+    names.sort_unstable();
+    assert_eq!(
+      names,
+      [
+        "META-INF/MANIFEST.MF",
+        &format!("{PACKAGE}/Application.class"),
+        &format!("{PACKAGE}/Helper.class"),
+      ]
+    );
+
+    for class in ["Application", "Helper"] {
+      let bytes = archive.read(&format!("{PACKAGE}/{class}.class")).unwrap();
+      assert!(bytes.unwrap().starts_with(&[0xCA, 0xFE, 0xBA, 0xBE]));
+    }
+    assert!(archive.read("Missing.class").unwrap().is_none());
+  }
+
+  #[test]
+  fn rejects_other_files() {
+    let error = ZipArchive::new(b"not a zip archive at all".to_vec())
+      .expect_err("there is no end of central directory record");
+    assert_eq!(error.to_string(), "Not a zip archive");
+  }
+}
@@ -1,15 +1,57 @@
-use crate::value::MistValue;
+use crate::{
+  class::descriptor::{BaseType, FieldType},
+  run::RuntimeError,
+  value::MistValue,
+};
 
 #[derive(Debug)]
 pub struct Heap {
   memory: Vec<Object>,
+  /// The [crate::class::Class::id] every array has as its class.
+  array_class: usize,
 }
 
+/// `atype` operands of `newarray`.
+pub const T_BOOLEAN: u8 = 4;
+pub const T_CHAR: u8 = 5;
+pub const T_FLOAT: u8 = 6;
+pub const T_DOUBLE: u8 = 7;
+pub const T_BYTE: u8 = 8;
+pub const T_SHORT: u8 = 9;
+pub const T_INT: u8 = 10;
+pub const T_LONG: u8 = 11;
+
 #[derive(Debug)]
 pub struct Object {
   pub id: usize,
   pub class: usize,
-  pub mem: Vec<MistValue>,
+  pub mem: Memory,
+}
+
+/// What an object holds: the fields of a class instance, or the elements of
+/// an array.
+#[derive(Debug)]
+pub enum Memory {
+  Fields(Vec<MistValue>),
+  Array(Array),
+}
+
+/// The elements of an array, stored as their component type.
+#[derive(Clone, Debug)]
+pub enum Array {
+  Boolean(Vec<bool>),
+  Char(Vec<u16>),
+  Float(Vec<f32>),
+  Double(Vec<f64>),
+  Byte(Vec<i8>),
+  Short(Vec<i16>),
+  Int(Vec<i32>),
+  Long(Vec<i64>),
+  Reference {
+    /// A class, interface or array type.
+    component: FieldType,
+    elements: Vec<usize>,
+  },
 }
 
 impl Object {
@@ -17,36 +59,290 @@ impl Object {
     Self {
       id: 0,
       class: 0,
-      mem: Vec::new(),
+      mem: Memory::Fields(Vec::new()),
+    }
+  }
+}
+
+impl Array {
+  /// An array of `length` zeroes or nulls of type `component`.
+  pub fn new(component: FieldType, length: usize) -> Self {
+    match component {
+      FieldType::Base(BaseType::Boolean) => Self::Boolean(vec![false; length]),
+      FieldType::Base(BaseType::Char) => Self::Char(vec![0; length]),
+      FieldType::Base(BaseType::Float) => Self::Float(vec![0.0; length]),
+      FieldType::Base(BaseType::Double) => Self::Double(vec![0.0; length]),
+      FieldType::Base(BaseType::Byte) => Self::Byte(vec![0; length]),
+      FieldType::Base(BaseType::Short) => Self::Short(vec![0; length]),
+      FieldType::Base(BaseType::Int) => Self::Int(vec![0; length]),
+      FieldType::Base(BaseType::Long) => Self::Long(vec![0; length]),
+      component => Self::Reference {
+        component,
+        elements: vec![0; length],
+      },
+    }
+  }
+}
+
+impl Array {
+  pub fn len(&self) -> usize {
+    match self {
+      Self::Boolean(elements) => elements.len(),
+      Self::Char(elements) => elements.len(),
+      Self::Float(elements) => elements.len(),
+      Self::Double(elements) => elements.len(),
+      Self::Byte(elements) => elements.len(),
+      Self::Short(elements) => elements.len(),
+      Self::Int(elements) => elements.len(),
+      Self::Long(elements) => elements.len(),
+      Self::Reference { elements, .. } => elements.len(),
+    }
+  }
+
+  /// The type of the elements.
+  pub fn component(&self) -> FieldType {
+    let base_type = match self {
+      Self::Boolean(_) => BaseType::Boolean,
+      Self::Char(_) => BaseType::Char,
+      Self::Float(_) => BaseType::Float,
+      Self::Double(_) => BaseType::Double,
+      Self::Byte(_) => BaseType::Byte,
+      Self::Short(_) => BaseType::Short,
+      Self::Int(_) => BaseType::Int,
+      Self::Long(_) => BaseType::Long,
+      Self::Reference { component, .. } => return component.clone(),
+    };
+    FieldType::Base(base_type)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// The element at `index`, widened to the form it takes on the operand
+  /// stack.
+  pub fn load(&self, index: usize) -> MistValue {
+    match self {
+      Self::Boolean(elements) => MistValue::Integer(elements[index].into()),
+      Self::Char(elements) => MistValue::Integer(elements[index].into()),
+      Self::Float(elements) => MistValue::Float(elements[index]),
+      Self::Double(elements) => MistValue::Double(elements[index]),
+      Self::Byte(elements) => MistValue::Integer(elements[index].into()),
+      Self::Short(elements) => MistValue::Integer(elements[index].into()),
+      Self::Int(elements) => MistValue::Integer(elements[index]),
+      Self::Long(elements) => MistValue::Long(elements[index]),
+      Self::Reference { elements, .. } => {
+        MistValue::ObjectReference(elements[index])
+      }
+    }
+  }
+
+  /// Stores `value` at `index`, truncating ints to the component type.
+  pub fn store(&mut self, index: usize, value: MistValue) {
+    match self {
+      Self::Boolean(elements) => {
+        elements[index] = i32::from(value) & 1 != 0;
+      }
+      Self::Char(elements) => elements[index] = i32::from(value) as u16,
+      Self::Float(elements) => elements[index] = value.into(),
+      Self::Double(elements) => elements[index] = value.into(),
+      Self::Byte(elements) => elements[index] = i32::from(value) as i8,
+      Self::Short(elements) => elements[index] = i32::from(value) as i16,
+      Self::Int(elements) => elements[index] = value.into(),
+      Self::Long(elements) => elements[index] = value.into(),
+      Self::Reference { elements, .. } => elements[index] = value.into(),
     }
   }
 }
 
 impl Heap {
-  pub fn new() -> Self {
+  pub fn new(array_class: usize) -> Self {
     Self {
       memory: vec![Object::null()],
+      array_class,
     }
   }
 }
 
 impl Heap {
-  pub fn newarray(&mut self, _atype: u8, count: i32) -> usize {
+  /// Allocates an object of the class numbered `class`, with its fields set
+  /// to `mem`.
+  pub fn alloc(&mut self, class: usize, mem: Vec<MistValue>) -> usize {
+    self.push(class, Memory::Fields(mem))
+  }
+
+  /// Allocates an array holding `elements`.
+  pub fn alloc_array(&mut self, elements: Array) -> usize {
+    self.push(self.array_class, Memory::Array(elements))
+  }
+
+  fn push(&mut self, class: usize, mem: Memory) -> usize {
     let id = self.memory.len();
-    self.memory.push(Object {
-      id,
-      class: 0, // FIXME
-      mem: vec![MistValue::Integer(0); count as usize],
-    });
+    self.memory.push(Object { id, class, mem });
     id
   }
 
-  pub fn iastore(&mut self, arrayref: usize, index: i32, value: i32) {
-    let object = &mut self.memory[arrayref];
-    object.mem[index as usize] = MistValue::Integer(value);
+  /// The [crate::class::Class::id] of the class of `objectref`.
+  pub fn class_of(&self, objectref: usize) -> usize {
+    self.memory[objectref].class
+  }
+
+  pub fn get_field(&self, objectref: usize, offset: usize) -> MistValue {
+    self.fields(objectref)[offset]
+  }
+
+  pub fn put_field(
+    &mut self,
+    objectref: usize,
+    offset: usize,
+    value: MistValue,
+  ) {
+    let Memory::Fields(fields) = &mut self.memory[objectref].mem else {
+      panic!("{objectref} is an array, not a class instance");
+    };
+    fields[offset] = value;
+  }
+
+  fn fields(&self, objectref: usize) -> &[MistValue] {
+    let Memory::Fields(fields) = &self.memory[objectref].mem else {
+      panic!("{objectref} is an array, not a class instance");
+    };
+    fields
+  }
+
+  /// The elements of `objectref`, unless it is a class instance.
+  pub fn as_array(&self, objectref: usize) -> Option<&Array> {
+    match &self.memory[objectref].mem {
+      Memory::Array(elements) => Some(elements),
+      Memory::Fields(_) => None,
+    }
   }
 
-  pub fn get(&self, arrayref: usize, index: i32) -> MistValue {
-    self.memory[arrayref].mem[index as usize]
+  /// The elements of the array `arrayref`.
+  pub fn array(&self, arrayref: usize) -> &Array {
+    self
+      .as_array(arrayref)
+      .unwrap_or_else(|| panic!("{arrayref} is a class instance, not an array"))
   }
+
+  pub fn array_mut(&mut self, arrayref: usize) -> &mut Array {
+    let Memory::Array(elements) = &mut self.memory[arrayref].mem else {
+      panic!("{arrayref} is a class instance, not an array");
+    };
+    elements
+  }
+
+  /// Allocates an array of `count` elements of the primitive type `atype`.
+  pub fn newarray(
+    &mut self,
+    atype: u8,
+    count: i32,
+  ) -> Result<usize, RuntimeError> {
+    let base_type = match atype {
+      T_BOOLEAN => BaseType::Boolean,
+      T_CHAR => BaseType::Char,
+      T_FLOAT => BaseType::Float,
+      T_DOUBLE => BaseType::Double,
+      T_BYTE => BaseType::Byte,
+      T_SHORT => BaseType::Short,
+      T_INT => BaseType::Int,
+      T_LONG => BaseType::Long,
+      _ => {
+        return Err(RuntimeError::new(
+          "java/lang/VerifyError",
+          format!("Bad array type {atype}"),
+        ))
+      }
+    };
+    self.anewarray(FieldType::Base(base_type), count)
+  }
+
+  /// Allocates an array of `count` elements of type `component`.
+  pub fn anewarray(
+    &mut self,
+    component: FieldType,
+    count: i32,
+  ) -> Result<usize, RuntimeError> {
+    let length = array_length(count)?;
+    Ok(self.alloc_array(Array::new(component, length)))
+  }
+
+  /// Allocates an array of type `array_type` whose first `counts.len()`
+  /// dimensions have the given lengths, leaving deeper dimensions null.
+  pub fn multianewarray(
+    &mut self,
+    array_type: &FieldType,
+    counts: &[i32],
+  ) -> Result<usize, RuntimeError> {
+    let lengths = counts
+      .iter()
+      .map(|&count| array_length(count))
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok(self.nested_array(array_type, &lengths))
+  }
+
+  fn nested_array(
+    &mut self,
+    array_type: &FieldType,
+    lengths: &[usize],
+  ) -> usize {
+    let FieldType::Array(component) = array_type else {
+      panic!("{array_type} is not an array type");
+    };
+    let (&length, deeper) = lengths.split_first().expect("A dimension.");
+
+    let mut array = Array::new(FieldType::clone(component), length);
+    if !deeper.is_empty() {
+      for index in 0..length {
+        let element = self.nested_array(component, deeper);
+        array.store(index, MistValue::ObjectReference(element));
+      }
+    }
+    self.alloc_array(array)
+  }
+
+  /// The element at `index` of the array `arrayref`.
+  pub fn load(
+    &self,
+    arrayref: usize,
+    index: i32,
+  ) -> Result<MistValue, RuntimeError> {
+    let index = self.index(arrayref, index)?;
+    Ok(self.array(arrayref).load(index))
+  }
+
+  /// Stores `value` at `index` of the array `arrayref`.
+  pub fn store(
+    &mut self,
+    arrayref: usize,
+    index: i32,
+    value: MistValue,
+  ) -> Result<(), RuntimeError> {
+    let index = self.index(arrayref, index)?;
+    self.array_mut(arrayref).store(index, value);
+    Ok(())
+  }
+
+  /// `index` as an offset into the array `arrayref`, if it is in bounds.
+  pub fn index(
+    &self,
+    arrayref: usize,
+    index: i32,
+  ) -> Result<usize, RuntimeError> {
+    let length = self.array(arrayref).len();
+    match usize::try_from(index) {
+      Ok(offset) if offset < length => Ok(offset),
+      _ => Err(RuntimeError::new(
+        "java/lang/ArrayIndexOutOfBoundsException",
+        format!("Index {index} out of bounds for length {length}"),
+      )),
+    }
+  }
+}
+
+/// `count` as the length of a new array, unless it is negative.
+fn array_length(count: i32) -> Result<usize, RuntimeError> {
+  usize::try_from(count).map_err(|_| {
+    RuntimeError::new("java/lang/NegativeArraySizeException", count.to_string())
+  })
 }
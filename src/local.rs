@@ -11,9 +11,18 @@ impl Local {
     Self { variables }
   }
 
+  /// Stores `variable` at `index`, growing to hold it if the frame was made
+  /// with fewer variables. Longs and doubles also take up `index + 1`.
   #[inline(always)]
   pub fn store(&mut self, index: usize, variable: MistValue) {
+    let end = index + variable.category();
+    if end > self.variables.len() {
+      self.variables.resize(end, MistValue::default());
+    }
     self.variables[index] = variable;
+    if end > index + 1 {
+      self.variables[index + 1] = MistValue::default();
+    }
   }
 
   #[inline(always)]
@@ -25,6 +34,6 @@ impl Local {
   #[inline(always)]
   pub fn iinc(&mut self, index: usize, r#const: i32) {
     let int: i32 = self.variables[index].into();
-    self.variables[index] = MistValue::Integer(int + r#const);
+    self.variables[index] = MistValue::Integer(int.wrapping_add(r#const));
   }
 }
@@ -1,35 +1,92 @@
-use std::{env, fs::File};
+use std::{env, error::Error, path::Path, process};
 
-use crate::run::RuntimeContext;
+use crate::{
+  classpath::{zip::ZipArchive, Classpath},
+  run::RuntimeContext,
+};
 
 pub mod class;
+pub mod classpath;
+pub mod heap;
 pub mod local;
 pub mod opcode;
 pub mod run;
 pub mod stack;
 pub mod value;
-pub mod heap;
+
+const USAGE: &str = "\
+usage: mist [-cp <class search path>] <main class> [args...]
+       mist -jar <jar file> [args...]";
 
 fn main() {
   let args = env::args().collect::<Vec<String>>();
 
   if let Err(e) = run(&args) {
     eprintln!("{e}");
+    process::exit(1);
   }
 }
 
-fn run(args: &[String]) -> std::io::Result<()> {
-  let class_file = File::open(format!("{}.class", &args[1]))?;
-  let mut reader = class::Reader::new(class_file);
-  let class = reader.read_class()?;
-  let class_name = class.this_class.clone();
-  // println!("{class:?}");
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+  let mut classpath = None;
+  let mut main_class = None;
+  let mut args = args.iter().skip(1);
+
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "-cp" | "-classpath" | "--classpath" | "--class-path" => {
+        let path = args.next().ok_or(USAGE)?;
+        classpath = Some(Classpath::parse(path)?);
+      }
+      "-jar" => {
+        let jar = args.next().ok_or(USAGE)?;
+        let archive = ZipArchive::open(Path::new(jar))?;
+        let name = classpath::main_class(&archive)?
+          .ok_or_else(|| format!("no main manifest attribute, in {jar}"))?;
+
+        let mut jar_classpath = Classpath::default();
+        jar_classpath.push(Path::new(jar))?;
+        classpath = Some(jar_classpath);
+        main_class = Some(name);
+        break;
+      }
+      _ => {
+        main_class = Some(arg.replace('.', "/"));
+        break;
+      }
+    }
+  }
+
+  let Some(main_class) = main_class else {
+    return Err(USAGE.into());
+  };
+  let classpath = match classpath {
+    Some(classpath) => classpath,
+    None => Classpath::parse(&env::var("CLASSPATH").unwrap_or(".".into()))?,
+  };
 
   let mut ctx = class::context::Context::new();
-  ctx.add_class(class);
+  ctx.add_loader(classpath);
 
-  let result = RuntimeContext::boot(&ctx, &class_name);
-  println!("result: {result:?}");
+  if let Err(error) = ctx.lookup_class(&main_class) {
+    return Err(
+      format!(
+        "Error: Could not find or load main class {}\nCaused by: {}: {error}",
+        main_class.replace('/', "."),
+        error.exception_class().replace('/', "."),
+      )
+      .into(),
+    );
+  }
 
-  Ok(())
+  let mut rt = RuntimeContext::new(&mut ctx);
+  match rt.boot(&main_class, &args.cloned().collect::<Vec<_>>()) {
+    Ok(result) => {
+      println!("result: {result:?}");
+      Ok(())
+    }
+    Err(error) => {
+      Err(format!("Exception in thread \"main\" {}", rt.describe(error)).into())
+    }
+  }
 }
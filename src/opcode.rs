@@ -4,10 +4,10 @@ pub type Opcode = u8;
 pub const ACONST_NULL: Opcode = 0x01;
 
 /// Load reference from array.
-// pub const AALOAD: Opcode = 0x32;
+pub const AALOAD: Opcode = 0x32;
 
 /// Store into reference array.
-// pub const AASTORE: Opcode = 0x53;
+pub const AASTORE: Opcode = 0x53;
 
 /// Load reference from local variable.
 pub const ALOAD: Opcode = 0x19;
@@ -17,13 +17,13 @@ pub const ALOAD_2: Opcode = 0x2c;
 pub const ALOAD_3: Opcode = 0x2d;
 
 /// Create a new array of reference.
-// pub const ANEWARRAY: Opcode = 0xbd;
+pub const ANEWARRAY: Opcode = 0xbd;
 
 /// Return reference from method.
 pub const ARETURN: Opcode = 0xb0;
 
 /// Get length of array.
-// pub const ARRAYLENGTH: Opcode = 0xbe;
+pub const ARRAYLENGTH: Opcode = 0xbe;
 
 /// Store reference into local variable.
 pub const ASTORE: Opcode = 0x3a;
@@ -427,7 +427,7 @@ pub const LMUL: Opcode = 0x69;
 pub const LNEG: Opcode = 0x75;
 
 /// Access jump table by key match and jump.
-pub const LOOKUPSWTICH: Opcode = 0xab;
+pub const LOOKUPSWITCH: Opcode = 0xab;
 
 /// Boolean OR long.
 pub const LOR: Opcode = 0x81;
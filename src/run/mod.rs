@@ -0,0 +1,2187 @@
+use std::{
+  cmp::Ordering,
+  collections::HashMap,
+  io::{self, BufWriter, Stderr, Stdout, Write},
+  ops::ControlFlow,
+  rc::Rc,
+};
+
+use crate::{
+  class::{
+    self,
+    context::LoadError,
+    descriptor::{BaseType, FieldType},
+    native::{
+      array,
+      java::{
+        io::print_stream,
+        lang::{
+          class as java_lang_class,
+          invoke::string_concat_factory::{self, Recipe},
+          stack_trace_element, string, throwable,
+        },
+        util::random::Random,
+      },
+    },
+    pool::{ConstantPool, Dispatch, Entry, PoolError, Resolved},
+    Class, InitState, Selected,
+  },
+  heap::{Array, Heap},
+  local::Local,
+  opcode,
+  stack::MistStack,
+  value::MistValue,
+};
+
+mod null_pointer;
+
+const MAIN: &str = "main";
+const MAIN_DESCRIPTOR: &str = "([Ljava/lang/String;)I";
+
+/// A Java exception on its way up the frame stack.
+#[derive(Debug)]
+pub enum RuntimeError {
+  /// Raised by the runtime, and only allocated once it is caught or reported.
+  Raised {
+    /// Binary name of the exception class, such as `java/lang/LinkageError`.
+    class_name: String,
+    message: String,
+  },
+  /// A `java.lang.Throwable` on the heap.
+  Thrown(usize),
+}
+
+impl RuntimeError {
+  pub fn new(class_name: &str, message: impl Into<String>) -> Self {
+    Self::Raised {
+      class_name: class_name.to_string(),
+      message: message.into(),
+    }
+  }
+}
+
+impl From<LoadError> for RuntimeError {
+  fn from(error: LoadError) -> Self {
+    Self::new(error.exception_class(), error.to_string())
+  }
+}
+
+impl From<PoolError> for RuntimeError {
+  fn from(error: PoolError) -> Self {
+    Self::new("java/lang/ClassFormatError", error.to_string())
+  }
+}
+
+/// An activation record of a method being executed.
+#[derive(Debug)]
+pub struct Frame {
+  /// The class that owns the method, and with it the constant pool.
+  pub class: Rc<Class>,
+  /// Index of the method in [Class::methods].
+  pub method: usize,
+  pub pc: usize,
+  pub local: Local,
+  pub stack: MistStack,
+}
+
+impl Frame {
+  pub fn new(class: Rc<Class>, method: usize, local: Local) -> Self {
+    let code = class.methods[method].code().expect("Method has code.");
+    let stack = MistStack::new(code.max_stack as usize);
+    Self {
+      class,
+      method,
+      pc: 0,
+      local,
+      stack,
+    }
+  }
+}
+
+pub struct RuntimeContext<'ctx> {
+  context: &'ctx mut class::Context,
+  heap: Heap,
+  frames: Vec<Frame>,
+  /// The canonical `java.lang.String` for each sequence of UTF-16 code units.
+  interned: HashMap<Vec<u16>, usize>,
+  /// The `java.lang.Class` of each type, created on first use.
+  mirrors: HashMap<FieldType, usize>,
+  /// The wrappers `valueOf` shares between boxings of the same small value,
+  /// by primitive type and value.
+  boxes: HashMap<(BaseType, i64), usize>,
+  /// What `System.out` and `System.err` print, until it is flushed.
+  stdout: BufWriter<Stdout>,
+  stderr: BufWriter<Stderr>,
+  /// The generator of `Math.random`, created on first use.
+  random: Option<Random>,
+}
+
+impl<'ctx> RuntimeContext<'ctx> {
+  pub fn new(context: &'ctx mut class::Context) -> Self {
+    let array_class = context
+      .lookup_class(array::CLASS)
+      .expect("The array class is built in.");
+    Self {
+      context,
+      heap: Heap::new(array_class.id),
+      frames: Vec::new(),
+      interned: HashMap::new(),
+      mirrors: HashMap::new(),
+      boxes: HashMap::new(),
+      stdout: BufWriter::new(io::stdout()),
+      stderr: BufWriter::new(io::stderr()),
+      random: None,
+    }
+  }
+
+  /// Runs the `main` method of `main_class`, passing it `args`.
+  pub fn boot(
+    &mut self,
+    main_class: &str,
+    args: &[String],
+  ) -> Result<Option<MistValue>, RuntimeError> {
+    let this_class = self.context.lookup_class(main_class)?;
+    let Some(main) = this_class.method_index(MAIN, MAIN_DESCRIPTOR) else {
+      return Ok(None);
+    };
+    let Some(code) = this_class.methods[main].code() else {
+      return Ok(None);
+    };
+
+    let args = args.iter().map(|arg| self.new_string(arg)).collect();
+    let args = self.heap.alloc_array(Array::Reference {
+      component: FieldType::Object(String::from("java/lang/String")),
+      elements: args,
+    });
+    let mut local = Local::new(code.max_local as usize);
+    local.store(0, MistValue::ObjectReference(args));
+
+    let result = self
+      .initialize(&this_class)
+      .and_then(|()| self.call(this_class, main, local));
+    self.flush();
+    result
+  }
+
+  pub fn heap(&mut self) -> &mut Heap {
+    &mut self.heap
+  }
+
+  /// Finds `class_name`, loading it on first reference.
+  pub fn lookup_class(
+    &mut self,
+    class_name: &str,
+  ) -> Result<Rc<Class>, RuntimeError> {
+    Ok(self.context.lookup_class(class_name)?)
+  }
+
+  /// Runs `class.methods[method]` to completion on top of the current frames
+  /// and yields what it returns.
+  fn call(
+    &mut self,
+    class: Rc<Class>,
+    method: usize,
+    local: Local,
+  ) -> Result<Option<MistValue>, RuntimeError> {
+    let code = class.methods[method].code().expect("Method has code.");
+    if let Some(native) = &code.native {
+      return Rc::clone(native)(self, &local);
+    }
+
+    let base = self.frames.len();
+    self.frames.push(Frame::new(class, method, local));
+    self.execute(base)
+  }
+
+  /// Initializes `class` unless that is done or already underway (JVMS §5.5):
+  /// its superclass first, then its constant fields, then `<clinit>`.
+  fn initialize(&mut self, class: &Rc<Class>) -> Result<(), RuntimeError> {
+    match class.state.get() {
+      InitState::BeingInitialized | InitState::Initialized => return Ok(()),
+      InitState::Erroneous => {
+        return Err(RuntimeError::new(
+          "java/lang/NoClassDefFoundError",
+          format!(
+            "Could not initialize class {}",
+            class.this_class.replace('/', ".")
+          ),
+        ))
+      }
+      InitState::Uninitialized => {}
+    }
+
+    class.state.set(InitState::BeingInitialized);
+    let result = self.run_initializers(class);
+    class.state.set(match result {
+      Ok(()) => InitState::Initialized,
+      Err(_) => InitState::Erroneous,
+    });
+    result
+  }
+
+  fn run_initializers(
+    &mut self,
+    class: &Rc<Class>,
+  ) -> Result<(), RuntimeError> {
+    if !class.is_interface() {
+      if let Some(superclass) = &class.superclass {
+        self.initialize(superclass)?;
+      }
+
+      for interface in class.all_interfaces() {
+        let has_defaults = interface
+          .methods
+          .iter()
+          .any(|method| !method.is_abstract() && !method.is_static());
+        if has_defaults {
+          self.initialize(&interface)?;
+        }
+      }
+    }
+
+    for (field, field_info) in class.fields.iter().enumerate() {
+      let Some(index) = field_info.constant_value() else {
+        continue;
+      };
+      if !field_info.is_static() {
+        continue;
+      }
+      let value = self.constant_value(&class.constant_pool, index)?;
+      class.statics.borrow_mut()[class.slots[field]] = value;
+    }
+
+    if let Some(clinit) = class.method_index("<clinit>", "()V") {
+      let code = class.methods[clinit].code().expect("Method has code.");
+      let local = Local::new(code.max_local as usize);
+      if let Err(error) = self.call(Rc::clone(class), clinit, local) {
+        return Err(self.in_initializer(error));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Pops the arguments of `class.methods[method]` off the current frame and
+  /// either calls it natively or pushes a new frame for it.
+  fn invoke(
+    &mut self,
+    class: Rc<Class>,
+    method: usize,
+  ) -> Result<(), RuntimeError> {
+    let method_info = &class.methods[method];
+    let Some(code) = method_info.code() else {
+      let error = if method_info.is_native() {
+        "java/lang/UnsatisfiedLinkError"
+      } else {
+        "java/lang/AbstractMethodError"
+      };
+      return Err(RuntimeError::new(
+        error,
+        format!(
+          "{}.{}{}",
+          class.this_class, method_info.name, method_info.descriptor
+        ),
+      ));
+    };
+    let caller = self.frames.last_mut().expect("Caller frame.");
+
+    let mut local = Local::new(code.max_local as usize);
+    let mut slot = method_info.argument_slots();
+    for _ in 0..method_info.argument_count() {
+      let argument = caller.stack.pop();
+      slot -= argument.category();
+      local.store(slot, argument);
+    }
+
+    if let Some(native) = &code.native {
+      if let Some(ret) = Rc::clone(native)(self, &local)? {
+        self.stack().push(ret);
+      }
+    } else {
+      let frame = Frame::new(Rc::clone(&class), method, local);
+      self.frames.push(frame);
+    }
+    Ok(())
+  }
+
+  /// Finds the method referenced by `index` (JVMS §5.4.3.3).
+  fn lookup_method(
+    &mut self,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<(Rc<Class>, usize), RuntimeError> {
+    let (class_name, method_name, descriptor) =
+      constant_pool.method_ref(index)?;
+
+    self
+      .context
+      .lookup_method(class_name, method_name, descriptor)?
+      .ok_or_else(|| {
+        RuntimeError::new(
+          "java/lang/NoSuchMethodError",
+          format!("{class_name}.{method_name}{descriptor}"),
+        )
+      })
+  }
+
+  /// The static method referenced by `index`, looked up only on the first
+  /// call.
+  fn resolve_static(
+    &mut self,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<(Rc<Class>, usize), RuntimeError> {
+    if let Some(Resolved::Method { class, method }) =
+      constant_pool.resolved(index)
+    {
+      return Ok((class, method));
+    }
+
+    let (class, method) = self.lookup_method(constant_pool, index)?;
+    if !class.methods[method].is_static() {
+      return Err(incompatible_method(&class, method, "static"));
+    }
+
+    let resolved = Resolved::Method {
+      class: Rc::clone(&class),
+      method,
+    };
+    constant_pool.resolve(index, resolved);
+
+    Ok((class, method))
+  }
+
+  /// The method `invokespecial` at `index` calls from `current`: the one
+  /// referenced, or for a call to a superclass method, the one `current`
+  /// inherits (JVMS §6.5.invokespecial).
+  fn resolve_special(
+    &mut self,
+    current: &Class,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<(Rc<Class>, usize), RuntimeError> {
+    if let Some(Resolved::Method { class, method }) =
+      constant_pool.resolved(index)
+    {
+      return Ok((class, method));
+    }
+
+    let (mut class, mut method) = self.lookup_method(constant_pool, index)?;
+    let method_info = &class.methods[method];
+    if method_info.is_static() {
+      return Err(incompatible_method(&class, method, "non-static"));
+    }
+
+    if method_info.name != "<init>"
+      && !class.is_interface()
+      && current.is_subclass_of(&class)
+    {
+      let (method_name, descriptor) =
+        (method_info.name.clone(), method_info.descriptor.clone());
+      (class, method) = self
+        .context
+        .lookup_method(&current.super_class, &method_name, &descriptor)?
+        .expect("The superclass inherits the resolved method.");
+    }
+
+    let resolved = Resolved::Method {
+      class: Rc::clone(&class),
+      method,
+    };
+    constant_pool.resolve(index, resolved);
+
+    Ok((class, method))
+  }
+
+  /// The method referenced by `index` for `invokevirtual` or
+  /// `invokeinterface`, and how to dispatch on the receiver.
+  fn resolve_virtual(
+    &mut self,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<(Rc<Class>, usize, Dispatch), RuntimeError> {
+    if let Some(Resolved::Virtual {
+      class,
+      method,
+      dispatch,
+    }) = constant_pool.resolved(index)
+    {
+      return Ok((class, method, dispatch));
+    }
+
+    let (class, method) = self.lookup_method(constant_pool, index)?;
+    let method_info = &class.methods[method];
+    if method_info.is_static() {
+      return Err(incompatible_method(&class, method, "non-static"));
+    }
+
+    let dispatch = if method_info.is_private() {
+      Dispatch::Direct
+    } else if class.is_interface() {
+      Dispatch::Interface
+    } else {
+      let slot = class
+        .vtable
+        .iter()
+        .position(|&entry| entry == (class.id, method));
+      Dispatch::Virtual(slot.expect("Instance methods have a vtable slot."))
+    };
+
+    let resolved = Resolved::Virtual {
+      class: Rc::clone(&class),
+      method,
+      dispatch,
+    };
+    constant_pool.resolve(index, resolved);
+
+    Ok((class, method, dispatch))
+  }
+
+  /// The method that a call resolved to `class.methods[method]` runs on
+  /// `objectref`.
+  fn select(
+    &self,
+    class: Rc<Class>,
+    method: usize,
+    dispatch: Dispatch,
+    objectref: usize,
+  ) -> Result<(Rc<Class>, usize), RuntimeError> {
+    let receiver = self.context.class_by_id(self.heap.class_of(objectref));
+
+    let (id, method) = match dispatch {
+      Dispatch::Direct => return Ok((class, method)),
+      Dispatch::Virtual(slot) => receiver.vtable[slot],
+      Dispatch::Interface => {
+        let Some(itable) = receiver.itable.get(&class.id) else {
+          return Err(RuntimeError::new(
+            "java/lang/IncompatibleClassChangeError",
+            format!(
+              "Class {} does not implement the requested interface {}",
+              receiver.this_class.replace('/', "."),
+              class.this_class.replace('/', ".")
+            ),
+          ));
+        };
+
+        let method_info = &class.methods[method];
+        match itable[method] {
+          Selected::Method(id, method) => (id, method),
+          Selected::Abstract => {
+            return Err(RuntimeError::new(
+              "java/lang/AbstractMethodError",
+              format!(
+                "Receiver class {} does not define or inherit an \
+                 implementation of the resolved method {}{} of interface {}.",
+                receiver.this_class.replace('/', "."),
+                method_info.name,
+                method_info.descriptor,
+                class.this_class.replace('/', ".")
+              ),
+            ))
+          }
+          Selected::Conflict => {
+            return Err(RuntimeError::new(
+              "java/lang/IncompatibleClassChangeError",
+              format!(
+                "Conflicting default methods: {}{}",
+                method_info.name, method_info.descriptor
+              ),
+            ))
+          }
+        }
+      }
+    };
+
+    Ok((self.context.class_by_id(id), method))
+  }
+
+  /// Checks that the receiver of a call to `class.methods[method]` is not
+  /// null, and yields it.
+  fn receiver(
+    &mut self,
+    class: &Class,
+    method: usize,
+  ) -> Result<usize, RuntimeError> {
+    let depth = class.methods[method].argument_count() - 1;
+    match self.stack().peek(depth) {
+      MistValue::ObjectReference(0) => Err(self.null_pointer()),
+      objectref => Ok(objectref.into()),
+    }
+  }
+
+  /// Pops an array reference and an index, and pushes the element there.
+  fn array_load(&mut self) -> Result<(), RuntimeError> {
+    let stack = self.stack();
+    let index: i32 = stack.pop().into();
+    let arrayref: usize = stack.pop().into();
+    if arrayref == 0 {
+      return Err(self.null_pointer());
+    }
+
+    let value = self.heap.load(arrayref, index)?;
+    self.stack().push(value);
+    Ok(())
+  }
+
+  /// Pops an array reference, an index and a value, and stores the value in
+  /// the array.
+  fn array_store(&mut self) -> Result<(), RuntimeError> {
+    let stack = self.stack();
+    let value = stack.pop();
+    let index: i32 = stack.pop().into();
+    let arrayref: usize = stack.pop().into();
+    if arrayref == 0 {
+      return Err(self.null_pointer());
+    }
+
+    self.heap.store(arrayref, index, value)
+  }
+
+  /// [opcode::AASTORE], which also checks that the value is an instance of
+  /// the component type of the array.
+  fn aastore(&mut self) -> Result<(), RuntimeError> {
+    let stack = self.stack();
+    let value: usize = stack.pop().into();
+    let index: i32 = stack.pop().into();
+    let arrayref: usize = stack.pop().into();
+    if arrayref == 0 {
+      return Err(self.null_pointer());
+    }
+
+    self.heap.index(arrayref, index)?;
+    if value != 0 {
+      let component = self.heap.array(arrayref).component();
+      let value_type = self.type_of(value);
+      if !self.is_assignable(&value_type, &component) {
+        return Err(RuntimeError::new(
+          "java/lang/ArrayStoreException",
+          value_type.class_name(),
+        ));
+      }
+    }
+    self
+      .heap
+      .store(arrayref, index, MistValue::ObjectReference(value))
+  }
+
+  /// A `NullPointerException` for the instruction the running frame is at.
+  fn null_pointer(&self) -> RuntimeError {
+    let frame = self.frames.last().expect("A running frame.");
+    let message =
+      null_pointer::message(&frame.class, frame.method, frame.pc - 1);
+    RuntimeError::new(
+      "java/lang/NullPointerException",
+      message.unwrap_or_default(),
+    )
+  }
+
+  /// The class referenced by `index`, looked up only on the first use.
+  fn resolve_class(
+    &mut self,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<Rc<Class>, RuntimeError> {
+    if let Some(Resolved::Class(class)) = constant_pool.resolved(index) {
+      return Ok(class);
+    }
+
+    let class = self
+      .context
+      .lookup_class(constant_pool.class_name(index)?)?;
+    constant_pool.resolve(index, Resolved::Class(Rc::clone(&class)));
+
+    Ok(class)
+  }
+
+  /// The class or array type referenced by `index`, after loading the class
+  /// or the element class of the array.
+  fn resolve_type(
+    &mut self,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<FieldType, RuntimeError> {
+    let class_name = constant_pool.class_name(index)?;
+    if !class_name.starts_with('[') {
+      let class = self.resolve_class(constant_pool, index)?;
+      return Ok(FieldType::Object(class.this_class.clone()));
+    }
+
+    let array_type = FieldType::parse(class_name).map_err(|error| {
+      RuntimeError::new("java/lang/NoClassDefFoundError", error.to_string())
+    })?;
+    let mut element = &array_type;
+    while let FieldType::Array(component) = element {
+      element = component;
+    }
+    if let FieldType::Object(class_name) = element {
+      self.context.lookup_class(class_name)?;
+    }
+    Ok(array_type)
+  }
+
+  /// The offset of the instance field referenced by `index`, looked up only
+  /// on the first use.
+  fn resolve_field(
+    &mut self,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<usize, RuntimeError> {
+    if let Some(Resolved::Field { offset }) = constant_pool.resolved(index) {
+      return Ok(offset);
+    }
+
+    let (class_name, field_name, descriptor) =
+      constant_pool.field_ref(index)?;
+
+    let Some((class, field)) = self
+      .context
+      .lookup_field(class_name, field_name, descriptor)?
+    else {
+      return Err(RuntimeError::new("java/lang/NoSuchFieldError", field_name));
+    };
+    if class.fields[field].is_static() {
+      return Err(RuntimeError::new(
+        "java/lang/IncompatibleClassChangeError",
+        format!("Expected non-static field {class_name}.{field_name}"),
+      ));
+    }
+
+    let offset = class.slots[field];
+    constant_pool.resolve(index, Resolved::Field { offset });
+
+    Ok(offset)
+  }
+
+  /// The static field referenced by `index`, looked up only on the first use.
+  fn resolve_static_field(
+    &mut self,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<(Rc<Class>, usize), RuntimeError> {
+    if let Some(Resolved::StaticField { class, field }) =
+      constant_pool.resolved(index)
+    {
+      return Ok((class, field));
+    }
+
+    let (class_name, field_name, descriptor) =
+      constant_pool.field_ref(index)?;
+
+    let Some((class, field)) = self
+      .context
+      .lookup_field(class_name, field_name, descriptor)?
+    else {
+      return Err(RuntimeError::new("java/lang/NoSuchFieldError", field_name));
+    };
+    if !class.fields[field].is_static() {
+      return Err(RuntimeError::new(
+        "java/lang/IncompatibleClassChangeError",
+        format!("Expected static field {class_name}.{field_name}"),
+      ));
+    }
+
+    let resolved = Resolved::StaticField {
+      class: Rc::clone(&class),
+      field,
+    };
+    constant_pool.resolve(index, resolved);
+
+    Ok((class, field))
+  }
+
+  /// What the `invokedynamic` call site at `index` in `class` links to,
+  /// bootstrapped only on the first execution. Only the string
+  /// concatenations of `StringConcatFactory` are supported.
+  fn resolve_call_site(
+    &mut self,
+    class: &Class,
+    index: u16,
+  ) -> Result<Rc<Recipe>, RuntimeError> {
+    let constant_pool = &class.constant_pool;
+    if let Some(Resolved::Concat(recipe)) = constant_pool.resolved(index) {
+      return Ok(recipe);
+    }
+
+    let (bootstrap, name, descriptor) = constant_pool.invoke_dynamic(index)?;
+    let Some(bootstrap) = class.bootstrap_methods().get(bootstrap as usize)
+    else {
+      return Err(RuntimeError::new(
+        "java/lang/ClassFormatError",
+        format!("Call site #{index} has no bootstrap method #{bootstrap}"),
+      ));
+    };
+    let (_, reference) =
+      constant_pool.method_handle(bootstrap.bootstrap_method_ref)?;
+    let (class_name, method_name, _) = constant_pool.method_ref(reference)?;
+    if class_name != string_concat_factory::CLASS {
+      return Err(RuntimeError::new(
+        "java/lang/BootstrapMethodError",
+        format!(
+          "Bootstrap method {class_name}.{method_name} of call site \
+           {name}{descriptor} is not supported"
+        ),
+      ));
+    }
+
+    let recipe = Rc::new(string_concat_factory::bootstrap(
+      constant_pool,
+      method_name,
+      descriptor,
+      &bootstrap.bootstrap_arguments,
+    )?);
+    constant_pool.resolve(index, Resolved::Concat(Rc::clone(&recipe)));
+    Ok(recipe)
+  }
+
+  /// The value pushed by [opcode::LDC] and [opcode::LDC_W].
+  fn ldc(
+    &mut self,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<MistValue, RuntimeError> {
+    let value = match constant_pool.get(index)? {
+      Entry::IntegerInfo { .. } => {
+        MistValue::Integer(constant_pool.integer(index)?)
+      }
+      Entry::FloatInfo { .. } => MistValue::Float(constant_pool.float(index)?),
+      Entry::StringInfo { .. } => {
+        if let Some(Resolved::String(string)) = constant_pool.resolved(index) {
+          return Ok(MistValue::ObjectReference(string));
+        }
+        let string = self.new_string(constant_pool.string(index)?);
+        let string = self.intern(string);
+        constant_pool.resolve(index, Resolved::String(string));
+        MistValue::ObjectReference(string)
+      }
+      Entry::ClassInfo { .. } => {
+        let field_type = self.resolve_type(constant_pool, index)?;
+        MistValue::ObjectReference(self.mirror(field_type))
+      }
+      Entry::MethodHandleInfo { .. } => {
+        return Err(unsupported_ldc("CONSTANT_MethodHandle"))
+      }
+      Entry::MethodTypeInfo { .. } => {
+        return Err(unsupported_ldc("CONSTANT_MethodType"))
+      }
+      Entry::DynamicInfo { .. } => {
+        return Err(unsupported_ldc("CONSTANT_Dynamic"))
+      }
+      _ => {
+        return Err(
+          PoolError {
+            index,
+            expected: "loadable constant",
+          }
+          .into(),
+        )
+      }
+    };
+    Ok(value)
+  }
+
+  /// The initial value a `ConstantValue` attribute at `index` gives a field.
+  fn constant_value(
+    &mut self,
+    constant_pool: &ConstantPool,
+    index: u16,
+  ) -> Result<MistValue, RuntimeError> {
+    match constant_pool.get(index)? {
+      Entry::LongInfo { .. } => Ok(MistValue::Long(constant_pool.long(index)?)),
+      Entry::DoubleInfo { .. } => {
+        Ok(MistValue::Double(constant_pool.double(index)?))
+      }
+      _ => self.ldc(constant_pool, index),
+    }
+  }
+
+  /// The operand stack of the running frame.
+  fn stack(&mut self) -> &mut MistStack {
+    &mut self.frames.last_mut().expect("A running frame.").stack
+  }
+
+  /// Pops the current frame and hands `ret` to the caller. Yields the result
+  /// of the whole execution once the frame at `base` returns.
+  fn return_from_method(
+    &mut self,
+    base: usize,
+    ret: Option<MistValue>,
+  ) -> ControlFlow<Option<MistValue>> {
+    self.frames.pop();
+    if self.frames.len() == base {
+      return ControlFlow::Break(ret);
+    }
+
+    if let Some(ret) = ret {
+      self.stack().push(ret);
+    }
+    ControlFlow::Continue(())
+  }
+}
+
+impl<'ctx> RuntimeContext<'ctx> {
+  /// Allocates a `java.lang.String` holding `string`.
+  pub fn new_string(&mut self, string: &str) -> usize {
+    let units = string.encode_utf16().collect::<Vec<_>>();
+    self.new_string_utf16(&units)
+  }
+
+  /// Allocates a `java.lang.String` holding the UTF-16 code units `units`,
+  /// stored one byte per character when they all fit in Latin-1.
+  pub fn new_string_utf16(&mut self, units: &[u16]) -> usize {
+    let class = self
+      .context
+      .lookup_class("java/lang/String")
+      .expect("java/lang/String is built in.");
+
+    let (bytes, coder) = if units.iter().all(|&unit| unit <= 0xff) {
+      let bytes = units.iter().map(|&unit| unit as u8 as i8).collect();
+      (bytes, string::LATIN1)
+    } else {
+      let bytes = units
+        .iter()
+        .flat_map(|unit| unit.to_ne_bytes())
+        .map(|byte| byte as i8)
+        .collect();
+      (bytes, string::UTF16)
+    };
+    let value = self.heap.alloc_array(Array::Byte(bytes));
+
+    let mut mem = class.layout.clone();
+    mem[string::VALUE] = MistValue::ObjectReference(value);
+    mem[string::CODER] = MistValue::Integer(coder);
+    self.heap.alloc(class.id, mem)
+  }
+
+  /// The UTF-16 code units of the `java.lang.String` at `string`, read in
+  /// place.
+  pub fn string_units(&self, string: usize) -> string::Units<'_> {
+    let value = self.heap.get_field(string, string::VALUE);
+    let Array::Byte(bytes) = self.heap.array(value.into()) else {
+      panic!("String.value is a byte[].");
+    };
+    let coder = self.heap.get_field(string, string::CODER);
+    string::Units::new(bytes, coder.into())
+  }
+
+  /// A copy of the UTF-16 code units of the `java.lang.String` at `string`.
+  pub fn string_utf16(&self, string: usize) -> Vec<u16> {
+    self.string_units(string).iter().collect()
+  }
+
+  /// The contents of the `java.lang.String` at `string`.
+  pub fn string(&self, string: usize) -> String {
+    char::decode_utf16(self.string_utf16(string))
+      .map(|chr| chr.unwrap_or(char::REPLACEMENT_CHARACTER))
+      .collect()
+  }
+
+  /// The canonical `java.lang.String` with the same contents as `string`,
+  /// which becomes canonical if there was none.
+  pub fn intern(&mut self, string: usize) -> usize {
+    let units = self.string_utf16(string);
+    *self.interned.entry(units).or_insert(string)
+  }
+
+  /// The `java.lang.Class` of `field_type`, which is the same object every
+  /// time.
+  pub fn mirror(&mut self, field_type: FieldType) -> usize {
+    if let Some(&mirror) = self.mirrors.get(&field_type) {
+      return mirror;
+    }
+    let name = self.new_string(&field_type.class_name());
+    let mirror = self.new_object(java_lang_class::CLASS);
+    self.heap.put_field(
+      mirror,
+      java_lang_class::NAME,
+      MistValue::ObjectReference(name),
+    );
+    self.mirrors.insert(field_type, mirror);
+    mirror
+  }
+
+  /// Allocates an instance of `class_name` with its fields at their default
+  /// values.
+  pub fn new_object(&mut self, class_name: &str) -> usize {
+    let class = self
+      .context
+      .lookup_class(class_name)
+      .expect("Classes the runtime instantiates are built in.");
+    self.heap.alloc(class.id, class.layout.clone())
+  }
+
+  /// Sets the static field `field_name` of `class_name`.
+  pub fn set_static(
+    &mut self,
+    class_name: &str,
+    field_name: &str,
+    value: MistValue,
+  ) {
+    let class = self
+      .context
+      .lookup_class(class_name)
+      .expect("Classes the runtime initializes are built in.");
+    let field = class
+      .fields
+      .iter()
+      .position(|field| field.name == field_name && field.is_static())
+      .expect("The static field is declared.");
+    class.statics.borrow_mut()[class.slots[field]] = value;
+  }
+
+  /// Calls the instance method `name` with `descriptor` that `objectref`
+  /// selects, passing it `args`.
+  pub fn call_virtual(
+    &mut self,
+    objectref: usize,
+    name: &str,
+    descriptor: &str,
+    args: &[MistValue],
+  ) -> Result<Option<MistValue>, RuntimeError> {
+    let receiver = self.context.class_by_id(self.heap.class_of(objectref));
+    let selected = receiver
+      .vtable
+      .iter()
+      .map(|&(id, method)| (self.context.class_by_id(id), method))
+      .find(|(class, method)| {
+        let method_info = &class.methods[*method];
+        method_info.name == name && method_info.descriptor == descriptor
+      });
+    let Some((class, method)) = selected else {
+      return Err(RuntimeError::new(
+        "java/lang/NoSuchMethodError",
+        format!("{}.{name}{descriptor}", receiver.this_class),
+      ));
+    };
+    let Some(code) = class.methods[method].code() else {
+      return Err(RuntimeError::new(
+        "java/lang/AbstractMethodError",
+        format!("{}.{name}{descriptor}", class.this_class),
+      ));
+    };
+
+    let mut local = Local::new(code.max_local as usize);
+    local.store(0, MistValue::ObjectReference(objectref));
+    let mut slot = 1;
+    for &arg in args {
+      local.store(slot, arg);
+      slot += arg.category();
+    }
+    self.call(class, method, local)
+  }
+
+  /// `String.valueOf(Object)`: "null", or what `toString` returns.
+  pub fn string_of(
+    &mut self,
+    objectref: usize,
+  ) -> Result<String, RuntimeError> {
+    if objectref == 0 {
+      return Ok(String::from("null"));
+    }
+    match self.call_virtual(
+      objectref,
+      "toString",
+      "()Ljava/lang/String;",
+      &[],
+    )? {
+      Some(MistValue::ObjectReference(0)) | None => Ok(String::from("null")),
+      Some(string) => Ok(self.string(string.into())),
+    }
+  }
+
+  /// Writes `text` to standard output or, for [print_stream::STDERR],
+  /// standard error, after flushing the other one so that the two stay in
+  /// order. Failures to write are ignored, as `PrintStream` does.
+  pub fn write(&mut self, fd: i32, text: &str) {
+    if fd == print_stream::STDERR {
+      _ = self.stdout.flush();
+      _ = self.stderr.write_all(text.as_bytes());
+    } else {
+      _ = self.stderr.flush();
+      _ = self.stdout.write_all(text.as_bytes());
+    }
+  }
+
+  pub fn boxes(&mut self) -> &mut HashMap<(BaseType, i64), usize> {
+    &mut self.boxes
+  }
+
+  pub fn random(&mut self) -> &mut Random {
+    self.random.get_or_insert_with(Random::from_time)
+  }
+
+  pub fn flush(&mut self) {
+    _ = self.stdout.flush();
+    _ = self.stderr.flush();
+  }
+
+  /// Whether `objectref` is an instance of `class_name` or of a subclass.
+  pub fn is_instance(&self, objectref: usize, class_name: &str) -> bool {
+    let class = self.context.class_by_id(self.heap.class_of(objectref));
+    let mut superclass = Some(&class);
+    while let Some(current) = superclass {
+      if current.this_class == class_name {
+        return true;
+      }
+      superclass = current.superclass.as_ref();
+    }
+    false
+  }
+
+  /// The class or array type of `objectref`.
+  pub fn type_of(&self, objectref: usize) -> FieldType {
+    match self.heap.as_array(objectref) {
+      Some(array) => FieldType::Array(Box::new(array.component())),
+      None => {
+        let class = self.context.class_by_id(self.heap.class_of(objectref));
+        FieldType::Object(class.this_class.clone())
+      }
+    }
+  }
+
+  /// Whether a value of type `source` can be used as a `target`, following
+  /// the rules of `checkcast` (JVMS §6.5).
+  fn is_assignable(&mut self, source: &FieldType, target: &FieldType) -> bool {
+    match (source, target) {
+      (FieldType::Base(source), FieldType::Base(target)) => source == target,
+      (FieldType::Object(source), FieldType::Object(target)) => {
+        let (Ok(source), Ok(target)) = (
+          self.context.lookup_class(source),
+          self.context.lookup_class(target),
+        ) else {
+          return false;
+        };
+        source.id == target.id
+          || source.is_subclass_of(&target)
+          || target.is_interface()
+            && source
+              .all_interfaces()
+              .iter()
+              .any(|interface| interface.id == target.id)
+      }
+      (FieldType::Array(_), FieldType::Object(target)) => matches!(
+        target.as_str(),
+        "java/lang/Object" | "java/lang/Cloneable" | "java/io/Serializable"
+      ),
+      (FieldType::Array(source), FieldType::Array(target)) => {
+        self.is_assignable(source, target)
+      }
+      _ => false,
+    }
+  }
+
+  /// Allocates an exception of class `class_name`, with `message` unless it
+  /// is empty.
+  fn new_throwable(&mut self, class_name: &str, message: &str) -> usize {
+    let class = self
+      .context
+      .lookup_class(class_name)
+      .expect("Exceptions raised by the runtime are built in.");
+    let throwable = self.heap.alloc(class.id, class.layout.clone());
+
+    if !message.is_empty() {
+      let message = self.new_string(message);
+      self.heap.put_field(
+        throwable,
+        throwable::DETAIL_MESSAGE,
+        MistValue::ObjectReference(message),
+      );
+    }
+    self.fill_in_stack_trace(throwable);
+    throwable
+  }
+
+  /// Records the frames `throwable` is being created in, from the innermost
+  /// out, leaving out its own constructors.
+  pub fn fill_in_stack_trace(&mut self, throwable: usize) {
+    let frames = self
+      .frames
+      .iter()
+      .rev()
+      .skip_while(|frame| {
+        frame.class.methods[frame.method].name == "<init>"
+          && self.is_instance(throwable, &frame.class.this_class)
+      })
+      .map(|frame| (Rc::clone(&frame.class), frame.method, frame.pc - 1))
+      .collect::<Vec<_>>();
+
+    let elements = frames
+      .into_iter()
+      .map(|(class, method, pc)| {
+        self.new_stack_trace_element(&class, method, pc)
+      })
+      .collect();
+    let stack_trace = self.heap.alloc_array(Array::Reference {
+      component: FieldType::Object(String::from("java/lang/StackTraceElement")),
+      elements,
+    });
+    self.heap.put_field(
+      throwable,
+      throwable::STACK_TRACE,
+      MistValue::ObjectReference(stack_trace),
+    );
+  }
+
+  /// Allocates a `java.lang.StackTraceElement` for the instruction at `pc` in
+  /// `class.methods[method]`.
+  fn new_stack_trace_element(
+    &mut self,
+    class: &Class,
+    method: usize,
+    pc: usize,
+  ) -> usize {
+    let element_class = self
+      .context
+      .lookup_class("java/lang/StackTraceElement")
+      .expect("java/lang/StackTraceElement is built in.");
+    let method = &class.methods[method];
+
+    let declaring_class = self.new_string(&class.this_class.replace('/', "."));
+    let method_name = self.new_string(&method.name);
+    let file_name = match class.source_file() {
+      Some(source_file) => self.new_string(source_file),
+      None => 0,
+    };
+    let line_number = method
+      .code()
+      .and_then(|code| code.line_number(pc))
+      .map_or(-1, i32::from);
+
+    let mut mem = element_class.layout.clone();
+    mem[stack_trace_element::DECLARING_CLASS] =
+      MistValue::ObjectReference(declaring_class);
+    mem[stack_trace_element::METHOD_NAME] =
+      MistValue::ObjectReference(method_name);
+    mem[stack_trace_element::FILE_NAME] = MistValue::ObjectReference(file_name);
+    mem[stack_trace_element::LINE_NUMBER] = MistValue::Integer(line_number);
+    self.heap.alloc(element_class.id, mem)
+  }
+
+  /// The heap object for `error`, allocating it if the runtime raised it.
+  fn exception(&mut self, error: RuntimeError) -> usize {
+    match error {
+      RuntimeError::Raised {
+        class_name,
+        message,
+      } => self.new_throwable(&class_name, &message),
+      RuntimeError::Thrown(exception) => exception,
+    }
+  }
+
+  /// What an exception thrown by `<clinit>` turns into (JVMS §5.5, step 11).
+  fn in_initializer(&mut self, error: RuntimeError) -> RuntimeError {
+    let exception = self.exception(error);
+    if self.is_instance(exception, "java/lang/Error") {
+      return RuntimeError::Thrown(exception);
+    }
+
+    let error = self.new_throwable("java/lang/ExceptionInInitializerError", "");
+    self.heap.put_field(
+      error,
+      throwable::CAUSE,
+      MistValue::ObjectReference(exception),
+    );
+    RuntimeError::Thrown(error)
+  }
+
+  /// Unwinds to the innermost frame above `base` with a handler for
+  /// `exception` and continues there. Returns false if there is none.
+  fn catch(&mut self, base: usize, exception: usize) -> bool {
+    while self.frames.len() > base {
+      let frame = self.frames.last().expect("A running frame.");
+      let class = Rc::clone(&frame.class);
+      let code = class.methods[frame.method]
+        .code()
+        .expect("Method has code.");
+
+      // Every frame is past the first byte of the instruction that threw or
+      // made the call, and not past its last.
+      let pc = frame.pc - 1;
+      let handler = code.exception_table.iter().find(|entry| {
+        let catches = match entry.catch_type {
+          0 => true,
+          catch_type => class
+            .constant_pool
+            .class_name(catch_type)
+            .is_ok_and(|class_name| self.is_instance(exception, class_name)),
+        };
+        (entry.start_pc as usize..entry.end_pc as usize).contains(&pc)
+          && catches
+      });
+
+      if let Some(handler) = handler {
+        let frame = self.frames.last_mut().expect("A running frame.");
+        frame.pc = handler.handler_pc as usize;
+        frame.stack.clear();
+        frame.stack.push(MistValue::ObjectReference(exception));
+        return true;
+      }
+      self.frames.pop();
+    }
+    false
+  }
+
+  /// What `Throwable.toString` returns for `throwable`.
+  pub fn throwable_string(&self, throwable: usize) -> String {
+    let class = self.context.class_by_id(self.heap.class_of(throwable));
+    let class_name = class.this_class.replace('/', ".");
+
+    match self.heap.get_field(throwable, throwable::DETAIL_MESSAGE) {
+      MistValue::ObjectReference(0) => class_name,
+      message => format!("{class_name}: {}", self.string(message.into())),
+    }
+  }
+
+  /// What `StackTraceElement.toString` returns for `element`, such as
+  /// `App.snd(App.java:9)`.
+  pub fn stack_trace_element_string(&self, element: usize) -> String {
+    let field = |offset| self.heap.get_field(element, offset);
+    let declaring_class =
+      self.string(field(stack_trace_element::DECLARING_CLASS).into());
+    let method_name =
+      self.string(field(stack_trace_element::METHOD_NAME).into());
+
+    let location = match field(stack_trace_element::FILE_NAME) {
+      MistValue::ObjectReference(0) => String::from("Unknown Source"),
+      file_name => match field(stack_trace_element::LINE_NUMBER) {
+        MistValue::Integer(line_number @ 0..) => {
+          format!("{}:{line_number}", self.string(file_name.into()))
+        }
+        _ => self.string(file_name.into()),
+      },
+    };
+    format!("{declaring_class}.{method_name}({location})")
+  }
+
+  /// The frames recorded for `throwable`, formatted like
+  /// [Self::stack_trace_element_string].
+  fn frames_of(&self, throwable: usize) -> Vec<String> {
+    match self.heap.get_field(throwable, throwable::STACK_TRACE) {
+      MistValue::ObjectReference(0) => Vec::new(),
+      stack_trace => {
+        let Array::Reference { elements, .. } =
+          self.heap.array(stack_trace.into())
+        else {
+          panic!("Throwable.stackTrace is a StackTraceElement[].");
+        };
+        elements
+          .iter()
+          .map(|&element| self.stack_trace_element_string(element))
+          .collect()
+      }
+    }
+  }
+
+  /// What `Throwable.printStackTrace` prints for `throwable`: its frames,
+  /// then each cause with the frames it shares with the one it caused
+  /// folded into `... n more`.
+  pub fn stack_trace(&self, throwable: usize) -> String {
+    let mut trace = self.throwable_string(throwable);
+    let mut frames = self.frames_of(throwable);
+    for frame in &frames {
+      trace.push_str(&format!("\n\tat {frame}"));
+    }
+
+    let mut seen = vec![throwable];
+    let mut exception = throwable;
+    loop {
+      let cause = self.heap.get_field(exception, throwable::CAUSE).into();
+      if cause == 0 || seen.contains(&cause) {
+        break trace;
+      }
+
+      let cause_frames = self.frames_of(cause);
+      let in_common = cause_frames
+        .iter()
+        .rev()
+        .zip(frames.iter().rev())
+        .take_while(|(frame, enclosing)| frame == enclosing)
+        .count();
+
+      trace.push_str("\nCaused by: ");
+      trace.push_str(&self.throwable_string(cause));
+      for frame in &cause_frames[..cause_frames.len() - in_common] {
+        trace.push_str(&format!("\n\tat {frame}"));
+      }
+      if in_common != 0 {
+        trace.push_str(&format!("\n\t... {in_common} more"));
+      }
+
+      seen.push(cause);
+      exception = cause;
+      frames = cause_frames;
+    }
+  }
+
+  /// Describes an uncaught `error` with its stack trace and causes.
+  pub fn describe(&mut self, error: RuntimeError) -> String {
+    let exception = self.exception(error);
+    self.stack_trace(exception)
+  }
+}
+
+impl<'ctx> RuntimeContext<'ctx> {
+  /// Runs until the frame at `base` returns, and yields its result.
+  fn execute(
+    &mut self,
+    base: usize,
+  ) -> Result<Option<MistValue>, RuntimeError> {
+    loop {
+      match self.step(base) {
+        Ok(ControlFlow::Continue(())) => {}
+        Ok(ControlFlow::Break(result)) => break Ok(result),
+        Err(error) => {
+          let exception = self.exception(error);
+          if !self.catch(base, exception) {
+            break Err(RuntimeError::Thrown(exception));
+          }
+        }
+      }
+    }
+  }
+
+  /// Executes a single instruction of the running frame.
+  fn step(
+    &mut self,
+    base: usize,
+  ) -> Result<ControlFlow<Option<MistValue>>, RuntimeError> {
+    let frame = self.frames.last_mut().expect("A frame to run.");
+    let class = Rc::clone(&frame.class);
+    let constant_pool = &class.constant_pool;
+    let program = &class.methods[frame.method]
+      .code()
+      .expect("Method has code.")
+      .code;
+    let Frame {
+      pc: ip,
+      local,
+      stack,
+      ..
+    } = frame;
+
+    let instruction = fetch(program, ip);
+
+    // println!("{instruction:x}");
+    match instruction {
+      opcode::AALOAD => self.array_load()?,
+
+      opcode::AASTORE => self.aastore()?,
+
+      opcode::ACONST_NULL => stack.aconst_null(),
+
+      opcode::ALOAD => {
+        let index = fetch(program, ip) as usize;
+        stack.push(local.load(index));
+      }
+      opcode::ALOAD_0 => stack.push(local.load(0)),
+      opcode::ALOAD_1 => stack.push(local.load(1)),
+      opcode::ALOAD_2 => stack.push(local.load(2)),
+      opcode::ALOAD_3 => stack.push(local.load(3)),
+
+      opcode::ANEWARRAY => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+        let count: i32 = stack.pop().into();
+
+        let component = self.resolve_type(constant_pool, index)?;
+        let r#ref = self.heap.anewarray(component, count)?;
+        self.stack().push(MistValue::ObjectReference(r#ref));
+      }
+
+      opcode::ARETURN => {
+        let ret = stack.pop();
+        return Ok(self.return_from_method(base, Some(ret)));
+      }
+
+      opcode::ASTORE => {
+        let index = fetch(program, ip);
+        local.store(index as usize, stack.pop());
+      }
+      opcode::ASTORE_0 => local.store(0, stack.pop()),
+      opcode::ASTORE_1 => local.store(1, stack.pop()),
+      opcode::ASTORE_2 => local.store(2, stack.pop()),
+      opcode::ASTORE_3 => local.store(3, stack.pop()),
+
+      opcode::ARRAYLENGTH => match stack.pop() {
+        MistValue::ObjectReference(0) => return Err(self.null_pointer()),
+        arrayref => {
+          let length = self.heap.array(arrayref.into()).len();
+          self.stack().iconst(length as i32);
+        }
+      },
+
+      opcode::ATHROW => match stack.pop() {
+        MistValue::ObjectReference(0) => return Err(self.null_pointer()),
+        objectref => return Err(RuntimeError::Thrown(objectref.into())),
+      },
+
+      opcode::BALOAD => self.array_load()?,
+
+      opcode::BASTORE => self.array_store()?,
+
+      opcode::BIPUSH => stack.iconst(fetch(program, ip) as i8 as i32),
+
+      opcode::CALOAD => self.array_load()?,
+
+      opcode::CASTORE => self.array_store()?,
+
+      opcode::CHECKCAST => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+        let objectref: usize = stack.peek(0).into();
+
+        let target = self.resolve_type(constant_pool, index)?;
+        if objectref != 0 {
+          let source = self.type_of(objectref);
+          if !self.is_assignable(&source, &target) {
+            return Err(RuntimeError::new(
+              "java/lang/ClassCastException",
+              format!(
+                "class {} cannot be cast to class {}",
+                source.class_name(),
+                target.class_name()
+              ),
+            ));
+          }
+        }
+      }
+
+      opcode::D2F => stack.d2f(),
+      opcode::D2I => stack.d2i(),
+      opcode::D2L => stack.d2l(),
+
+      opcode::DADD => stack.dadd(),
+
+      opcode::DALOAD => self.array_load()?,
+
+      opcode::DASTORE => self.array_store()?,
+
+      opcode::DCMPG => stack.dcmpg(),
+      opcode::DCMPL => stack.dcmpl(),
+
+      opcode::DCONST_0 => stack.dconst(0f64),
+      opcode::DCONST_1 => stack.dconst(1f64),
+
+      opcode::DDIV => stack.ddiv(),
+
+      opcode::DLOAD => {
+        let index = fetch(program, ip) as usize;
+        stack.push(local.load(index));
+      }
+      opcode::DLOAD_0 => stack.push(local.load(0)),
+      opcode::DLOAD_1 => stack.push(local.load(1)),
+      opcode::DLOAD_2 => stack.push(local.load(2)),
+      opcode::DLOAD_3 => stack.push(local.load(3)),
+
+      opcode::DMUL => stack.dmul(),
+
+      opcode::DNEG => stack.dneg(),
+
+      opcode::DREM => stack.drem(),
+
+      opcode::DRETURN => {
+        let ret = stack.pop();
+        return Ok(self.return_from_method(base, Some(ret)));
+      }
+
+      opcode::DSTORE => {
+        let index = fetch(program, ip);
+        local.store(index as usize, stack.pop());
+      }
+      opcode::DSTORE_0 => local.store(0, stack.pop()),
+      opcode::DSTORE_1 => local.store(1, stack.pop()),
+      opcode::DSTORE_2 => local.store(2, stack.pop()),
+      opcode::DSTORE_3 => local.store(3, stack.pop()),
+
+      opcode::DSUB => stack.dsub(),
+
+      opcode::DUP => stack.dup(),
+      opcode::DUP_X1 => stack.dup_x1(),
+      opcode::DUP_X2 => stack.dup_x2(),
+      opcode::DUP2 => stack.dup2(),
+      opcode::DUP2_X1 => stack.dup2_x1(),
+      opcode::DUP2_X2 => stack.dup2_x2(),
+
+      opcode::F2D => stack.f2d(),
+      opcode::F2I => stack.f2i(),
+      opcode::F2L => stack.f2l(),
+
+      opcode::FADD => stack.fadd(),
+
+      opcode::FALOAD => self.array_load()?,
+
+      opcode::FASTORE => self.array_store()?,
+
+      opcode::FCMPG => stack.fcmpg(),
+      opcode::FCMPL => stack.fcmpl(),
+
+      opcode::FCONSTF_0 => stack.fconst(0f32),
+      opcode::FCONSTF_1 => stack.fconst(1f32),
+      opcode::FCONSTF_2 => stack.fconst(2f32),
+
+      opcode::FDIV => stack.fdiv(),
+
+      opcode::FLOAD => {
+        let index = fetch(program, ip) as usize;
+        stack.push(local.load(index));
+      }
+      opcode::FLOAD_0 => stack.push(local.load(0)),
+      opcode::FLOAD_1 => stack.push(local.load(1)),
+      opcode::FLOAD_2 => stack.push(local.load(2)),
+      opcode::FLOAD_3 => stack.push(local.load(3)),
+
+      opcode::FMUL => stack.fmul(),
+
+      opcode::FNEG => stack.fneg(),
+
+      opcode::FREM => stack.frem(),
+
+      opcode::FRETURN => {
+        let ret = stack.pop();
+        return Ok(self.return_from_method(base, Some(ret)));
+      }
+
+      opcode::FSTORE => {
+        let index = fetch(program, ip);
+        local.store(index as usize, stack.pop());
+      }
+      opcode::FSTORE_0 => local.store(0, stack.pop()),
+      opcode::FSTORE_1 => local.store(1, stack.pop()),
+      opcode::FSTORE_2 => local.store(2, stack.pop()),
+      opcode::FSTORE_3 => local.store(3, stack.pop()),
+
+      opcode::FSUB => stack.fsub(),
+
+      opcode::GETFIELD => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+
+        let offset = self.resolve_field(constant_pool, index)?;
+        let objectref: usize = self.stack().pop().into();
+        if objectref == 0 {
+          return Err(self.null_pointer());
+        }
+        let value = self.heap.get_field(objectref, offset);
+        self.stack().push(value);
+      }
+
+      opcode::GETSTATIC => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+
+        let (class, field) = self.resolve_static_field(constant_pool, index)?;
+        self.initialize(&class)?;
+        let value = class.statics.borrow()[class.slots[field]];
+        self.stack().push(value);
+      }
+
+      // 16-bit branchoffset.
+      opcode::GOTO => branch(program, ip, true),
+
+      // 32-bit branchoffset.
+      opcode::GOTO_W => {
+        let offset = int_at(program, *ip);
+        *ip = (*ip - 1).wrapping_add_signed(offset as isize);
+      }
+
+      opcode::I2B => stack.i2b(),
+      opcode::I2C => stack.i2c(),
+      opcode::I2D => stack.i2d(),
+      opcode::I2F => stack.i2f(),
+      opcode::I2L => stack.i2l(),
+      opcode::I2S => stack.i2s(),
+
+      opcode::IADD => stack.iadd(),
+
+      opcode::IALOAD => self.array_load()?,
+
+      opcode::IAND => stack.iand(),
+
+      opcode::IASTORE => self.array_store()?,
+
+      opcode::ICONST_M1 => stack.iconst(-1),
+      opcode::ICONST_0 => stack.iconst(0),
+      opcode::ICONST_1 => stack.iconst(1),
+      opcode::ICONST_2 => stack.iconst(2),
+      opcode::ICONST_3 => stack.iconst(3),
+      opcode::ICONST_4 => stack.iconst(4),
+      opcode::ICONST_5 => stack.iconst(5),
+
+      opcode::IDIV => stack.idiv()?,
+
+      // Both values needs to be of type reference.
+      opcode::IF_ACMPEQ => {
+        let value2: usize = stack.pop().into();
+        let value1: usize = stack.pop().into();
+        branch(program, ip, value1 == value2);
+      }
+      opcode::IF_ACMPNE => {
+        let value2: usize = stack.pop().into();
+        let value1: usize = stack.pop().into();
+        branch(program, ip, value1 != value2);
+      }
+
+      // Both values needs to be of type int.
+      opcode::IF_ICMPEQ => {
+        let value2: i32 = stack.pop().into();
+        let value1: i32 = stack.pop().into();
+        branch(program, ip, value1 == value2);
+      }
+      opcode::IF_ICMPNE => {
+        let value2: i32 = stack.pop().into();
+        let value1: i32 = stack.pop().into();
+        branch(program, ip, value1 != value2);
+      }
+      opcode::IF_ICMPLT => {
+        let value2: i32 = stack.pop().into();
+        let value1: i32 = stack.pop().into();
+        branch(program, ip, value1 < value2);
+      }
+      opcode::IF_ICMPGE => {
+        let value2: i32 = stack.pop().into();
+        let value1: i32 = stack.pop().into();
+        branch(program, ip, value1 >= value2);
+      }
+      opcode::IF_ICMPGT => {
+        let value2: i32 = stack.pop().into();
+        let value1: i32 = stack.pop().into();
+        branch(program, ip, value1 > value2);
+      }
+      opcode::IF_ICMPLE => {
+        let value2: i32 = stack.pop().into();
+        let value1: i32 = stack.pop().into();
+        branch(program, ip, value1 <= value2);
+      }
+
+      // Value needs to be of type int.
+      opcode::IFEQ => {
+        let value: i32 = stack.pop().into();
+        branch(program, ip, value == 0);
+      }
+      opcode::IFNE => {
+        let value: i32 = stack.pop().into();
+        branch(program, ip, value != 0);
+      }
+      opcode::IFLT => {
+        let value: i32 = stack.pop().into();
+        branch(program, ip, value < 0);
+      }
+      opcode::IFGE => {
+        let value: i32 = stack.pop().into();
+        branch(program, ip, value >= 0);
+      }
+      opcode::IFGT => {
+        let value: i32 = stack.pop().into();
+        branch(program, ip, value > 0);
+      }
+      opcode::IFLE => {
+        let value: i32 = stack.pop().into();
+        branch(program, ip, value <= 0);
+      }
+
+      // Value needs to be of type reference.
+      opcode::IFNONNULL => {
+        let value: usize = stack.pop().into();
+        branch(program, ip, value != 0);
+      }
+      opcode::IFNULL => {
+        let value: usize = stack.pop().into();
+        branch(program, ip, value == 0);
+      }
+
+      opcode::IINC => {
+        let index = fetch(program, ip) as usize;
+        let r#const = fetch(program, ip) as i8 as i32;
+        local.iinc(index, r#const);
+      }
+
+      opcode::ILOAD => {
+        let load = local.load(fetch(program, ip) as usize);
+        stack.push(load);
+      }
+      opcode::ILOAD_0 => stack.push(local.load(0)),
+      opcode::ILOAD_1 => stack.push(local.load(1)),
+      opcode::ILOAD_2 => stack.push(local.load(2)),
+      opcode::ILOAD_3 => stack.push(local.load(3)),
+
+      opcode::IMUL => stack.imul(),
+
+      opcode::INEG => stack.ineg(),
+
+      opcode::INSTANCEOF => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+        let objectref: usize = stack.pop().into();
+
+        let target = self.resolve_type(constant_pool, index)?;
+        let is_instance = objectref != 0 && {
+          let source = self.type_of(objectref);
+          self.is_assignable(&source, &target)
+        };
+        self.stack().iconst(i32::from(is_instance));
+      }
+
+      opcode::INVOKEDYNAMIC => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+        let _zero = fetch(program, ip);
+        let _zero = fetch(program, ip);
+
+        let recipe = self.resolve_call_site(&class, index)?;
+        let stack = self.stack();
+        let mut args: Vec<MistValue> =
+          recipe.params.iter().map(|_| stack.pop()).collect();
+        args.reverse();
+        let text = recipe.concat(self, &args)?;
+        let string = self.new_string(&text);
+        self.stack().push(MistValue::ObjectReference(string));
+      }
+
+      opcode::INVOKEINTERFACE => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+        let _count = fetch(program, ip);
+        let _zero = fetch(program, ip);
+
+        let (class, method, dispatch) =
+          self.resolve_virtual(constant_pool, index)?;
+        let objectref = self.receiver(&class, method)?;
+        let (class, method) =
+          self.select(class, method, dispatch, objectref)?;
+        self.invoke(class, method)?;
+      }
+
+      opcode::INVOKESPECIAL => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+
+        let (class, method) =
+          self.resolve_special(&class, constant_pool, index)?;
+        self.receiver(&class, method)?;
+        self.invoke(class, method)?;
+      }
+
+      opcode::INVOKESTATIC => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+
+        let (class, method) = self.resolve_static(constant_pool, index)?;
+        self.initialize(&class)?;
+        self.invoke(class, method)?;
+      }
+
+      opcode::INVOKEVIRTUAL => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+
+        let (class, method, dispatch) =
+          self.resolve_virtual(constant_pool, index)?;
+        let objectref = self.receiver(&class, method)?;
+        let (class, method) =
+          self.select(class, method, dispatch, objectref)?;
+        self.invoke(class, method)?;
+      }
+
+      opcode::IOR => stack.ior(),
+
+      opcode::IREM => stack.irem()?,
+
+      // The current method must have return type boolean, byte, short, char, or int.
+      opcode::IRETURN => {
+        let ret = stack.pop();
+        return Ok(self.return_from_method(base, Some(ret)));
+      }
+
+      opcode::ISHL => stack.ishl(),
+
+      opcode::ISHR => stack.ishr(),
+
+      opcode::ISTORE => {
+        let index = fetch(program, ip) as usize;
+        local.store(index, stack.pop());
+      }
+      opcode::ISTORE_0 => local.store(0, stack.pop()),
+      opcode::ISTORE_1 => local.store(1, stack.pop()),
+      opcode::ISTORE_2 => local.store(2, stack.pop()),
+      opcode::ISTORE_3 => local.store(3, stack.pop()),
+
+      opcode::ISUB => stack.isub(),
+
+      opcode::IUSHR => stack.iushr(),
+
+      opcode::IXOR => stack.ixor(),
+
+      opcode::JSR => {
+        stack.push(MistValue::ReturnAddress(*ip + 2));
+        branch(program, ip, true);
+      }
+
+      opcode::JSR_W => {
+        let offset = int_at(program, *ip);
+        stack.push(MistValue::ReturnAddress(*ip + 4));
+        *ip = (*ip - 1).wrapping_add_signed(offset as isize);
+      }
+
+      opcode::L2D => stack.l2d(),
+      opcode::L2F => stack.l2f(),
+      opcode::L2I => stack.l2i(),
+
+      opcode::LADD => stack.ladd(),
+
+      opcode::LALOAD => self.array_load()?,
+
+      opcode::LAND => stack.land(),
+
+      opcode::LASTORE => self.array_store()?,
+
+      opcode::LCMP => stack.lcmp(),
+
+      opcode::LCONST_0 => stack.lconst(0i64),
+      opcode::LCONST_1 => stack.lconst(1i64),
+
+      opcode::LDC => {
+        let index = fetch(program, ip) as u16;
+        let value = self.ldc(constant_pool, index)?;
+        self.stack().push(value);
+      }
+      opcode::LDC_W => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+        let value = self.ldc(constant_pool, index)?;
+        self.stack().push(value);
+      }
+      opcode::LDC2_W => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+        match constant_pool.get(index)? {
+          Entry::LongInfo { .. } => stack.lconst(constant_pool.long(index)?),
+          Entry::DoubleInfo { .. } => {
+            stack.dconst(constant_pool.double(index)?)
+          }
+          Entry::DynamicInfo { .. } => {
+            return Err(unsupported_ldc("CONSTANT_Dynamic"))
+          }
+          _ => {
+            return Err(
+              PoolError {
+                index,
+                expected: "Long or Double",
+              }
+              .into(),
+            )
+          }
+        }
+      }
+
+      opcode::LDIV => stack.ldiv()?,
+
+      opcode::LLOAD => {
+        let index = fetch(program, ip) as usize;
+        stack.push(local.load(index));
+      }
+      opcode::LLOAD_0 => stack.push(local.load(0)),
+      opcode::LLOAD_1 => stack.push(local.load(1)),
+      opcode::LLOAD_2 => stack.push(local.load(2)),
+      opcode::LLOAD_3 => stack.push(local.load(3)),
+
+      opcode::LMUL => stack.lmul(),
+
+      opcode::LNEG => stack.lneg(),
+
+      opcode::LOOKUPSWITCH => {
+        let key: i32 = stack.pop().into();
+        let at = *ip - 1;
+        // Operands start at the next multiple of four from the method start.
+        let operands = (at + 4) & !3;
+        let pairs = operands + 8;
+        let npairs = int_at(program, operands + 4) as usize;
+
+        // The pairs are sorted by their match.
+        let (mut low, mut high) = (0, npairs);
+        let mut offset = int_at(program, operands);
+        while low < high {
+          let mid = low + (high - low) / 2;
+          match int_at(program, pairs + 8 * mid).cmp(&key) {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => {
+              offset = int_at(program, pairs + 8 * mid + 4);
+              break;
+            }
+          }
+        }
+        *ip = at.wrapping_add_signed(offset as isize);
+      }
+
+      opcode::LOR => stack.lor(),
+
+      opcode::LREM => stack.lrem()?,
+
+      opcode::LRETURN => {
+        let ret = stack.pop();
+        return Ok(self.return_from_method(base, Some(ret)));
+      }
+
+      opcode::LSHL => stack.lshl(),
+
+      opcode::LSHR => stack.lshr(),
+
+      opcode::LUSHR => stack.lushr(),
+
+      opcode::LSTORE => {
+        let index = fetch(program, ip) as usize;
+        local.store(index, stack.pop());
+      }
+      opcode::LSTORE_0 => local.store(0, stack.pop()),
+      opcode::LSTORE_1 => local.store(1, stack.pop()),
+      opcode::LSTORE_2 => local.store(2, stack.pop()),
+      opcode::LSTORE_3 => local.store(3, stack.pop()),
+
+      opcode::LSUB => stack.lsub(),
+
+      opcode::LXOR => stack.lxor(),
+
+      // There is a single thread, so holding a monitor never excludes
+      // anything and only the null check is left.
+      opcode::MONITORENTER | opcode::MONITOREXIT => {
+        if let MistValue::ObjectReference(0) = stack.pop() {
+          return Err(self.null_pointer());
+        }
+      }
+
+      opcode::MULTIANEWARRAY => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+        let dimensions = fetch(program, ip);
+        let mut counts = (0..dimensions)
+          .map(|_| stack.pop().into())
+          .collect::<Vec<i32>>();
+        counts.reverse();
+
+        let array_type = self.resolve_type(constant_pool, index)?;
+        let r#ref = self.heap.multianewarray(&array_type, &counts)?;
+        self.stack().push(MistValue::ObjectReference(r#ref));
+      }
+
+      opcode::NEWARRAY => {
+        let atype = fetch(program, ip);
+        let count: i32 = stack.pop().into();
+
+        let r#ref = self.heap.newarray(atype, count)?;
+        stack.push(MistValue::ObjectReference(r#ref));
+      }
+
+      opcode::NEW => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+
+        let class = self.resolve_class(constant_pool, index)?;
+        if class.is_interface() || class.is_abstract() {
+          return Err(RuntimeError::new(
+            "java/lang/InstantiationError",
+            class.this_class.replace('/', "."),
+          ));
+        }
+        self.initialize(&class)?;
+
+        let r#ref = self.heap.alloc(class.id, class.layout.clone());
+        self.stack().push(MistValue::ObjectReference(r#ref));
+      }
+
+      opcode::NOP => {}
+
+      opcode::POP => _ = stack.pop(),
+
+      opcode::POP2 => stack.pop2(),
+
+      opcode::PUTFIELD => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+
+        let offset = self.resolve_field(constant_pool, index)?;
+        let value = self.stack().pop();
+        let objectref: usize = self.stack().pop().into();
+        if objectref == 0 {
+          return Err(self.null_pointer());
+        }
+        self.heap.put_field(objectref, offset, value);
+      }
+
+      opcode::PUTSTATIC => {
+        let indexbyte1 = fetch(program, ip) as u16;
+        let indexbyte2 = fetch(program, ip) as u16;
+        let index = indexbyte1 << 8 | indexbyte2;
+
+        let (class, field) = self.resolve_static_field(constant_pool, index)?;
+        self.initialize(&class)?;
+        let value = self.stack().pop();
+        class.statics.borrow_mut()[class.slots[field]] = value;
+      }
+
+      opcode::RET => {
+        let index = fetch(program, ip) as usize;
+        *ip = return_address(local.load(index))?;
+      }
+
+      opcode::RETURN => {
+        return Ok(self.return_from_method(base, None));
+      }
+
+      opcode::SALOAD => self.array_load()?,
+
+      opcode::SASTORE => self.array_store()?,
+
+      opcode::SIPUSH => {
+        let byte1 = fetch(program, ip);
+        let byte2 = fetch(program, ip);
+        stack.iconst(i16::from_be_bytes([byte1, byte2]) as i32);
+      }
+
+      opcode::SWAP => {
+        let value1 = stack.pop();
+        let value2 = stack.pop();
+        stack.push(value1);
+        stack.push(value2);
+      }
+
+      opcode::TABLESWITCH => {
+        let index: i32 = stack.pop().into();
+        let at = *ip - 1;
+        // Operands start at the next multiple of four from the method start.
+        let operands = (at + 4) & !3;
+        let low = int_at(program, operands + 4);
+        let high = int_at(program, operands + 8);
+
+        let offset = if (low..=high).contains(&index) {
+          let entry = (index as i64 - low as i64) as usize;
+          int_at(program, operands + 12 + 4 * entry)
+        } else {
+          int_at(program, operands)
+        };
+        *ip = at.wrapping_add_signed(offset as isize);
+      }
+
+      // Widens the local variable index of the next instruction, and the
+      // constant of an iinc.
+      opcode::WIDE => {
+        let instruction = fetch(program, ip);
+        let indexbyte1 = fetch(program, ip);
+        let indexbyte2 = fetch(program, ip);
+        let index = u16::from_be_bytes([indexbyte1, indexbyte2]) as usize;
+
+        match instruction {
+          opcode::ILOAD
+          | opcode::LLOAD
+          | opcode::FLOAD
+          | opcode::DLOAD
+          | opcode::ALOAD => stack.push(local.load(index)),
+          opcode::ISTORE
+          | opcode::LSTORE
+          | opcode::FSTORE
+          | opcode::DSTORE
+          | opcode::ASTORE => local.store(index, stack.pop()),
+          opcode::IINC => {
+            let constbyte1 = fetch(program, ip);
+            let constbyte2 = fetch(program, ip);
+            let r#const = i16::from_be_bytes([constbyte1, constbyte2]);
+            local.iinc(index, r#const as i32);
+          }
+          opcode::RET => *ip = return_address(local.load(index))?,
+          other => {
+            return Err(verify_error(format!(
+              "Bad instruction {other:#04x} after wide"
+            )))
+          }
+        }
+      }
+
+      other => {
+        return Err(verify_error(format!("Bad instruction {other:#04x}")))
+      }
+    }
+    Ok(ControlFlow::Continue(()))
+  }
+}
+
+/// An `IncompatibleClassChangeError` for calling `class.methods[method]` as
+/// if it was `expected`.
+fn incompatible_method(
+  class: &Class,
+  method: usize,
+  expected: &str,
+) -> RuntimeError {
+  let method = &class.methods[method];
+  RuntimeError::new(
+    "java/lang/IncompatibleClassChangeError",
+    format!(
+      "Expected {expected} method {}.{}{}",
+      class.this_class, method.name, method.descriptor
+    ),
+  )
+}
+
+/// An `InternalError` for loading a kind of constant the runtime cannot
+/// create.
+fn unsupported_ldc(kind: &str) -> RuntimeError {
+  RuntimeError::new(
+    "java/lang/InternalError",
+    format!("ldc of {kind} is not supported"),
+  )
+}
+
+#[inline(always)]
+/// Moves `ip`, just past a branch opcode, to the target of the branch if it
+/// is `taken`, and otherwise past the signed 16-bit offset to the target.
+fn branch(program: &[u8], ip: &mut usize, taken: bool) {
+  if taken {
+    let offset = i16::from_be_bytes([program[*ip], program[*ip + 1]]);
+    *ip = (*ip - 1).wrapping_add_signed(offset as isize);
+  } else {
+    *ip += 2;
+  }
+}
+
+/// The address a `ret` continues at, stored by `jsr` in `value`.
+fn return_address(value: MistValue) -> Result<usize, RuntimeError> {
+  let MistValue::ReturnAddress(address) = value else {
+    return Err(verify_error(format!(
+      "Expected a return address, found {value:?}"
+    )));
+  };
+  Ok(address)
+}
+
+/// A `VerifyError` for code the verifier would have rejected.
+fn verify_error(message: String) -> RuntimeError {
+  RuntimeError::new("java/lang/VerifyError", message)
+}
+
+/// The signed 32-bit operand at `at`.
+fn int_at(program: &[u8], at: usize) -> i32 {
+  i32::from_be_bytes([
+    program[at],
+    program[at + 1],
+    program[at + 2],
+    program[at + 3],
+  ])
+}
+
+fn fetch(program: &[u8], ip: &mut usize) -> u8 {
+  let instruction = program[*ip];
+  *ip += 1;
+  instruction
+}
@@ -0,0 +1,702 @@
+//! Messages for `NullPointerException`s that say what could not be done and
+//! which expression was null, as in JEP 358.
+
+use std::collections::{HashMap, HashSet};
+
+use super::int_at;
+use crate::{
+  class::{
+    attribute_info::AttributeInfo,
+    descriptor::{FieldType, MethodDescriptor},
+    method::MethodInfo,
+    pool::Entry,
+    Class,
+  },
+  opcode,
+};
+
+/// What pushed a value onto the operand stack.
+#[derive(Clone, PartialEq)]
+enum Source {
+  Unknown,
+  Null,
+  Constant(i32),
+  Local(usize),
+  StaticField {
+    class: String,
+    name: String,
+  },
+  Field {
+    object: Box<Source>,
+    name: String,
+  },
+  ArrayElement {
+    array: Box<Source>,
+    index: Box<Source>,
+  },
+  /// The return value of the method, printed as `Class.name(Parameters)`.
+  Return(String),
+}
+
+#[derive(Clone, PartialEq)]
+struct Value {
+  source: Source,
+  /// Whether the value is a long or a double.
+  wide: bool,
+}
+
+impl Value {
+  fn new(source: Source) -> Self {
+    Self {
+      source,
+      wide: false,
+    }
+  }
+
+  fn unknown(wide: bool) -> Self {
+    Self {
+      source: Source::Unknown,
+      wide,
+    }
+  }
+}
+
+/// The message for a null reference met by the instruction at `pc` in
+/// `class.methods[method]`, if that instruction dereferences one.
+pub fn message(class: &Class, method: usize, pc: usize) -> Option<String> {
+  let method = &class.methods[method];
+  let code = method.code()?;
+  let program = &code.code;
+
+  let backward = backward_targets(program)?;
+  let handlers = code
+    .exception_table
+    .iter()
+    .map(|entry| entry.handler_pc as usize)
+    .collect::<HashSet<_>>();
+
+  // The operand stack before the instruction at `at`, or None where the
+  // instruction can only be reached by a branch.
+  let mut stack = Some(Vec::new());
+  let mut pending = HashMap::<usize, Vec<Value>>::new();
+  let mut at = 0;
+
+  loop {
+    if let Some(state) = pending.remove(&at) {
+      stack = match stack {
+        Some(stack) => Some(merge(stack, state)?),
+        None => Some(state),
+      };
+    }
+    if stack.is_none() && handlers.contains(&at) {
+      stack = Some(vec![Value::unknown(false)]);
+    }
+    let current = stack.as_mut()?;
+    if backward.contains(&at) {
+      for value in current.iter_mut() {
+        value.source = Source::Unknown;
+      }
+    }
+
+    let length = length(program, at)?;
+    if pc < at + length {
+      return describe(class, method, program, at, current);
+    }
+
+    let flow = simulate(class, program, at, current)?;
+    for target in flow.targets {
+      let state = match pending.remove(&target) {
+        Some(state) => merge(state, current.clone())?,
+        None => current.clone(),
+      };
+      pending.insert(target, state);
+    }
+    if !flow.falls_through {
+      stack = None;
+    }
+    at += length;
+  }
+}
+
+/// The message for the instruction at `at`, run with `stack`.
+fn describe(
+  class: &Class,
+  method: &MethodInfo,
+  program: &[u8],
+  at: usize,
+  stack: &[Value],
+) -> Option<String> {
+  let index = || u16::from_be_bytes([program[at + 1], program[at + 2]]);
+  let pool = &class.constant_pool;
+
+  let (action, depth) = match program[at] {
+    opcode::GETFIELD => {
+      let (_, name, _) = pool.field_ref(index()).ok()?;
+      (format!("Cannot read field \"{name}\""), 0)
+    }
+    opcode::PUTFIELD => {
+      let (_, name, _) = pool.field_ref(index()).ok()?;
+      (format!("Cannot assign field \"{name}\""), 1)
+    }
+    opcode::INVOKEVIRTUAL | opcode::INVOKESPECIAL | opcode::INVOKEINTERFACE => {
+      let (class_name, name, descriptor) = pool.method_ref(index()).ok()?;
+      let depth = MethodDescriptor::parse(descriptor).ok()?.params.len();
+      let method = method_name(class_name, name, descriptor);
+      (format!("Cannot invoke \"{method}\""), depth)
+    }
+    opcode::ARRAYLENGTH => (String::from("Cannot read the array length"), 0),
+    opcode::ATHROW => (String::from("Cannot throw exception"), 0),
+    opcode::MONITORENTER => {
+      (String::from("Cannot enter synchronized block"), 0)
+    }
+    opcode::MONITOREXIT => (String::from("Cannot exit synchronized block"), 0),
+    load @ opcode::IALOAD..=opcode::SALOAD => {
+      let array = array_kind(load - opcode::IALOAD);
+      (format!("Cannot load from {array} array"), 1)
+    }
+    store @ opcode::IASTORE..=opcode::SASTORE => {
+      let array = array_kind(store - opcode::IASTORE);
+      (format!("Cannot store to {array} array"), 2)
+    }
+    _ => return None,
+  };
+
+  let null = stack
+    .len()
+    .checked_sub(depth + 1)
+    .map(|index| &stack[index]);
+  let because = match null.map(|value| &value.source) {
+    Some(Source::Return(method)) => {
+      format!(" because the return value of \"{method}\" is null")
+    }
+    Some(source) => match expression(method, at, source) {
+      Some(expression) => format!(" because \"{expression}\" is null"),
+      None => String::new(),
+    },
+    None => String::new(),
+  };
+  Some(format!("{action}{because}"))
+}
+
+/// How `source` reads in Java, if it can be told.
+fn expression(
+  method: &MethodInfo,
+  at: usize,
+  source: &Source,
+) -> Option<String> {
+  let expression = match source {
+    Source::Unknown => return None,
+    Source::Null => String::from("null"),
+    Source::Constant(int) => int.to_string(),
+    Source::Local(slot) => local_name(method, at, *slot),
+    Source::StaticField { class, name } => {
+      format!("{}.{name}", external_class(class))
+    }
+    Source::Field { object, name } => match expression(method, at, object) {
+      Some(object) => format!("{object}.{name}"),
+      None => name.clone(),
+    },
+    Source::ArrayElement { array, index } => {
+      let array = expression(method, at, array);
+      let index = expression(method, at, index);
+      format!(
+        "{}[{}]",
+        array.as_deref().unwrap_or("..."),
+        index.as_deref().unwrap_or("...")
+      )
+    }
+    Source::Return(method) => method.clone(),
+  };
+  Some(expression)
+}
+
+/// The name of local variable `slot` at `at`: its declared name if the
+/// method has a `LocalVariableTable`, and otherwise `this`, `<parameterN>`
+/// or `<localN>`.
+fn local_name(method: &MethodInfo, at: usize, slot: usize) -> String {
+  let declared = method.code().and_then(|code| {
+    code
+      .attributes
+      .iter()
+      .filter_map(|attribute| match attribute {
+        AttributeInfo::LocalVariableTable(table) => Some(table),
+        _ => None,
+      })
+      .flatten()
+      .find(|variable| {
+        let start = variable.start_pc as usize;
+        variable.index as usize == slot
+          && (start..start + variable.length as usize).contains(&at)
+      })
+  });
+  if let Some(variable) = declared {
+    return variable.name.clone();
+  }
+
+  let mut first = 0;
+  if !method.is_static() {
+    if slot == 0 {
+      return String::from("this");
+    }
+    first = 1;
+  }
+  let params = &method.method_descriptor.params;
+  for (number, parameter) in params.iter().enumerate() {
+    if slot == first {
+      return format!("<parameter{}>", number + 1);
+    }
+    first += parameter.slots();
+  }
+  format!("<local{slot}>")
+}
+
+/// Runs the instruction at `at` on the sources in `stack`.
+fn simulate(
+  class: &Class,
+  program: &[u8],
+  at: usize,
+  stack: &mut Vec<Value>,
+) -> Option<Flow> {
+  let pool = &class.constant_pool;
+  let index = || u16::from_be_bytes([program[at + 1], program[at + 2]]);
+  let offset = || i16::from_be_bytes([program[at + 1], program[at + 2]]);
+  let jump = |offset: i32| at.checked_add_signed(offset as isize);
+
+  let mut flow = Flow::next();
+  let value = |source| Value::new(source);
+  match program[at] {
+    opcode::NOP | opcode::IINC => {}
+
+    opcode::ACONST_NULL => stack.push(value(Source::Null)),
+    int @ opcode::ICONST_M1..=opcode::ICONST_5 => {
+      let int = int as i32 - opcode::ICONST_0 as i32;
+      stack.push(value(Source::Constant(int)));
+    }
+    opcode::BIPUSH => {
+      stack.push(value(Source::Constant(program[at + 1] as i8 as i32)));
+    }
+    opcode::SIPUSH => stack.push(value(Source::Constant(offset() as i32))),
+    opcode::FCONSTF_0..=opcode::FCONSTF_2 | opcode::LDC | opcode::LDC_W => {
+      stack.push(Value::unknown(false));
+    }
+    opcode::LCONST_0
+    | opcode::LCONST_1
+    | opcode::DCONST_0
+    | opcode::DCONST_1
+    | opcode::LDC2_W => stack.push(Value::unknown(true)),
+
+    opcode::ILOAD | opcode::FLOAD | opcode::ALOAD => {
+      stack.push(value(Source::Local(program[at + 1] as usize)));
+    }
+    opcode::LLOAD | opcode::DLOAD => stack.push(Value {
+      source: Source::Local(program[at + 1] as usize),
+      wide: true,
+    }),
+    load @ opcode::ILOAD_0..=opcode::ALOAD_3 => {
+      let load = (load - opcode::ILOAD_0) as usize;
+      stack.push(Value {
+        source: Source::Local(load % 4),
+        wide: matches!(load / 4, 1 | 3),
+      });
+    }
+
+    load @ opcode::IALOAD..=opcode::SALOAD => {
+      let index = stack.pop()?;
+      let array = stack.pop()?;
+      stack.push(match load {
+        opcode::AALOAD => value(Source::ArrayElement {
+          array: Box::new(array.source),
+          index: Box::new(index.source),
+        }),
+        opcode::LALOAD | opcode::DALOAD => Value::unknown(true),
+        _ => Value::unknown(false),
+      });
+    }
+
+    opcode::ISTORE..=opcode::ASTORE_3 => {
+      stack.pop()?;
+    }
+    opcode::IASTORE..=opcode::SASTORE => pop(stack, 3)?,
+
+    opcode::POP => {
+      stack.pop()?;
+    }
+    opcode::POP2 => {
+      if !stack.pop()?.wide {
+        stack.pop()?;
+      }
+    }
+    opcode::DUP => dup(stack, 1, 0)?,
+    opcode::DUP_X1 => dup(stack, 1, 1)?,
+    opcode::DUP_X2 => dup(stack, 1, 2)?,
+    opcode::DUP2 => dup(stack, 2, 0)?,
+    opcode::DUP2_X1 => dup(stack, 2, 1)?,
+    opcode::DUP2_X2 => dup(stack, 2, 2)?,
+    opcode::SWAP => {
+      let value1 = stack.pop()?;
+      let value2 = stack.pop()?;
+      stack.extend([value1, value2]);
+    }
+
+    arithmetic @ opcode::IADD..=opcode::DREM => {
+      pop(stack, 2)?;
+      stack.push(Value::unknown(arithmetic % 2 == 1));
+    }
+    negation @ opcode::INEG..=opcode::DNEG => {
+      stack.pop()?;
+      stack.push(Value::unknown(negation % 2 == 1));
+    }
+    shift @ opcode::ISHL..=opcode::LXOR => {
+      pop(stack, 2)?;
+      stack.push(Value::unknown(shift % 2 == 1));
+    }
+    conversion @ opcode::I2L..=opcode::I2S => {
+      stack.pop()?;
+      let wide = matches!(
+        conversion,
+        opcode::I2L
+          | opcode::I2D
+          | opcode::L2D
+          | opcode::F2L
+          | opcode::F2D
+          | opcode::D2L
+      );
+      stack.push(Value::unknown(wide));
+    }
+    opcode::LCMP..=opcode::DCMPG => {
+      pop(stack, 2)?;
+      stack.push(Value::unknown(false));
+    }
+
+    opcode::IFEQ..=opcode::IFLE | opcode::IFNULL | opcode::IFNONNULL => {
+      stack.pop()?;
+      flow.targets.push(jump(offset() as i32)?);
+    }
+    opcode::IF_ICMPEQ..=opcode::IF_ACMPNE => {
+      pop(stack, 2)?;
+      flow.targets.push(jump(offset() as i32)?);
+    }
+    opcode::GOTO => flow = Flow::jump(jump(offset() as i32)?),
+    opcode::GOTO_W => flow = Flow::jump(jump(int_at(program, at + 1))?),
+    opcode::TABLESWITCH | opcode::LOOKUPSWITCH => {
+      stack.pop()?;
+      flow = Flow::jump_any(switch_targets(program, at)?);
+    }
+    opcode::IRETURN..=opcode::RETURN | opcode::ATHROW => flow = Flow::stop(),
+
+    opcode::GETSTATIC => {
+      let (class, name, descriptor) = pool.field_ref(index()).ok()?;
+      stack.push(Value {
+        source: Source::StaticField {
+          class: class.to_string(),
+          name: name.to_string(),
+        },
+        wide: is_wide(descriptor),
+      });
+    }
+    opcode::PUTSTATIC => {
+      stack.pop()?;
+    }
+    opcode::GETFIELD => {
+      let (_, name, descriptor) = pool.field_ref(index()).ok()?;
+      let object = stack.pop()?;
+      stack.push(Value {
+        source: Source::Field {
+          object: Box::new(object.source),
+          name: name.to_string(),
+        },
+        wide: is_wide(descriptor),
+      });
+    }
+    opcode::PUTFIELD => pop(stack, 2)?,
+
+    invoke @ (opcode::INVOKEVIRTUAL
+    | opcode::INVOKESPECIAL
+    | opcode::INVOKESTATIC
+    | opcode::INVOKEINTERFACE) => {
+      let (class, name, descriptor) = pool.method_ref(index()).ok()?;
+      let receiver = usize::from(invoke != opcode::INVOKESTATIC);
+      let method = MethodDescriptor::parse(descriptor).ok()?;
+      pop(stack, method.params.len() + receiver)?;
+
+      if let Some(ret) = method.ret {
+        stack.push(Value {
+          source: Source::Return(method_name(class, name, descriptor)),
+          wide: ret.slots() == 2,
+        });
+      }
+    }
+    opcode::INVOKEDYNAMIC => {
+      let (_, descriptor) = dynamic_name_and_type(class, index())?;
+      let method = MethodDescriptor::parse(descriptor).ok()?;
+      pop(stack, method.params.len())?;
+      if let Some(ret) = method.ret {
+        stack.push(Value::unknown(ret.slots() == 2));
+      }
+    }
+
+    opcode::NEW => stack.push(Value::unknown(false)),
+    opcode::NEWARRAY
+    | opcode::ANEWARRAY
+    | opcode::ARRAYLENGTH
+    | opcode::INSTANCEOF => {
+      stack.pop()?;
+      stack.push(Value::unknown(false));
+    }
+    opcode::MULTIANEWARRAY => {
+      pop(stack, program[at + 3] as usize)?;
+      stack.push(Value::unknown(false));
+    }
+    opcode::CHECKCAST => {}
+    opcode::MONITORENTER | opcode::MONITOREXIT => {
+      stack.pop()?;
+    }
+
+    // Subroutines and wide locals are rare enough to give up on.
+    _ => return None,
+  }
+
+  Some(flow)
+}
+
+/// Where control goes after an instruction.
+struct Flow {
+  targets: Vec<usize>,
+  falls_through: bool,
+}
+
+impl Flow {
+  fn next() -> Self {
+    Self {
+      targets: Vec::new(),
+      falls_through: true,
+    }
+  }
+
+  fn jump(target: usize) -> Self {
+    Self::jump_any(vec![target])
+  }
+
+  fn jump_any(targets: Vec<usize>) -> Self {
+    Self {
+      targets,
+      falls_through: false,
+    }
+  }
+
+  fn stop() -> Self {
+    Self::jump_any(Vec::new())
+  }
+}
+
+fn pop(stack: &mut Vec<Value>, count: usize) -> Option<()> {
+  let len = stack.len().checked_sub(count)?;
+  stack.truncate(len);
+  Some(())
+}
+
+/// Copies the values in the top `copied` slots of `stack` below the values
+/// in the `skipped` slots under them, as the forms of dup do.
+fn dup(stack: &mut Vec<Value>, copied: usize, skipped: usize) -> Option<()> {
+  let slots = |value: &Value| if value.wide { 2 } else { 1 };
+
+  let mut top = stack.len();
+  let mut taken = 0;
+  while taken < copied {
+    top = top.checked_sub(1)?;
+    taken += slots(&stack[top]);
+  }
+  let mut below = top;
+  let mut passed = 0;
+  while passed < skipped {
+    below = below.checked_sub(1)?;
+    passed += slots(&stack[below]);
+  }
+  if taken != copied || passed != skipped {
+    return None;
+  }
+
+  let copies = stack[top..].to_vec();
+  stack.splice(below..below, copies);
+  Some(())
+}
+
+/// Joins the stacks two paths bring to one instruction, forgetting sources
+/// the paths disagree on.
+fn merge(stack: Vec<Value>, other: Vec<Value>) -> Option<Vec<Value>> {
+  if stack.len() != other.len() {
+    return None;
+  }
+  let merged = stack
+    .into_iter()
+    .zip(other)
+    .map(|(value, other)| {
+      if value == other {
+        value
+      } else {
+        Value::unknown(value.wide)
+      }
+    })
+    .collect();
+  Some(merged)
+}
+
+/// Instructions that backward branches go to, where the sources of the
+/// loop body are not known on the first pass.
+fn backward_targets(program: &[u8]) -> Option<HashSet<usize>> {
+  let mut targets = HashSet::new();
+  let mut at = 0;
+  while at < program.len() {
+    let offset = match program[at] {
+      opcode::IFEQ..=opcode::GOTO | opcode::IFNULL | opcode::IFNONNULL => {
+        i16::from_be_bytes([program[at + 1], program[at + 2]]) as i32
+      }
+      opcode::GOTO_W => int_at(program, at + 1),
+      opcode::TABLESWITCH | opcode::LOOKUPSWITCH => {
+        targets.extend(
+          switch_targets(program, at)?
+            .into_iter()
+            .filter(|&target| target <= at),
+        );
+        0
+      }
+      _ => 0,
+    };
+    if offset < 0 {
+      targets.insert(at.checked_add_signed(offset as isize)?);
+    }
+    at += length(program, at)?;
+  }
+  Some(targets)
+}
+
+/// Size in bytes of the instruction at `at`, with its operands.
+fn length(program: &[u8], at: usize) -> Option<usize> {
+  let length = match *program.get(at)? {
+    opcode::BIPUSH
+    | opcode::LDC
+    | opcode::ILOAD..=opcode::ALOAD
+    | opcode::ISTORE..=opcode::ASTORE
+    | opcode::RET
+    | opcode::NEWARRAY => 2,
+    opcode::SIPUSH
+    | opcode::LDC_W
+    | opcode::LDC2_W
+    | opcode::IINC
+    | opcode::IFEQ..=opcode::JSR
+    | opcode::GETSTATIC..=opcode::INVOKESTATIC
+    | opcode::NEW
+    | opcode::ANEWARRAY
+    | opcode::CHECKCAST
+    | opcode::INSTANCEOF
+    | opcode::IFNULL
+    | opcode::IFNONNULL => 3,
+    opcode::MULTIANEWARRAY => 4,
+    opcode::INVOKEINTERFACE
+    | opcode::INVOKEDYNAMIC
+    | opcode::GOTO_W
+    | opcode::JSR_W => 5,
+    opcode::WIDE => match *program.get(at + 1)? {
+      opcode::IINC => 6,
+      _ => 4,
+    },
+    opcode::TABLESWITCH => {
+      let operands = (at + 4) & !3;
+      let low = int_at(program, operands + 4);
+      let high = int_at(program, operands + 8);
+      let count = usize::try_from(high.checked_sub(low)?).ok()? + 1;
+      operands + 12 + 4 * count - at
+    }
+    opcode::LOOKUPSWITCH => {
+      let operands = (at + 4) & !3;
+      let count = usize::try_from(int_at(program, operands + 4)).ok()?;
+      operands + 8 + 8 * count - at
+    }
+    _ => 1,
+  };
+  Some(length)
+}
+
+/// Every place a `tableswitch` or `lookupswitch` at `at` may jump to.
+fn switch_targets(program: &[u8], at: usize) -> Option<Vec<usize>> {
+  let operands = (at + 4) & !3;
+  let jump = |offset| at.checked_add_signed(offset as isize);
+
+  let mut targets = vec![jump(int_at(program, operands))?];
+  let step = match program[at] {
+    opcode::TABLESWITCH => 4,
+    _ => 8,
+  };
+  let end = at + length(program, at)?;
+  let mut entry = operands + 12;
+  while entry < end {
+    targets.push(jump(int_at(program, entry))?);
+    entry += step;
+  }
+  Some(targets)
+}
+
+/// The name and descriptor of the `CONSTANT_InvokeDynamic` at `index`.
+fn dynamic_name_and_type(class: &Class, index: u16) -> Option<(&str, &str)> {
+  let pool = &class.constant_pool;
+  match pool.get(index).ok()? {
+    Entry::InvokeDynamicInfo {
+      name_and_type_index,
+      ..
+    } => pool.name_and_type(*name_and_type_index).ok(),
+    _ => None,
+  }
+}
+
+/// The element type named by the offset of an array load or store from
+/// `iaload` or `iastore`.
+fn array_kind(offset: u8) -> &'static str {
+  [
+    "int",
+    "long",
+    "float",
+    "double",
+    "object",
+    "byte/boolean",
+    "char",
+    "short",
+  ][offset as usize]
+}
+
+/// `Class.name(Parameters)`, as messages name methods.
+fn method_name(class: &str, name: &str, descriptor: &str) -> String {
+  let parameters = MethodDescriptor::parse(descriptor)
+    .map(|method| method.params)
+    .unwrap_or_default()
+    .iter()
+    .map(external_type)
+    .collect::<Vec<_>>();
+  format!(
+    "{}.{name}({})",
+    external_class(class),
+    parameters.join(", ")
+  )
+}
+
+fn is_wide(descriptor: &str) -> bool {
+  FieldType::parse(descriptor).is_ok_and(|field_type| field_type.slots() == 2)
+}
+
+/// A field type as Java source spells it.
+fn external_type(field_type: &FieldType) -> String {
+  match field_type {
+    FieldType::Base(base_type) => base_type.java_name().to_string(),
+    FieldType::Object(class) => external_class(class),
+    FieldType::Array(component) => format!("{}[]", external_type(component)),
+  }
+}
+
+/// A binary class name with dots, leaving out the package of `Object` and
+/// `String`.
+fn external_class(class: &str) -> String {
+  match class {
+    "java/lang/Object" => String::from("Object"),
+    "java/lang/String" => String::from("String"),
+    _ => class.replace('/', "."),
+  }
+}
@@ -1,6 +1,6 @@
 use std::{cmp::Ordering, ops::Neg};
 
-use crate::value::MistValue;
+use crate::{run::RuntimeError, value::MistValue};
 
 #[derive(Default, Debug)]
 pub struct MistStack {
@@ -24,6 +24,27 @@ impl MistStack {
   pub fn pop(&mut self) -> MistValue {
     self.stack.pop().expect("Stack is not empty.")
   }
+
+  /// [crate::opcode::POP2].
+  pub fn pop2(&mut self) {
+    if self.pop().category() == 1 {
+      self.pop();
+    }
+  }
+
+  fn extend<const N: usize>(&mut self, values: [MistValue; N]) {
+    self.stack.extend(values);
+  }
+
+  pub fn clear(&mut self) {
+    self.stack.clear();
+  }
+
+  /// The value `depth` entries below the top, left in place.
+  #[inline(always)]
+  pub fn peek(&self, depth: usize) -> MistValue {
+    self.stack[self.stack.len() - 1 - depth]
+  }
 }
 
 impl MistStack {
@@ -136,9 +157,62 @@ impl MistStack {
     self.push(value1);
   }
 
+  /// [crate::opcode::DUP_X2].
+  pub fn dup_x2(&mut self) {
+    let value1 = self.pop();
+    let value2 = self.pop();
+    if value2.category() == 2 {
+      self.extend([value1, value2, value1]);
+    } else {
+      let value3 = self.pop();
+      self.extend([value1, value3, value2, value1]);
+    }
+  }
+
   /// [crate::opcode::DUP2].
   pub fn dup2(&mut self) {
-    todo!();
+    let value1 = self.pop();
+    if value1.category() == 2 {
+      self.extend([value1, value1]);
+    } else {
+      let value2 = self.pop();
+      self.extend([value2, value1, value2, value1]);
+    }
+  }
+
+  /// [crate::opcode::DUP2_X1].
+  pub fn dup2_x1(&mut self) {
+    let value1 = self.pop();
+    if value1.category() == 2 {
+      let value2 = self.pop();
+      self.extend([value1, value2, value1]);
+    } else {
+      let value2 = self.pop();
+      let value3 = self.pop();
+      self.extend([value2, value1, value3, value2, value1]);
+    }
+  }
+
+  /// [crate::opcode::DUP2_X2].
+  pub fn dup2_x2(&mut self) {
+    let value1 = self.pop();
+    let value2 = self.pop();
+    match (value1.category(), value2.category()) {
+      (2, 2) => self.extend([value1, value2, value1]),
+      (2, _) => {
+        let value3 = self.pop();
+        self.extend([value1, value3, value2, value1]);
+      }
+      _ => {
+        let value3 = self.pop();
+        if value3.category() == 2 {
+          self.extend([value2, value1, value3, value2, value1]);
+        } else {
+          let value4 = self.pop();
+          self.extend([value2, value1, value4, value3, value2, value1]);
+        }
+      }
+    }
   }
 
   /// [crate::opcode::F2D].
@@ -232,14 +306,13 @@ impl MistStack {
   /// [crate::opcode::I2B].
   pub fn i2b(&mut self) {
     let int: i32 = self.pop().into();
-    self.push(MistValue::Byte(int as i8));
+    self.push(MistValue::Integer(int as i8 as i32));
   }
 
   /// [crate::opcode::I2C].
   pub fn i2c(&mut self) {
     let int: i32 = self.pop().into();
-    let chr = char::from_u32(int as u32).expect("Convert i32 to char");
-    self.push(MistValue::Char(chr));
+    self.push(MistValue::Integer(int as u16 as i32));
   }
 
   /// [crate::opcode::I2D].
@@ -263,14 +336,14 @@ impl MistStack {
   /// [crate::opcode::I2S].
   pub fn i2s(&mut self) {
     let int: i32 = self.pop().into();
-    self.push(MistValue::Short(int as i16));
+    self.push(MistValue::Integer(int as i16 as i32));
   }
 
   /// [crate::opcode::IADD].
   pub fn iadd(&mut self) {
     let rhs: i32 = self.pop().into();
     let lhs: i32 = self.pop().into();
-    self.push(MistValue::Integer(lhs + rhs));
+    self.push(MistValue::Integer(lhs.wrapping_add(rhs)));
   }
 
   /// [crate::opcode::IAND].
@@ -286,23 +359,27 @@ impl MistStack {
   }
 
   /// [crate::opcode::IDIV].
-  pub fn idiv(&mut self) {
+  pub fn idiv(&mut self) -> Result<(), RuntimeError> {
     let rhs: i32 = self.pop().into();
     let lhs: i32 = self.pop().into();
-    self.push(MistValue::Integer(lhs / rhs));
+    if rhs == 0 {
+      return Err(divide_by_zero());
+    }
+    self.push(MistValue::Integer(lhs.wrapping_div(rhs)));
+    Ok(())
   }
 
   /// [crate::opcode::IMUL].
   pub fn imul(&mut self) {
     let rhs: i32 = self.pop().into();
     let lhs: i32 = self.pop().into();
-    self.push(MistValue::Integer(lhs * rhs));
+    self.push(MistValue::Integer(lhs.wrapping_mul(rhs)));
   }
 
   /// [crate::opcode::INEG].
   pub fn ineg(&mut self) {
     let int: i32 = self.pop().into();
-    self.push(MistValue::Integer(int.neg()));
+    self.push(MistValue::Integer(int.wrapping_neg()));
   }
 
   /// [crate::opcode::IOR].
@@ -313,31 +390,35 @@ impl MistStack {
   }
 
   /// [crate::opcode::IREM].
-  pub fn irem(&mut self) {
+  pub fn irem(&mut self) -> Result<(), RuntimeError> {
     let rhs: i32 = self.pop().into();
     let lhs: i32 = self.pop().into();
-    self.push(MistValue::Integer(lhs % rhs));
+    if rhs == 0 {
+      return Err(divide_by_zero());
+    }
+    self.push(MistValue::Integer(lhs.wrapping_rem(rhs)));
+    Ok(())
   }
 
   /// [crate::opcode::ISHL].
   pub fn ishl(&mut self) {
     let rhs: i32 = self.pop().into();
     let lhs: i32 = self.pop().into();
-    self.push(MistValue::Integer(lhs << rhs));
+    self.push(MistValue::Integer(lhs.wrapping_shl(rhs as u32)));
   }
 
   /// [crate::opcode::ISHR].
   pub fn ishr(&mut self) {
     let rhs: i32 = self.pop().into();
     let lhs: i32 = self.pop().into();
-    self.push(MistValue::Integer(lhs >> rhs));
+    self.push(MistValue::Integer(lhs.wrapping_shr(rhs as u32)));
   }
 
   /// [crate::opcode::ISUB].
   pub fn isub(&mut self) {
     let rhs: i32 = self.pop().into();
     let lhs: i32 = self.pop().into();
-    self.push(MistValue::Integer(lhs - rhs));
+    self.push(MistValue::Integer(lhs.wrapping_sub(rhs)));
   }
 
   /// [crate::opcode::IUSHR].
@@ -346,7 +427,7 @@ impl MistStack {
     let lhs: i32 = self.pop().into();
     let rhs = rhs as u32;
     let lhs = lhs as u32;
-    self.push(MistValue::Integer((lhs >> rhs) as i32));
+    self.push(MistValue::Integer(lhs.wrapping_shr(rhs) as i32));
   }
 
   /// [crate::opcode::IXOR].
@@ -378,7 +459,7 @@ impl MistStack {
   pub fn ladd(&mut self) {
     let rhs: i64 = self.pop().into();
     let lhs: i64 = self.pop().into();
-    self.push(MistValue::Long(lhs + rhs));
+    self.push(MistValue::Long(lhs.wrapping_add(rhs)));
   }
 
   /// [crate::opcode::LAND].
@@ -390,8 +471,8 @@ impl MistStack {
 
   /// [crate::opcode::LCMP].
   pub fn lcmp(&mut self) {
-    let value1: i64 = self.pop().into();
     let value2: i64 = self.pop().into();
+    let value1: i64 = self.pop().into();
     match value1.cmp(&value2) {
       Ordering::Less => self.push(MistValue::Integer(-1)),
       Ordering::Equal => self.push(MistValue::Integer(0)),
@@ -405,23 +486,27 @@ impl MistStack {
   }
 
   /// [crate::opcode::LDIV].
-  pub fn ldiv(&mut self) {
+  pub fn ldiv(&mut self) -> Result<(), RuntimeError> {
     let rhs: i64 = self.pop().into();
     let lhs: i64 = self.pop().into();
-    self.push(MistValue::Long(lhs / rhs));
+    if rhs == 0 {
+      return Err(divide_by_zero());
+    }
+    self.push(MistValue::Long(lhs.wrapping_div(rhs)));
+    Ok(())
   }
 
   /// [crate::opcode::LMUL].
   pub fn lmul(&mut self) {
     let rhs: i64 = self.pop().into();
     let lhs: i64 = self.pop().into();
-    self.push(MistValue::Long(lhs * rhs));
+    self.push(MistValue::Long(lhs.wrapping_mul(rhs)));
   }
 
   /// [crate::opcode::LNEG].
   pub fn lneg(&mut self) {
     let long: i64 = self.pop().into();
-    self.push(MistValue::Long(long.neg()));
+    self.push(MistValue::Long(long.wrapping_neg()));
   }
 
   /// [crate::opcode::LOR].
@@ -432,40 +517,42 @@ impl MistStack {
   }
 
   /// [crate::opcode::LREM].
-  pub fn lrem(&mut self) {
+  pub fn lrem(&mut self) -> Result<(), RuntimeError> {
     let rhs: i64 = self.pop().into();
     let lhs: i64 = self.pop().into();
-    self.push(MistValue::Long(lhs % rhs));
+    if rhs == 0 {
+      return Err(divide_by_zero());
+    }
+    self.push(MistValue::Long(lhs.wrapping_rem(rhs)));
+    Ok(())
   }
 
   /// [crate::opcode::LSHL].
   pub fn lshl(&mut self) {
-    let rhs: i64 = self.pop().into();
+    let rhs: i32 = self.pop().into();
     let lhs: i64 = self.pop().into();
-    self.push(MistValue::Long(lhs << rhs));
+    self.push(MistValue::Long(lhs.wrapping_shl(rhs as u32)));
   }
 
   /// [crate::opcode::LSHR].
   pub fn lshr(&mut self) {
-    let rhs: i64 = self.pop().into();
+    let rhs: i32 = self.pop().into();
     let lhs: i64 = self.pop().into();
-    self.push(MistValue::Long(lhs >> rhs));
+    self.push(MistValue::Long(lhs.wrapping_shr(rhs as u32)));
   }
 
   /// [crate::opcode::LSUB].
   pub fn lsub(&mut self) {
     let rhs: i64 = self.pop().into();
     let lhs: i64 = self.pop().into();
-    self.push(MistValue::Long(lhs - rhs));
+    self.push(MistValue::Long(lhs.wrapping_sub(rhs)));
   }
 
   /// [crate::opcode::LUSHR].
   pub fn lushr(&mut self) {
-    let rhs: i64 = self.pop().into();
+    let rhs: i32 = self.pop().into();
     let lhs: i64 = self.pop().into();
-    let rhs = rhs as u64;
-    let lhs = lhs as u64;
-    self.push(MistValue::Long((lhs >> rhs) as i64));
+    self.push(MistValue::Long((lhs as u64).wrapping_shr(rhs as u32) as i64));
   }
 
   /// [crate::opcode::LXOR].
@@ -475,3 +562,7 @@ impl MistStack {
     self.push(MistValue::Long(lhs ^ rhs));
   }
 }
+
+fn divide_by_zero() -> RuntimeError {
+  RuntimeError::new("java/lang/ArithmeticException", "/ by zero")
+}
@@ -19,6 +19,8 @@ pub enum MistValue {
   // TODO: use u16?
   Char(char),
   ObjectReference(usize),
+  /// Where a `ret` continues, pushed by `jsr`.
+  ReturnAddress(usize),
 }
 
 impl MistValue {
@@ -33,6 +35,16 @@ impl MistValue {
       MistValue::Double(..) => 'D',
       MistValue::Char(..) => 'C',
       MistValue::ObjectReference(..) => 'A',
+      MistValue::ReturnAddress(..) => 'R',
+    }
+  }
+
+  /// The computational type category: 2 for longs and doubles, which take
+  /// two local variables, and 1 for everything else.
+  pub fn category(&self) -> usize {
+    match self {
+      MistValue::Long(..) | MistValue::Double(..) => 2,
+      _ => 1,
     }
   }
 }
@@ -0,0 +1,359 @@
+//! Runs the programs in `tests/conformance` and checks what their `main`
+//! returns. Each program sets one bit of its result per check that passes,
+//! and the `.class` files are compiled from the `.java` files next to them
+//! with `javac --release 8`, except for `Concat`, compiled by javac 17 so
+//! that string concatenation goes through `invokedynamic`.
+
+use std::process::{Command, Output};
+
+fn mist_output(args: &[&str]) -> Output {
+  Command::new(env!("CARGO_BIN_EXE_mist"))
+    .current_dir(env!("CARGO_MANIFEST_DIR"))
+    .args(args)
+    .output()
+    .expect("mist runs")
+}
+
+/// What mist prints to standard output when run with `args`.
+fn mist(args: &[&str]) -> String {
+  String::from_utf8_lossy(&mist_output(args).stdout).into_owned()
+}
+
+fn run(main_class: &str, args: &[&str]) -> String {
+  mist(&[&["-cp", "tests/conformance", main_class], args].concat())
+}
+
+fn assert_returns(main_class: &str, expected: i32) {
+  assert_returns_with_args(main_class, &[], expected);
+}
+
+fn assert_returns_with_args(main_class: &str, args: &[&str], expected: i32) {
+  assert_prints(main_class, args, "", expected);
+}
+
+/// Checks that the program writes `printed` to standard output before
+/// returning.
+fn assert_prints(
+  main_class: &str,
+  args: &[&str],
+  printed: &str,
+  expected: i32,
+) {
+  assert_eq!(
+    run(main_class, args),
+    format!("{printed}result: Some(Integer({expected}))\n")
+  );
+}
+
+#[test]
+fn loops() {
+  assert_returns("Loops", 0b1111_1111);
+}
+
+#[test]
+fn conditionals() {
+  assert_returns("Conditionals", 0b1111_1111);
+}
+
+#[test]
+fn do_while() {
+  assert_returns("DoWhile", 0b1_1111);
+}
+
+#[test]
+fn switches() {
+  assert_returns("Switches", 0b11_1111);
+}
+
+#[test]
+fn wide_locals() {
+  assert_returns("WideLocals", 0b111);
+}
+
+#[test]
+fn boxing() {
+  assert_returns("Boxing", 0b1111_1111);
+}
+
+#[test]
+fn bitwise() {
+  assert_returns("Bitwise", 0b1111_1111);
+}
+
+/// `BadOpcode.class` has an undefined opcode patched in; the comment in
+/// `BadOpcode.java` says where.
+#[test]
+fn bad_opcode() {
+  let output = mist_output(&["-cp", "tests/conformance", "BadOpcode"]);
+  assert_eq!(
+    String::from_utf8_lossy(&output.stderr),
+    "\
+Exception in thread \"main\" java.lang.VerifyError: Bad instruction 0xcb
+\tat BadOpcode.answer(BadOpcode.java:7)
+\tat BadOpcode.main(BadOpcode.java:11)
+"
+  );
+  assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn bad_array_type() {
+  let output = mist_output(&["-cp", "tests/conformance", "BadArrayType"]);
+  assert_eq!(
+    String::from_utf8_lossy(&output.stderr),
+    "\
+Exception in thread \"main\" java.lang.VerifyError: Bad array type 3
+\tat BadArrayType.allocate(BadArrayType.java:7)
+\tat BadArrayType.main(BadArrayType.java:11)
+"
+  );
+  assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn category_2() {
+  assert_returns("Category2", 0b1111_1111);
+}
+
+#[test]
+fn primitive_arrays() {
+  assert_returns("PrimitiveArrays", 0b1111_1111);
+}
+
+#[test]
+fn reference_arrays() {
+  assert_returns("ReferenceArrays", 0b1111_1111);
+}
+
+#[test]
+fn cloning() {
+  assert_returns_with_args("Cloning", &["a", "b"], 0b1111_1111);
+}
+
+#[test]
+fn math_functions() {
+  assert_returns("MathFunctions", 0b1111_1111);
+}
+
+#[test]
+fn math_bits() {
+  assert_prints(
+    "MathBits",
+    &[],
+    "\
+-0.0
+NaN
+1.4142135623730951
+-3.0
+-1.0
+-0.0
+-0.0
+2.0
+-4.0
+0.0
+0.0
+-0.0
+0.0
+-0.0
+NaN
+NaN
+-0.0
+-1.0
+NaN
+-0.0
+-Infinity
+NaN
+NaN
+1.0
+1.4142135623730951
+5.0
+Infinity
+0.0
+-Infinity
+3.0
+-0.0
+-3.141592653589793
+-0.0
+-2
+0
+0
+-9223372036854775808
+",
+    0,
+  );
+}
+
+#[test]
+fn concat() {
+  assert_returns("Concat", 0b1111_1111);
+}
+
+/// `LegacyConcat` is compiled for Java 8, so its concatenations go through
+/// `StringBuilder`.
+#[test]
+fn legacy_concat() {
+  assert_returns("LegacyConcat", 0b1111_1111);
+}
+
+#[test]
+fn strings() {
+  assert_returns_with_args(
+    "Strings",
+    &["first", "z\u{fc}rich \u{2603}"],
+    0b1111_1111,
+  );
+}
+
+#[test]
+fn null_pointers() {
+  assert_prints(
+    "NullPointers",
+    &[],
+    "Cannot read field \"value\" because \"<parameter2>.next\" is null\n\
+     Cannot assign field \"value\" because \"<parameter2>.next\" is null\n\
+     Cannot invoke \"NullPointers.next()\" because \"<local2>\" is null\n\
+     Cannot invoke \"NullPointers.next()\" because \"NullPointers.shared\" is null\n\
+     Cannot invoke \"NullPointers.next()\" because \"NullPointers.many[1]\" is null\n\
+     Cannot invoke \"NullPointers.next()\" because the return value of \"NullPointers.none()\" is null\n\
+     Cannot read the array length because \"<local2>\" is null\n\
+     Cannot throw exception because \"<local2>\" is null\n\
+     Cannot enter synchronized block because \"<local2>\" is null\n\
+     Cannot invoke \"NullPointers$Shape.area()\" because \"<local2>\" is null\n\
+     Cannot load from long array because \"<local2>\" is null\n\
+     Cannot store to object array because \"<local2>\" is null\n\
+     Cannot invoke \"NullPointers.next()\" because the return value of \"NullPointers.next()\" is null\n",
+    0b11_1111,
+  );
+}
+
+#[test]
+fn exceptions() {
+  assert_returns("Exceptions", 0b1111_1111);
+}
+
+#[test]
+fn initialization() {
+  assert_returns("Initialization", 0b111_1111);
+}
+
+#[test]
+fn dispatch() {
+  assert_returns("Dispatch", 0b1111_1111);
+}
+
+/// `Loading$Missing.class` is left out and `Loading$CircleB.class` comes from
+/// a different compile; the comment in `Loading.java` says how.
+#[test]
+fn loading() {
+  assert_returns("Loading", 0b111);
+}
+
+/// `StackTraces` prints the frames of a caught exception, then dies of an
+/// uncaught one with a cause.
+#[test]
+fn stack_traces() {
+  let output = mist_output(&["-cp", "tests/conformance", "StackTraces"]);
+  assert_eq!(
+    String::from_utf8_lossy(&output.stdout),
+    "\
+StackTraces.fail:4
+StackTraces.fail:6
+StackTraces.main:19
+"
+  );
+  assert_eq!(
+    String::from_utf8_lossy(&output.stderr),
+    "\
+Exception in thread \"main\" java.lang.RuntimeException: wrapped
+\tat StackTraces.wrap(StackTraces.java:13)
+\tat StackTraces.main(StackTraces.java:29)
+Caused by: java.lang.IllegalStateException: bottom
+\tat StackTraces.fail(StackTraces.java:4)
+\tat StackTraces.fail(StackTraces.java:6)
+\tat StackTraces.fail(StackTraces.java:6)
+\tat StackTraces.wrap(StackTraces.java:11)
+\t... 1 more
+"
+  );
+  assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn printing() {
+  assert_prints(
+    "Printing",
+    &[],
+    "true\n\
+     \u{e9}\n\
+     -42\n\
+     -9223372036854775808\n\
+     1.5\n\
+     0.30000000000000004\n\
+     1.0E7\n\
+     1.0E-4\n\
+     100.0\n\
+     NaN\n\
+     -Infinity\n\
+     -0.0\n\
+     3.4028235E38\n\
+     hi\n\
+     caf\u{e9} \u{2603}\n\
+     null\n\
+     (3, -4)\n\
+     null\n\
+     Printing$Nameless@cafe\n\
+     1+2\n\
+     7\n\
+     false\n\
+     x\n\
+     2.5\n\
+     1|    2|3    |-0004|1,234,567|+5|(6)\n\
+     ff|FF|10|0xff|ffffffff|ff\n\
+     a|     right|left      |tru|UP\n\
+     3.141590|2.68|3|    -1.500|1,234,567.89|0.3\n\
+     1.234568e+04|1.23e-04|1.000000E+100|0.000000e+00\n\
+     q|A|true|false|true|%|(1, 2)\n\
+     hello world\n\
+     1.0 10\n\
+     1.5|0.100|100000000000000000000.000000\n\
+     [    1.41]\n\
+     java.util.IllegalFormatArgumentIndexException: Illegal format argument index = 0\n\
+     java.util.IllegalFormatArgumentIndexException: Format argument index: (not representable as int)\n\
+     java.util.UnknownFormatConversionException: Conversion = 'q'\n\
+     java.util.UnknownFormatConversionException: Conversion = '5'\n\
+     java.util.UnknownFormatConversionException: Conversion = '%'\n",
+    0b1111_1111,
+  );
+}
+
+/// `tests/jar/app.jar` holds `Application`, deflated, and the `Helper` it
+/// calls, stored, both compiled from `tests/jar/src`. Its manifest wraps the
+/// long `Main-Class` onto a continuation line.
+#[test]
+fn jar() {
+  assert_eq!(
+    mist(&["-jar", "tests/jar/app.jar", "arg"]),
+    "result: Some(Integer(7))\n"
+  );
+}
+
+/// `UsesJar` is in the `tests/jar` directory and calls `Helper` in the JAR.
+#[test]
+fn classpath_with_jar() {
+  assert_eq!(
+    mist(&["-cp", "tests/jar/app.jar:tests/jar", "UsesJar"]),
+    "result: Some(Integer(3))\n"
+  );
+}
+
+#[test]
+fn class_literal() {
+  assert_returns("ClassLiteral", 0b1111_1111);
+}
+
+#[test]
+fn missing_main_class() {
+  let output = mist_output(&["-cp", "tests/conformance", "Nope"]);
+  assert!(output.stdout.is_empty());
+  assert_eq!(output.status.code(), Some(1));
+}
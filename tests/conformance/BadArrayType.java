@@ -0,0 +1,13 @@
+/**
+ * The {@code newarray int} in {@code allocate} is replaced after compiling by
+ * a {@code newarray} of the undefined type 3.
+ */
+public class BadArrayType {
+  static int[] allocate() {
+    return new int[4];
+  }
+
+  public static int main(String[] args) {
+    return allocate().length;
+  }
+}
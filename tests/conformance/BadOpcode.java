@@ -0,0 +1,13 @@
+/**
+ * The {@code bipush 42} in {@code answer} is replaced after compiling by a
+ * {@code nop} and the undefined opcode {@code 0xcb}.
+ */
+public class BadOpcode {
+  static int answer() {
+    return 42;
+  }
+
+  public static int main(String[] args) {
+    return answer();
+  }
+}
@@ -0,0 +1,18 @@
+public class Bitwise {
+  public static int main(String[] args) {
+    int checks = 0;
+    int x = args.length + 5;
+    checks |= (x ^ 3) == 6 ? 1 : 0;
+    checks |= (x & 6) == 4 && (x | 8) == 13 ? 2 : 0;
+    checks |= (-x ^ x) == -2 && (~x) == -6 ? 4 : 0;
+    checks |= (x << 29) == -1610612736 && (-x >> 1) == -3 ? 8 : 0;
+    checks |= (-x >>> 28) == 15 && (x << 33) == 10 ? 16 : 0;
+    long y = x * 0x100000000L + 3;
+    checks |= (y ^ 0x500000001L) == 2 && (y & 0xF) == 3 ? 32 : 0;
+    checks |= (y | 4) == 0x500000007L && (~y) == -0x500000004L ? 64 : 0;
+    checks |= (-y >>> 60) == 15 && (-y >> 32) == -6 && (y << 64) == y
+        ? 128
+        : 0;
+    return checks;
+  }
+}
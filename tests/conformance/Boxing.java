@@ -0,0 +1,29 @@
+public class Boxing {
+  static boolean same(Object a, Object b) {
+    return a == b;
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+    Integer x = 100, y = 100;
+    checks |= x == y ? 1 : 0;
+    checks |= same(-128, -128) && !same(128, 128) && !same(-129, -129) ? 2 : 0;
+    checks |= same((short) 127, (short) 127) && same(5L, 5L)
+        && !same(1000L, 1000L)
+        ? 4
+        : 0;
+    checks |= same((byte) -100, (byte) -100) && same('a', 'a')
+        && !same('\u00e9', '\u00e9')
+        ? 8
+        : 0;
+    checks |= same(true, Boolean.TRUE) && same(false, Boolean.FALSE)
+        && Boolean.TRUE.booleanValue()
+        ? 16
+        : 0;
+    checks |= !same(1.0, 1.0) && !same(1.0f, 1.0f) ? 32 : 0;
+    checks |= same(Integer.valueOf(7), 7) && !same(7, 7L) ? 64 : 0;
+    Integer big = 1000;
+    checks |= big.equals(1000) && big != Integer.valueOf(1000) ? 128 : 0;
+    return checks;
+  }
+}
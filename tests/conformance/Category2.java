@@ -0,0 +1,70 @@
+public class Category2 {
+  long count;
+  static long total;
+
+  static long mix(int a, long b, int c, double d, long e) {
+    return a + b * 10 + c * 100 + (long) (d * 1000) + e * 10000;
+  }
+
+  double scale(double x, int times, long offset) {
+    return x * times + offset;
+  }
+
+  long add(long x) {
+    return this.count += x;
+  }
+
+  static long accumulate(long x) {
+    return total += x;
+  }
+
+  static long sideEffect() {
+    total = 7;
+    return 123456789012L;
+  }
+
+  static int storeAndReturn(int[] array, int index, int value) {
+    return array[index] = value;
+  }
+
+  static long locals(int n) {
+    long a = 1;
+    double b = 2.5;
+    int c = 3;
+    long d = a << 40;
+    for (int i = 0; i < n; i++) {
+      a = a * 3 + c;
+      b = b * 2;
+    }
+    return a + (long) b + (d >> 38) + (-d >>> 60) + (-d >> 60);
+  }
+
+  static int compare(long x, long y, double p, double q) {
+    int bits = 0;
+    if (x < y) bits |= 1;
+    if (x > y) bits |= 2;
+    if (p < q) bits |= 4;
+    if (p > q) bits |= 8;
+    if (p != p) bits |= 16;
+    return bits;
+  }
+
+  public static int main(String[] args) {
+    Category2 object = new Category2();
+    int checks = 0;
+    checks += mix(1, 2, 3, 4.5, 6) == 64821 ? 1 : 0;
+    checks += object.scale(1.5, 4, 10000000000L) == 10000000006.0 ? 2 : 0;
+    object.add(40);
+    checks += object.add(2) == 42 && object.count == 42 ? 4 : 0;
+    accumulate(5000000000L);
+    checks += accumulate(1) == 5000000001L ? 8 : 0;
+    sideEffect();
+    checks += total == 7 ? 16 : 0;
+    int[] array = new int[2];
+    checks += storeAndReturn(array, 1, 9) == 9 && array[1] == 9 ? 32 : 0;
+    checks += locals(5) == 704 ? 64 : 0;
+    checks += compare(-1, 1, 0.5, 0.25) == 9
+        && compare(5000000000L, 1, 0.0 / 0.0, 1) == 18 ? 128 : 0;
+    return checks;
+  }
+}
@@ -0,0 +1,32 @@
+public class ClassLiteral {
+  static class Nested {}
+
+  public static int main(String[] args) {
+    int checks = 0;
+    Object literal = ClassLiteral.class;
+    checks |= literal == new ClassLiteral().getClass() ? 1 : 0;
+    checks |= String.class.getName().equals("java.lang.String")
+        && "text".getClass() == String.class
+        ? 2
+        : 0;
+    checks |= int[].class.getName().equals("[I")
+        && new int[0].getClass() == int[].class
+        && int[].class.isArray()
+        ? 4
+        : 0;
+    checks |= String[][].class.getName().equals("[[Ljava.lang.String;") ? 8 : 0;
+    checks |= Nested.class.getName().equals("ClassLiteral$Nested") ? 16 : 0;
+    checks |= Runnable.class.isInterface() && !Nested.class.isInterface()
+        ? 32
+        : 0;
+    checks |= Runnable.class.toString().equals("interface java.lang.Runnable")
+        && Nested.class.toString().equals("class ClassLiteral$Nested")
+        ? 64
+        : 0;
+    checks |= args.getClass().toString().equals("class [Ljava.lang.String;")
+        && literal != Nested.class
+        ? 128
+        : 0;
+    return checks;
+  }
+}
@@ -0,0 +1,33 @@
+import java.io.Serializable;
+
+public class Cloning {
+  public static int main(String[] args) {
+    int checks = 0;
+    int[] ints = {1, 2, 3};
+    int[] copy = ints.clone();
+    copy[0] = 5;
+    checks |= copy != ints && ints[0] == 1 && copy[2] == 3 ? 1 : 0;
+
+    String[] names = {"a", "b"};
+    String[] strings = names.clone();
+    checks |= strings != names && strings[1] == names[1] ? 2 : 0;
+
+    long[][] grid = {{1L, 2L}, {3L}};
+    long[][] rows = grid.clone();
+    rows[1][0] = 4L;
+    checks |= rows[0] == grid[0] && grid[1][0] == 4L ? 4 : 0;
+
+    double[] empty = new double[0].clone();
+    checks |= empty.length == 0 ? 8 : 0;
+
+    Object object = ints;
+    Cloneable cloneable = ints;
+    Serializable serializable = strings;
+    checks |= object instanceof Cloneable && cloneable != serializable ? 16 : 0;
+    checks |= ints.equals(object) && !ints.equals(copy) ? 32 : 0;
+    String string = object.toString();
+    checks |= string.charAt(0) == '[' && string.indexOf('@') == 2 ? 64 : 0;
+    checks |= args.clone().length == args.length ? 128 : 0;
+    return checks;
+  }
+}
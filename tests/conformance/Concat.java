@@ -0,0 +1,52 @@
+public class Concat {
+  static class Point {
+    final int x;
+    final int y;
+
+    Point(int x, int y) {
+      this.x = x;
+      this.y = y;
+    }
+
+    public String toString() {
+      return "(" + x + ", " + y + ")";
+    }
+  }
+
+  static String describe(String name, Object value) {
+    return name + '=' + value;
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+    int n = -42;
+    long big = 10000000000L;
+    char c = 'q';
+    boolean b = true;
+    byte small = -1;
+    checks += ("n" + n + c + big + b + small).equals("n-42q10000000000true-1") ? 1 : 0;
+    float f = 1.0f;
+    double d = 0.1;
+    checks += (f + "|" + d + "|" + 1e10 + "|" + (0.0 / 0.0)).equals("1.0|0.1|1.0E10|NaN") ? 2 : 0;
+    String none = null;
+    Object nothing = null;
+    checks += ("x" + none + nothing).equals("xnullnull") ? 4 : 0;
+    checks += describe("p", new Point(3, -4)).equals("p=(3, -4)") ? 8 : 0;
+    checks += ("\u0001" + n + "\u0002").equals("\u0001-42\u0002") ? 16 : 0;
+    String s = "";
+    for (int i = 0; i < 5; i++) {
+      s += i;
+    }
+    checks += s.equals("01234") ? 32 : 0;
+    checks += String.valueOf(new char[] {'h', 'i'}).equals("hi")
+        && String.valueOf(3.5).equals("3.5")
+        && String.valueOf(nothing).equals("null")
+        && String.valueOf('z').equals("z") ? 64 : 0;
+    try {
+      String.valueOf((char[]) null);
+    } catch (NullPointerException e) {
+      checks += 128;
+    }
+    return checks;
+  }
+}
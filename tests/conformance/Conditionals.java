@@ -0,0 +1,98 @@
+public class Conditionals {
+  static int sign(int x) {
+    if (x > 0) {
+      return 1;
+    } else if (x < 0) {
+      return -1;
+    } else {
+      return 0;
+    }
+  }
+
+  static int classify(int a, int b) {
+    if (a < b) {
+      if (a < 0) {
+        return 1;
+      }
+      return 2;
+    } else if (a == b) {
+      return 3;
+    } else {
+      if (b >= 0 && a <= 100) {
+        return 4;
+      }
+      return 5;
+    }
+  }
+
+  static int compareAll(int a, int b) {
+    int bits = 0;
+    if (a == b) bits |= 1;
+    if (a != b) bits |= 2;
+    if (a < b) bits |= 4;
+    if (a <= b) bits |= 8;
+    if (a > b) bits |= 16;
+    if (a >= b) bits |= 32;
+    return bits;
+  }
+
+  static int compareZero(int a) {
+    int bits = 0;
+    if (a == 0) bits |= 1;
+    if (a != 0) bits |= 2;
+    if (a < 0) bits |= 4;
+    if (a <= 0) bits |= 8;
+    if (a > 0) bits |= 16;
+    if (a >= 0) bits |= 32;
+    return bits;
+  }
+
+  static int references(Object a, Object b) {
+    int bits = 0;
+    if (a == null) bits |= 1;
+    if (a != null) bits |= 2;
+    if (a == b) bits |= 4;
+    if (a != b) bits |= 8;
+    return bits;
+  }
+
+  static int longs(int a, int b) {
+    long x = a;
+    long y = b;
+    int bits = 0;
+    if (x < y) bits |= 1;
+    if (x == y) bits |= 2;
+    if (x > y) bits |= 4;
+    return bits;
+  }
+
+  static int max(int a, int b, int c) {
+    return a > b ? (a > c ? a : c) : (b > c ? b : c);
+  }
+
+  static boolean between(int x, int low, int high) {
+    return low <= x && x <= high;
+  }
+
+  public static int main(String[] args) {
+    Object o = new Object();
+    int checks = 0;
+    checks += sign(-5) + sign(0) + sign(300) == 0 ? 1 : 0;
+    checks += classify(-3, 2) * 10000 + classify(1, 2) * 1000
+        + classify(7, 7) * 100 + classify(50, 3) * 10 + classify(500, 3)
+        == 12345 ? 2 : 0;
+    checks += compareAll(1, 2) == 14 && compareAll(2, 2) == 41
+        && compareAll(-1, -2) == 50 ? 4 : 0;
+    checks += compareZero(-7) == 14 && compareZero(0) == 41
+        && compareZero(7) == 50 ? 8 : 0;
+    checks += references(null, null) == 5 && references(o, o) == 6
+        && references(o, null) == 10 ? 16 : 0;
+    checks += longs(-3, 2) == 1 && longs(4, 4) == 2 && longs(5, -9) == 4
+        ? 32 : 0;
+    checks += max(3, 9, 4) == 9 && max(8, 1, 2) == 8 && max(1, 2, 7) == 7
+        ? 64 : 0;
+    checks += between(5, 1, 10) && !between(11, 1, 10) && !between(0, 1, 10)
+        ? 128 : 0;
+    return checks;
+  }
+}
@@ -0,0 +1,91 @@
+public class Dispatch {
+  interface Named {
+    default String name() {
+      return "named";
+    }
+  }
+
+  interface Left extends Named {
+    default String name() {
+      return "left";
+    }
+  }
+
+  interface Right extends Named {}
+
+  interface Other {
+    default String name() {
+      return "other";
+    }
+  }
+
+  static class Diamond implements Left, Right {}
+
+  static class Chosen implements Left, Other {
+    public String name() {
+      return Other.super.name().concat(Left.super.name());
+    }
+  }
+
+  static class Animal {
+    String sound() {
+      return "...";
+    }
+
+    private String secret() {
+      return "animal";
+    }
+
+    String reveal() {
+      return secret();
+    }
+  }
+
+  static class Dog extends Animal implements Named {
+    String sound() {
+      return "woof";
+    }
+
+    String secret() {
+      return "dog";
+    }
+  }
+
+  static class Puppy extends Dog {
+    String sound() {
+      return super.sound().concat("!");
+    }
+  }
+
+  abstract static class Shape {
+    abstract int sides();
+
+    int twice() {
+      return sides() * 2;
+    }
+  }
+
+  static class Square extends Shape {
+    int sides() {
+      return 4;
+    }
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+    Animal puppy = new Puppy();
+    checks |= puppy.sound().equals("woof!") ? 1 : 0;
+    checks |= puppy.reveal().equals("animal") ? 2 : 0;
+    checks |= ((Dog) puppy).secret().equals("dog") ? 4 : 0;
+    checks |= ((Named) puppy).name().equals("named") ? 8 : 0;
+    Named diamond = new Diamond();
+    checks |= diamond.name().equals("left") ? 16 : 0;
+    Other chosen = new Chosen();
+    checks |= chosen.name().equals("otherleft") ? 32 : 0;
+    Shape square = new Square();
+    checks |= square.twice() == 8 ? 64 : 0;
+    Object object = puppy;
+    checks |= object instanceof Named && !(object instanceof Left) ? 128 : 0;
+    return checks;
+  }
+}
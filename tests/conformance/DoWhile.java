@@ -0,0 +1,53 @@
+public class DoWhile {
+  static int gcd(int a, int b) {
+    do {
+      int r = a % b;
+      a = b;
+      b = r;
+    } while (b != 0);
+    return a;
+  }
+
+  static int triangle(int rows) {
+    int cells = 0;
+    int row = 0;
+    do {
+      int column = 0;
+      do {
+        cells++;
+        column++;
+      } while (column <= row);
+      row++;
+    } while (row < rows);
+    return cells;
+  }
+
+  static int firstSquareAbove(int limit) {
+    int n = 0;
+    while (true) {
+      n++;
+      if (n * n > limit) {
+        break;
+      }
+    }
+    return n;
+  }
+
+  static int runsOnce(int n) {
+    int runs = 0;
+    do {
+      runs++;
+    } while (n > 100);
+    return runs;
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+    checks += gcd(1071, 462) == 21 ? 1 : 0;
+    checks += gcd(17, 5) == 1 ? 2 : 0;
+    checks += triangle(20) == 210 ? 4 : 0;
+    checks += firstSquareAbove(1000) == 32 ? 8 : 0;
+    checks += runsOnce(0) == 1 ? 16 : 0;
+    return checks;
+  }
+}
@@ -0,0 +1,111 @@
+public class Exceptions {
+  static class Base extends Exception {
+    Base(String message) {
+      super(message);
+    }
+  }
+
+  static class Derived extends Base {
+    Derived(String message) {
+      super(message);
+    }
+  }
+
+  static String trail = "";
+
+  static void mark(String step) {
+    trail = trail.concat(step);
+  }
+
+  static int depth(int n) throws Base {
+    if (n == 0) {
+      throw new Derived("deep");
+    }
+    try {
+      return depth(n - 1) + 1;
+    } finally {
+      mark(String.valueOf(n));
+    }
+  }
+
+  static int rethrow() throws Base {
+    try {
+      return depth(3);
+    } catch (Derived e) {
+      mark("r");
+      throw e;
+    }
+  }
+
+  static int nested() {
+    try {
+      try {
+        throw new IllegalStateException("inner");
+      } catch (IllegalArgumentException e) {
+        return 1;
+      } finally {
+        mark("f");
+      }
+    } catch (RuntimeException e) {
+      return e.getMessage().equals("inner") ? 2 : 3;
+    }
+  }
+
+  static int replaced() {
+    try {
+      try {
+        throw new Base("first");
+      } catch (Base e) {
+        Base replacement = new Derived("second");
+        throw replacement;
+      }
+    } catch (Derived e) {
+      return e.getMessage().equals("second") ? 1 : 0;
+    } catch (Base e) {
+      return 0;
+    }
+  }
+
+  static int implicit(int[] array, int index) {
+    try {
+      return array[index] / index;
+    } catch (ArithmeticException e) {
+      return -1;
+    } catch (RuntimeException e) {
+      return -2;
+    }
+  }
+
+  static int loop() {
+    int caught = 0;
+    for (int i = 0; i < 4; i++) {
+      try {
+        if (i % 2 == 0) {
+          throw new Derived("even");
+        }
+      } catch (Base e) {
+        caught++;
+      }
+    }
+    return caught;
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+    try {
+      rethrow();
+    } catch (Base e) {
+      if (e instanceof Derived && e.getMessage().equals("deep")) {
+        checks |= 1;
+      }
+    }
+    checks |= trail.equals("123r") ? 2 : 0;
+    checks |= nested() == 2 && trail.equals("123rf") ? 4 : 0;
+    checks |= replaced() == 1 ? 8 : 0;
+    checks |= implicit(new int[] {1, 2}, 0) == -1 ? 16 : 0;
+    checks |= implicit(new int[] {1, 2}, 2) == -2 ? 32 : 0;
+    checks |= implicit(new int[] {1, 4}, 1) == 4 ? 64 : 0;
+    checks |= loop() == 2 ? 128 : 0;
+    return checks;
+  }
+}
@@ -0,0 +1,67 @@
+public class Initialization {
+  static String trail = "";
+
+  static void mark(String step) {
+    trail = trail.concat(step);
+  }
+
+  static class Parent {
+    static {
+      mark("P");
+    }
+
+    static int parent = 1;
+  }
+
+  static class Child extends Parent {
+    static final int CONSTANT = 42;
+    static int child = 2;
+
+    static {
+      mark("C");
+    }
+  }
+
+  static class Broken {
+    static int value = 1 / zero();
+
+    static int zero() {
+      return 0;
+    }
+  }
+
+  static class Counter {
+    static int count;
+
+    static {
+      count++;
+    }
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+    checks |= Child.CONSTANT == 42 && trail.equals("") ? 1 : 0;
+    checks |= Parent.parent == 1 && trail.equals("P") ? 2 : 0;
+    checks |= Child.child == 2 && trail.equals("PC") ? 4 : 0;
+    checks |= Child.parent == 1 && trail.equals("PC") ? 8 : 0;
+    try {
+      checks |= Broken.value;
+    } catch (ExceptionInInitializerError e) {
+      if (e.getCause() instanceof ArithmeticException) {
+        checks |= 16;
+      }
+    }
+    try {
+      checks |= Broken.value;
+    } catch (NoClassDefFoundError e) {
+      String message = e.getMessage();
+      if (message.equals("Could not initialize class Initialization$Broken")) {
+        checks |= 32;
+      }
+    }
+    new Counter();
+    new Counter();
+    checks |= Counter.count == 1 ? 64 : 0;
+    return checks;
+  }
+}
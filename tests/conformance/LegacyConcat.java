@@ -0,0 +1,34 @@
+public class LegacyConcat {
+  public static int main(String[] args) {
+    int checks = 0;
+    checks |= ("n=" + args.length).equals("n=0") ? 1 : 0;
+    long big = 1L << 40;
+    char c = 'x';
+    String mixed = c + "|" + big + "|" + 1.5f + "|" + 0.1 + "|" + true;
+    checks |= mixed.equals("x|1099511627776|1.5|0.1|true") ? 2 : 0;
+    Object none = null;
+    String nothing = null;
+    checks |= ("" + none + nothing).equals("nullnull") ? 4 : 0;
+    String grown = "";
+    for (int i = 0; i < 20; i++) {
+      grown += i % 10;
+    }
+    checks |= grown.equals("01234567890123456789") ? 8 : 0;
+    StringBuilder builder = new StringBuilder("ab");
+    builder.append(new char[] {'c', '\u00e9'}).append('\ud83d').append('\ude00');
+    checks |= builder.length() == 6 && builder.charAt(3) == '\u00e9' ? 16 : 0;
+    String reversed = builder.reverse().toString();
+    checks |= reversed.equals("\ud83d\ude00\u00e9cba") ? 32 : 0;
+    try {
+      builder.charAt(6);
+    } catch (StringIndexOutOfBoundsException e) {
+      checks |= e.getMessage().equals("index 6, length 6") ? 64 : 0;
+    }
+    checks |= new StringBuilder(0).append(-7).append(new Object() {
+      public String toString() {
+        return "!";
+      }
+    }).toString().equals("-7!") ? 128 : 0;
+    return checks;
+  }
+}
@@ -0,0 +1,40 @@
+/**
+ * {@code Loading$Missing.class} is deleted after compiling, and
+ * {@code Loading$CircleB.class} is replaced by one compiled from a copy of
+ * this file where {@code CircleB extends CircleA}, so that the two classes
+ * are each other's superclass.
+ */
+public class Loading {
+  static class Missing {
+    static int value() {
+      return 1;
+    }
+  }
+
+  static class CircleA extends CircleB {}
+
+  static class CircleB {}
+
+  static int missing(boolean call) {
+    if (call) {
+      return Missing.value();
+    }
+    return 2;
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+    checks |= missing(false) == 2 ? 1 : 0;
+    try {
+      missing(true);
+    } catch (NoClassDefFoundError e) {
+      checks |= e.getMessage().equals("Loading$Missing") ? 2 : 0;
+    }
+    try {
+      new CircleA();
+    } catch (ClassCircularityError e) {
+      checks |= 4;
+    }
+    return checks;
+  }
+}
@@ -0,0 +1,90 @@
+public class Loops {
+  static int sum(int n) {
+    int total = 0;
+    for (int i = 1; i <= n; i++) {
+      total += i;
+    }
+    return total;
+  }
+
+  static int countdown(int n) {
+    int steps = 0;
+    while (n > 0) {
+      n = n - 3;
+      steps++;
+    }
+    return steps * 100 + n;
+  }
+
+  static int digits(int n) {
+    int count = 0;
+    do {
+      n /= 10;
+      count++;
+    } while (n != 0);
+    return count;
+  }
+
+  static int nested(int n) {
+    int pairs = 0;
+    for (int i = 0; i < n; i++) {
+      for (int j = i; j < n; j++) {
+        if ((i + j) % 2 == 0) {
+          pairs++;
+        }
+      }
+    }
+    return pairs;
+  }
+
+  static int breakContinue(int[] values, int length) {
+    int total = 0;
+    for (int i = 0; i < length; i++) {
+      if (values[i] < 0) {
+        continue;
+      }
+      if (values[i] == 99) {
+        break;
+      }
+      total += values[i];
+    }
+    return total;
+  }
+
+  static int labeled(int n) {
+    int found = 0;
+    outer:
+    for (int i = 2; i < n; i++) {
+      for (int j = 2; j * j <= i; j++) {
+        if (i % j == 0) {
+          continue outer;
+        }
+      }
+      found++;
+    }
+    return found;
+  }
+
+  static int collatz(int n) {
+    int steps = 0;
+    while (n != 1) {
+      n = n % 2 == 0 ? n / 2 : 3 * n + 1;
+      steps++;
+    }
+    return steps;
+  }
+
+  public static int main(String[] args) {
+    int[] values = {4, -1, 7, -20, 9, 99, 1000};
+    int checks = 0;
+    checks += sum(1000) == 500500 ? 1 : 0;
+    checks += countdown(10) == 398 ? 2 : 0;
+    checks += digits(1234567) == 7 ? 4 : 0;
+    checks += digits(0) == 1 ? 8 : 0;
+    checks += nested(10) == 30 ? 16 : 0;
+    checks += breakContinue(values, 7) == 20 ? 32 : 0;
+    checks += labeled(200) == 46 ? 64 : 0;
+    checks += collatz(27) == 111 ? 128 : 0;
+    return checks;
+  }
+}
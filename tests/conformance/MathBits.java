@@ -0,0 +1,58 @@
+/**
+ * Prints the exact results of {@link Math} where NaN, signed zeros and
+ * rounding matter; the shortest decimal form of a double names its bits.
+ */
+public class MathBits {
+  static void print(double value) {
+    System.out.println(value);
+  }
+
+  static void print(float value) {
+    System.out.println(value);
+  }
+
+  static void print(long value) {
+    System.out.println(value);
+  }
+
+  public static int main(String[] args) {
+    print(Math.sqrt(-0.0));
+    print(Math.sqrt(-1));
+    print(Math.sqrt(2));
+    print(Math.cbrt(-27));
+    print(Math.floor(-0.5));
+    print(Math.ceil(-0.5));
+    print(Math.rint(-0.5));
+    print(Math.rint(2.5));
+    print(Math.rint(-3.5));
+    print(Math.abs(-0.0));
+    print(Math.abs(-0.0f));
+    print(Math.min(0.0, -0.0));
+    print(Math.max(-0.0, 0.0));
+    print(Math.min(-0.0f, 0.0f));
+    print(Math.max(Float.NaN, 1f));
+    print(Math.min(1.0, Double.NaN));
+    print(Math.signum(-0.0));
+    print(Math.signum(-2.5f));
+    print(Math.signum(Double.NaN));
+    print(Math.pow(-0.0, 3));
+    print(Math.pow(-0.0, -3));
+    print(Math.pow(1, Double.NaN));
+    print(Math.pow(-1, Double.POSITIVE_INFINITY));
+    print(Math.pow(Double.NaN, 0));
+    print(Math.pow(2, 0.5));
+    print(Math.hypot(3, 4));
+    print(Math.hypot(Double.NaN, Double.NEGATIVE_INFINITY));
+    print(Math.exp(Double.NEGATIVE_INFINITY));
+    print(Math.log(-0.0));
+    print(Math.log10(1000));
+    print(Math.sin(-0.0));
+    print(Math.atan2(-0.0, -0.0));
+    print(Math.atan2(-0.0, 0.0));
+    print(Math.round(-2.5));
+    print(Math.round(0.49999999999999994));
+    print(Math.round(Float.NaN));
+    print(Math.round(-0x1p63 - 1e5));
+    return 0;
+  }
+}
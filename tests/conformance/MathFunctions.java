@@ -0,0 +1,154 @@
+public class MathFunctions {
+  static boolean isNegativeZero(double x) {
+    return x == 0.0 && 1 / x < 0;
+  }
+
+  static boolean isPositiveZero(double x) {
+    return x == 0.0 && 1 / x > 0;
+  }
+
+  static boolean isNaN(double x) {
+    return x != x;
+  }
+
+  static boolean throwsArithmetic(int which) {
+    try {
+      switch (which) {
+        case 0: Math.addExact(Integer.MAX_VALUE, 1); break;
+        case 1: Math.multiplyExact(Long.MIN_VALUE, -1L); break;
+        case 2: Math.floorDiv(1, 0); break;
+        case 3: Math.toIntExact(1L << 31); break;
+        default: Math.negateExact(Integer.MIN_VALUE); break;
+      }
+    } catch (ArithmeticException e) {
+      return true;
+    }
+    return false;
+  }
+
+  static boolean absMinMax() {
+    return Math.abs(-5) == 5
+        && Math.abs(Integer.MIN_VALUE) == Integer.MIN_VALUE
+        && Math.abs(-5000000000L) == 5000000000L
+        && isPositiveZero(Math.abs(-0.0))
+        && Math.abs(-1.5f) == 1.5f
+        && Math.min(3, -4) == -4
+        && Math.max(3L, -4L) == 3L
+        && isNegativeZero(Math.min(0.0, -0.0))
+        && isPositiveZero(Math.max(-0.0, 0.0))
+        && isNegativeZero(Math.min(0.0f, -0.0f))
+        && isNaN(Math.min(Double.NaN, 1.0))
+        && isNaN(Math.max(1.0f, Float.NaN))
+        && Math.max(1.5, 2.5) == 2.5;
+  }
+
+  static boolean powers() {
+    return Math.pow(2, 10) == 1024.0
+        && Math.pow(Double.NaN, 0) == 1.0
+        && isNaN(Math.pow(1, Double.NaN))
+        && isNaN(Math.pow(-1, Double.POSITIVE_INFINITY))
+        && Math.pow(-8, 1.0 / 3) != Math.pow(-8, 1.0 / 3)
+        && Math.sqrt(2.25) == 1.5
+        && isNaN(Math.sqrt(-1))
+        && Math.cbrt(-27) == -3.0
+        && Math.exp(0) == 1.0
+        && Math.log(Math.E) == 1.0
+        && Math.log10(1000) == 3.0
+        && Math.log(0) == Double.NEGATIVE_INFINITY
+        && Math.hypot(3, 4) == 5.0
+        && Math.hypot(Double.NaN, Double.POSITIVE_INFINITY)
+            == Double.POSITIVE_INFINITY;
+  }
+
+  static boolean trigonometry() {
+    return Math.sin(0) == 0.0
+        && isNegativeZero(Math.sin(-0.0))
+        && Math.cos(0) == 1.0
+        && Math.abs(Math.tan(Math.PI / 4) - 1) < 1e-15
+        && Math.asin(1) == Math.PI / 2
+        && Math.acos(1) == 0.0
+        && Math.atan(1) == Math.PI / 4
+        && Math.atan2(1, 1) == Math.PI / 4
+        && Math.atan2(0.0, -1) == Math.PI
+        && Math.atan2(-0.0, -1) == -Math.PI
+        && isNaN(Math.asin(2))
+        && Math.toDegrees(Math.PI) == 180.0;
+  }
+
+  static boolean rounding() {
+    return Math.floor(-1.5) == -2.0
+        && isNegativeZero(Math.ceil(-0.5))
+        && Math.ceil(1.2) == 2.0
+        && Math.rint(2.5) == 2.0
+        && Math.rint(3.5) == 4.0
+        && isNegativeZero(Math.rint(-0.5))
+        && Math.round(2.5) == 3L
+        && Math.round(-2.5) == -2L
+        && Math.round(0.49999999999999994) == 0L
+        && Math.round(-0.5f) == 0
+        && Math.round(Double.NaN) == 0L
+        && Math.round(1e20) == Long.MAX_VALUE
+        && Math.round(-1e20f) == Integer.MIN_VALUE
+        && Math.round(8388609.0f) == 8388609;
+  }
+
+  static boolean signs() {
+    return Math.signum(-3.5) == -1.0
+        && Math.signum(2.0f) == 1.0f
+        && isNegativeZero(Math.signum(-0.0))
+        && isPositiveZero(Math.signum(0.0f))
+        && isNaN(Math.signum(Double.NaN))
+        && Math.signum(Double.NEGATIVE_INFINITY) == -1.0;
+  }
+
+  static boolean floorDivMod() {
+    return Math.floorDiv(7, 2) == 3
+        && Math.floorDiv(-7, 2) == -4
+        && Math.floorDiv(7, -2) == -4
+        && Math.floorDiv(-8, 2) == -4
+        && Math.floorDiv(Integer.MIN_VALUE, -1) == Integer.MIN_VALUE
+        && Math.floorDiv(-7000000000L, 2000000000L) == -4L
+        && Math.floorMod(-7, 2) == 1
+        && Math.floorMod(7, -2) == -1
+        && Math.floorMod(-8, 2) == 0
+        && Math.floorMod(-7L, 3L) == 2L
+        && Math.floorMod(Long.MIN_VALUE, -1L) == 0L;
+  }
+
+  static boolean exact() {
+    return Math.addExact(1, 2) == 3
+        && Math.addExact(Long.MAX_VALUE - 1, 1L) == Long.MAX_VALUE
+        && Math.subtractExact(-5, 5) == -10
+        && Math.multiplyExact(-46341, 46340) == -2147441940
+        && Math.multiplyExact(3000000000L, 3L) == 9000000000L
+        && Math.toIntExact(-7L) == -7
+        && throwsArithmetic(0)
+        && throwsArithmetic(1)
+        && throwsArithmetic(2)
+        && throwsArithmetic(3)
+        && throwsArithmetic(4);
+  }
+
+  static boolean random() {
+    for (int i = 0; i < 100; i++) {
+      double x = Math.random();
+      if (!(x >= 0.0 && x < 1.0)) {
+        return false;
+      }
+    }
+    return Math.random() != Math.random();
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+    checks += absMinMax() ? 1 : 0;
+    checks += powers() ? 2 : 0;
+    checks += trigonometry() ? 4 : 0;
+    checks += rounding() ? 8 : 0;
+    checks += signs() ? 16 : 0;
+    checks += floorDivMod() ? 32 : 0;
+    checks += exact() ? 64 : 0;
+    checks += random() ? 128 : 0;
+    return checks;
+  }
+}
@@ -0,0 +1,116 @@
+public class NullPointers {
+  interface Shape {
+    int area();
+  }
+
+  int value;
+  NullPointers next;
+  static NullPointers shared;
+  static NullPointers[] many = new NullPointers[2];
+
+  NullPointers next() {
+    return next;
+  }
+
+  static NullPointers none() {
+    return null;
+  }
+
+  static int use(int which, NullPointers holder) {
+    switch (which) {
+      case 0:
+        return holder.next.value;
+      case 1:
+        holder.next.value = 1;
+        return 0;
+      case 2: {
+        NullPointers nothing = null;
+        return nothing.next().value;
+      }
+      case 3:
+        return shared.next().value;
+      case 4:
+        return many[1].next().value;
+      case 5:
+        return none().next().value;
+      case 6: {
+        int[] ints = null;
+        return ints.length;
+      }
+      case 7: {
+        RuntimeException exception = null;
+        throw exception;
+      }
+      case 8: {
+        Object lock = null;
+        synchronized (lock) {
+          return 1;
+        }
+      }
+      case 9: {
+        Shape shape = null;
+        return shape.area();
+      }
+      case 10: {
+        long[] longs = null;
+        return (int) longs[0];
+      }
+      case 11: {
+        Object[] objects = null;
+        objects[0] = "x";
+        return 0;
+      }
+      default:
+        return holder.next().next().value;
+    }
+  }
+
+  static int arithmetic(int which) {
+    try {
+      switch (which) {
+        case 0: {
+          int zero = 0;
+          return 1 / zero;
+        }
+        case 1: {
+          long zero = 0;
+          return (int) (1L % zero);
+        }
+        case 2: {
+          int[] two = new int[2];
+          return two[which + 3];
+        }
+        case 3:
+          return new Object[-1].length;
+        default:
+          return new int[3][-2].length;
+      }
+    } catch (ArithmeticException e) {
+      return e.getMessage().equals("/ by zero") ? 1 : 0;
+    } catch (ArrayIndexOutOfBoundsException e) {
+      return e.getMessage().equals("Index 5 out of bounds for length 2") ? 1 : 0;
+    } catch (NegativeArraySizeException e) {
+      return e.getMessage().equals(which == 3 ? "-1" : "-2") ? 1 : 0;
+    }
+  }
+
+  public static int main(String[] args) {
+    for (int which = 0; which <= 12; which++) {
+      try {
+        use(which, new NullPointers());
+      } catch (NullPointerException e) {
+        System.out.println(e.getMessage());
+      }
+    }
+    int checks = 0;
+    for (int which = 0; which < 5; which++) {
+      checks |= arithmetic(which) << which;
+    }
+    int[] counted = new int[1];
+    synchronized (counted) {
+      counted[0]++;
+    }
+    checks |= counted[0] << 5;
+    return checks;
+  }
+}
@@ -0,0 +1,81 @@
+public class PrimitiveArrays {
+  static int sum(byte[] array) {
+    int sum = 0;
+    for (int i = 0; i < array.length; i++) {
+      sum += array[i];
+    }
+    return sum;
+  }
+
+  static boolean outOfBounds(long[] array, int index) {
+    try {
+      array[index] = 1;
+      return false;
+    } catch (ArrayIndexOutOfBoundsException e) {
+      return true;
+    }
+  }
+
+  static boolean negativeSize(int size) {
+    try {
+      return new char[size] == null;
+    } catch (NegativeArraySizeException e) {
+      return true;
+    }
+  }
+
+  static boolean nullLength(short[] array) {
+    try {
+      return array.length == 0;
+    } catch (NullPointerException e) {
+      return true;
+    }
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+
+    byte[] bytes = new byte[4];
+    bytes[0] = (byte) 200;
+    bytes[1] = 127;
+    bytes[2] = (byte) (bytes[1] + 1);
+    bytes[3] = 1;
+    checks += bytes[0] == -56 && bytes[2] == -128 && sum(bytes) == -56 ? 1 : 0;
+
+    char[] chars = new char[3];
+    chars[0] = 'A';
+    chars[1] = (char) -1;
+    chars[2] = (char) (chars[0] + 2);
+    checks += chars[1] == 65535 && chars[2] == 'C' ? 2 : 0;
+
+    short[] shorts = new short[2];
+    shorts[0] = (short) 40000;
+    shorts[1] = -2;
+    checks += shorts[0] == -25536 && shorts[0] * shorts[1] == 51072 ? 4 : 0;
+
+    boolean[] flags = new boolean[3];
+    flags[1] = true;
+    checks += !flags[0] && flags[1] && !flags[2] && flags.length == 3 ? 8 : 0;
+
+    long[] longs = new long[3];
+    longs[0] = 1L << 40;
+    longs[2] = longs[0] + longs[1] - 1;
+    float[] floats = new float[2];
+    floats[1] = 1.5f;
+    floats[0] = floats[1] * 2;
+    double[] doubles = new double[2];
+    doubles[1] = 0.1;
+    doubles[0] = doubles[1] + 0.2;
+    checks += longs[2] == 1099511627775L && floats[0] == 3.0f
+        && doubles[0] == 0.1 + 0.2 ? 16 : 0;
+
+    int[] ints = new int[0];
+    checks += ints.length == 0 && new double[7].length == 7 ? 32 : 0;
+
+    checks += outOfBounds(longs, 3) && outOfBounds(longs, -1)
+        && !outOfBounds(longs, 2) ? 64 : 0;
+    checks += negativeSize(-1) && !negativeSize(0) && nullLength(null)
+        ? 128 : 0;
+    return checks;
+  }
+}
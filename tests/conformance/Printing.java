@@ -0,0 +1,80 @@
+public class Printing {
+  static class Point {
+    int x, y;
+    Point(int x, int y) { this.x = x; this.y = y; }
+    public String toString() { return String.format("(%d, %d)", x, y); }
+  }
+
+  static class Nameless {
+    public int hashCode() { return 0xcafe; }
+  }
+
+  public static int main(String[] args) {
+    System.out.println(true);
+    System.out.println('\u00e9');
+    System.out.println(-42);
+    System.out.println(Long.MIN_VALUE);
+    System.out.println(1.5f);
+    System.out.println(0.1 + 0.2);
+    System.out.println(1e7);
+    System.out.println(1.0e-4);
+    System.out.println(100.0);
+    System.out.println(Double.NaN);
+    System.out.println(-1.0f / 0);
+    System.out.println(-0.0);
+    System.out.println(3.4028235e38f);
+    System.out.println(new char[] {'h', 'i'});
+    System.out.println("caf\u00e9 \u2603");
+    System.out.println((String) null);
+    System.out.println(new Point(3, -4));
+    System.out.println((Object) null);
+    System.out.println(new Nameless());
+    System.out.print(1);
+    System.out.print('+');
+    System.out.print(2L);
+    System.out.println();
+    System.out.println(Integer.valueOf(7));
+    System.out.println(Boolean.valueOf(false));
+    System.out.println(Character.valueOf('x'));
+    System.out.println(Double.valueOf(2.5));
+
+    System.out.printf("%d|%5d|%-5d|%05d|%,d|%+d|%(d%n", 1, 2, 3, -4, 1234567, 5, -6);
+    System.out.printf("%x|%X|%o|%#x|%x|%x%n", 255, 255, 8, 255, -1, (byte) -1);
+    System.out.printf("%s|%10s|%-10s|%.3s|%S%n", "a", "right", "left", "truncate", "up");
+    System.out.printf("%f|%.2f|%.0f|%10.3f|%,.2f|%.1f%n", 3.14159, 2.675, 2.5, -1.5, 1234567.891, 0.25);
+    System.out.printf("%e|%.2e|%E|%e%n", 12345.678, 0.000123, 1e100, 0.0);
+    System.out.printf("%c|%c|%b|%b|%b|%%|%s%n", 'q', 65, true, null, "x", new Point(1, 2));
+    System.out.printf("%2$s %1$s%n", "world", "hello");
+    System.out.printf("%s %s%n", 1.0f, 10L);
+    System.out.printf("%.1f|%.3f|%f%n", 1.5f, 0.1f, 1e20);
+    System.out.println(String.format("[%8.2f]", Math.sqrt(2)));
+    for (String format : new String[] {"%0$s", "%99999999999$s", "%q", "%5!", "%"}) {
+      try {
+        String.format(format, "x");
+      } catch (IllegalArgumentException e) {
+        System.out.println(e);
+      }
+    }
+    System.err.println("to stderr");
+
+    int result = 0;
+    if (String.format("%d", 42).equals("42")) result |= 1;
+    if (new Point(1, 1).toString().equals("(1, 1)")) result |= 2;
+    Object o = new Object();
+    if (o.equals(o) && !o.equals(new Object())) result |= 4;
+    if (Integer.valueOf(5).equals(5) && !Integer.valueOf(5).equals(5L)) result |= 8;
+    if (Double.valueOf(Double.NaN).equals(Double.NaN)) result |= 16;
+    if (Integer.valueOf(-7).hashCode() == -7 && Boolean.valueOf(true).hashCode() == 1231) result |= 32;
+    try {
+      String.format("%d", "no");
+    } catch (IllegalArgumentException e) {
+      if (e.getMessage().equals("d != java.lang.String")) result |= 64;
+    }
+    try {
+      String.format("%s %s", "one");
+    } catch (IllegalArgumentException e) {
+      if (e.getMessage().equals("Format specifier '%s'")) result |= 128;
+    }
+    return result;
+  }
+}
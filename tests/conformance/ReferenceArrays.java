@@ -0,0 +1,114 @@
+public class ReferenceArrays {
+  interface Shape {
+    int area();
+  }
+
+  static class Square implements Shape {
+    int side;
+
+    Square(int side) {
+      this.side = side;
+    }
+
+    public int area() {
+      return side * side;
+    }
+  }
+
+  static class Cube extends Square {
+    Cube(int side) {
+      super(side);
+    }
+
+    public int area() {
+      return 6 * super.area();
+    }
+  }
+
+  static int total(Shape[] shapes) {
+    int total = 0;
+    for (int i = 0; i < shapes.length; i++) {
+      if (shapes[i] != null) {
+        total += shapes[i].area();
+      }
+    }
+    return total;
+  }
+
+  static boolean storeFails(Object[] array, Object value) {
+    try {
+      array[0] = value;
+      return false;
+    } catch (ArrayStoreException e) {
+      return true;
+    }
+  }
+
+  static boolean castFails(Object value) {
+    try {
+      return ((Square) value).side < 0;
+    } catch (ClassCastException e) {
+      return true;
+    }
+  }
+
+  static boolean negativeDimension(int inner) {
+    try {
+      return new long[2][inner] == null;
+    } catch (NegativeArraySizeException e) {
+      return true;
+    }
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+
+    Shape[] shapes = new Shape[3];
+    shapes[0] = new Square(3);
+    shapes[2] = new Cube(2);
+    checks += total(shapes) == 33 && shapes[1] == null ? 1 : 0;
+
+    String[] strings = new String[2];
+    Object[] objects = strings;
+    checks += storeFails(objects, new Square(1)) && !storeFails(objects, null)
+        && objects.length == 2 ? 2 : 0;
+
+    Square[] squares = new Cube[1];
+    checks += storeFails(squares, new Square(1))
+        && !storeFails(squares, new Cube(1)) ? 4 : 0;
+
+    int[][] grid = new int[3][4];
+    for (int i = 0; i < grid.length; i++) {
+      for (int j = 0; j < grid[i].length; j++) {
+        grid[i][j] = i * 10 + j;
+      }
+    }
+    checks += grid[2][3] == 23 && grid[1].length == 4 ? 8 : 0;
+
+    long[][][] cube = new long[2][3][];
+    int[][] jagged = new int[2][];
+    jagged[0] = new int[1];
+    jagged[1] = grid[2];
+    checks += cube[1].length == 3 && cube[1][2] == null
+        && jagged[1][3] == 23 ? 16 : 0;
+
+    Object[] mixed = new Object[3];
+    mixed[0] = grid;
+    mixed[1] = new Cube(1);
+    mixed[2] = shapes;
+    checks += mixed[0] instanceof int[][] && mixed[0] instanceof Object[]
+        && !(mixed[0] instanceof long[][])
+        && mixed[2] instanceof Shape[] && !(mixed[2] instanceof Square[])
+        && mixed[1] instanceof Shape && !(null instanceof Object) ? 32 : 0;
+
+    checks += castFails(shapes) && castFails(grid)
+        && !castFails(mixed[1]) ? 64 : 0;
+
+    Object[][] rows = new Object[2][2];
+    rows[0][1] = rows;
+    checks += negativeDimension(-1) && !negativeDimension(0)
+        && rows[0][1] == rows && storeFails(rows, new int[1])
+        && !storeFails(rows, strings) ? 128 : 0;
+    return checks;
+  }
+}
@@ -0,0 +1,32 @@
+public class StackTraces {
+  static void fail(int n) {
+    if (n == 0) {
+      throw new IllegalStateException("bottom");
+    }
+    fail(n - 1);
+  }
+
+  static void wrap() {
+    try {
+      fail(2);
+    } catch (IllegalStateException e) {
+      throw new RuntimeException("wrapped", e);
+    }
+  }
+
+  public static int main(String[] args) {
+    try {
+      fail(1);
+    } catch (IllegalStateException e) {
+      for (StackTraceElement element : e.getStackTrace()) {
+        System.out.println(element.getClassName()
+            .concat(".")
+            .concat(element.getMethodName())
+            .concat(":")
+            .concat(String.valueOf(element.getLineNumber())));
+      }
+    }
+    wrap();
+    return 0;
+  }
+}
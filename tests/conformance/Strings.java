@@ -0,0 +1,59 @@
+public class Strings {
+  static class Other {
+    static String greeting() {
+      return "hello";
+    }
+  }
+
+  static boolean outOfRange(String string, int begin, int end) {
+    try {
+      return string.substring(begin, end) == null;
+    } catch (StringIndexOutOfBoundsException e) {
+      return true;
+    }
+  }
+
+  static int compare(Comparable<String> comparable, String other) {
+    return comparable.compareTo(other);
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+
+    checks += args.length == 2 && args[0].equals("first")
+        && args[1].equals("z\u00fcrich \u2603") ? 1 : 0;
+
+    String hello = "hello";
+    checks += hello == Other.greeting() && hello.length() == 5
+        && hello.charAt(1) == 'e' && !hello.isEmpty() && "".isEmpty() ? 2 : 0;
+
+    String snowman = args[1];
+    checks += snowman.length() == 8 && snowman.charAt(1) == '\u00fc'
+        && snowman.charAt(7) == '\u2603' && snowman.indexOf('\u2603') == 7
+        && snowman.indexOf("rich") == 2 ? 4 : 0;
+
+    checks += hello.hashCode() == 99162322 && "".hashCode() == 0
+        && snowman.hashCode() == "z\u00fcrich \u2603".hashCode()
+        && hello.equals(Other.greeting()) && !hello.equals(null)
+        && !hello.equals(args) ? 8 : 0;
+
+    String world = "hello world".substring(6);
+    checks += world.equals("world") && hello.substring(1, 3).equals("el")
+        && hello.substring(0, 5) == hello && outOfRange(hello, 2, 1)
+        && outOfRange(hello, -1, 2) && outOfRange(hello, 0, 6) ? 16 : 0;
+
+    checks += hello.indexOf('l') == 2 && hello.indexOf('l', 3) == 3
+        && hello.indexOf('z') == -1 && hello.indexOf("lo", -4) == 3
+        && hello.indexOf("", 9) == 5 && hello.indexOf(0x1F600) == -1 ? 32 : 0;
+
+    String joined = hello.concat(" ").concat(world);
+    checks += joined.equals("hello world") && joined != "hello world"
+        && joined.intern() == "hello world" && hello.concat("") == hello
+        && hello.concat(snowman).length() == 13 ? 64 : 0;
+
+    checks += "apple".compareTo("banana") == -1 && "b".compareTo("a") == 1
+        && "abc".compareTo("ab") == 1 && hello.compareTo("hello") == 0
+        && compare("ab", "abcd") == -2 ? 128 : 0;
+    return checks;
+  }
+}
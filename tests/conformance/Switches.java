@@ -0,0 +1,101 @@
+public class Switches {
+  static int dense(int x) {
+    switch (x) {
+      case 0: return 10;
+      case 1: return 11;
+      case 2: return 12;
+      case 3: return 13;
+      case 4: return 14;
+      default: return -1;
+    }
+  }
+
+  static int negative(int x) {
+    switch (x) {
+      case -3: return 1;
+      case -2: return 2;
+      case -1: return 3;
+      case 0: return 4;
+      default: return 0;
+    }
+  }
+
+  static int sparse(int x) {
+    switch (x) {
+      case -1000000: return 1;
+      case -7: return 2;
+      case 10: return 3;
+      case 1000: return 4;
+      case 65536: return 5;
+      case 2147483647: return 6;
+      default: return 0;
+    }
+  }
+
+  static int fallthrough(int x) {
+    int total = 0;
+    switch (x) {
+      case 1:
+        total += 1;
+      case 2:
+        total += 10;
+        break;
+      case 3:
+        total += 100;
+      default:
+        total += 50;
+    }
+    return total;
+  }
+
+  static int vowels(int[] text, int length) {
+    int count = 0;
+    for (int i = 0; i < length; i++) {
+      switch (text[i]) {
+        case 'a':
+        case 'e':
+        case 'i':
+        case 'o':
+        case 'u':
+          count++;
+          break;
+        default:
+          break;
+      }
+    }
+    return count;
+  }
+
+  static int nested(int x, int y) {
+    switch (x) {
+      case 1:
+        switch (y) {
+          case 1: return 11;
+          case 2: return 12;
+          default: return 10;
+        }
+      case 2:
+        return 20;
+      default:
+        return 0;
+    }
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+    checks += dense(0) + dense(2) + dense(4) == 36 && dense(5) == -1
+        && dense(-1) == -1 ? 1 : 0;
+    checks += negative(-3) * 1000 + negative(-2) * 100 + negative(-1) * 10
+        + negative(0) == 1234 && negative(-4) == 0 && negative(1) == 0 ? 2 : 0;
+    checks += sparse(-1000000) + sparse(-7) + sparse(10) + sparse(1000)
+        + sparse(65536) + sparse(2147483647) == 21 && sparse(11) == 0
+        && sparse(-2147483648) == 0 ? 4 : 0;
+    checks += fallthrough(1) == 11 && fallthrough(2) == 10
+        && fallthrough(3) == 150 && fallthrough(4) == 50 ? 8 : 0;
+    checks += vowels(new int[] {'s', 'w', 'i', 't', 'c', 'h', 'e', 's'}, 8)
+        == 2 ? 16 : 0;
+    checks += nested(1, 1) + nested(1, 2) + nested(1, 3) + nested(2, 1)
+        + nested(3, 1) == 53 ? 32 : 0;
+    return checks;
+  }
+}
@@ -0,0 +1,341 @@
+// Generated with a script: the locals are numerous enough that javac has to
+// address most of them with wide loads, stores and iincs.
+public class WideLocals {
+  static int manyLocals(int seed) {
+    int v0 = seed + 0;
+    int v1 = seed + 1;
+    int v2 = seed + 2;
+    int v3 = seed + 3;
+    int v4 = seed + 4;
+    int v5 = seed + 5;
+    int v6 = seed + 6;
+    int v7 = seed + 7;
+    int v8 = seed + 8;
+    int v9 = seed + 9;
+    int v10 = seed + 10;
+    int v11 = seed + 11;
+    int v12 = seed + 12;
+    int v13 = seed + 13;
+    int v14 = seed + 14;
+    int v15 = seed + 15;
+    int v16 = seed + 16;
+    int v17 = seed + 17;
+    int v18 = seed + 18;
+    int v19 = seed + 19;
+    int v20 = seed + 20;
+    int v21 = seed + 21;
+    int v22 = seed + 22;
+    int v23 = seed + 23;
+    int v24 = seed + 24;
+    int v25 = seed + 25;
+    int v26 = seed + 26;
+    int v27 = seed + 27;
+    int v28 = seed + 28;
+    int v29 = seed + 29;
+    int v30 = seed + 30;
+    int v31 = seed + 31;
+    int v32 = seed + 32;
+    int v33 = seed + 33;
+    int v34 = seed + 34;
+    int v35 = seed + 35;
+    int v36 = seed + 36;
+    int v37 = seed + 37;
+    int v38 = seed + 38;
+    int v39 = seed + 39;
+    int v40 = seed + 40;
+    int v41 = seed + 41;
+    int v42 = seed + 42;
+    int v43 = seed + 43;
+    int v44 = seed + 44;
+    int v45 = seed + 45;
+    int v46 = seed + 46;
+    int v47 = seed + 47;
+    int v48 = seed + 48;
+    int v49 = seed + 49;
+    int v50 = seed + 50;
+    int v51 = seed + 51;
+    int v52 = seed + 52;
+    int v53 = seed + 53;
+    int v54 = seed + 54;
+    int v55 = seed + 55;
+    int v56 = seed + 56;
+    int v57 = seed + 57;
+    int v58 = seed + 58;
+    int v59 = seed + 59;
+    int v60 = seed + 60;
+    int v61 = seed + 61;
+    int v62 = seed + 62;
+    int v63 = seed + 63;
+    int v64 = seed + 64;
+    int v65 = seed + 65;
+    int v66 = seed + 66;
+    int v67 = seed + 67;
+    int v68 = seed + 68;
+    int v69 = seed + 69;
+    int v70 = seed + 70;
+    int v71 = seed + 71;
+    int v72 = seed + 72;
+    int v73 = seed + 73;
+    int v74 = seed + 74;
+    int v75 = seed + 75;
+    int v76 = seed + 76;
+    int v77 = seed + 77;
+    int v78 = seed + 78;
+    int v79 = seed + 79;
+    int v80 = seed + 80;
+    int v81 = seed + 81;
+    int v82 = seed + 82;
+    int v83 = seed + 83;
+    int v84 = seed + 84;
+    int v85 = seed + 85;
+    int v86 = seed + 86;
+    int v87 = seed + 87;
+    int v88 = seed + 88;
+    int v89 = seed + 89;
+    int v90 = seed + 90;
+    int v91 = seed + 91;
+    int v92 = seed + 92;
+    int v93 = seed + 93;
+    int v94 = seed + 94;
+    int v95 = seed + 95;
+    int v96 = seed + 96;
+    int v97 = seed + 97;
+    int v98 = seed + 98;
+    int v99 = seed + 99;
+    int v100 = seed + 0;
+    int v101 = seed + 1;
+    int v102 = seed + 2;
+    int v103 = seed + 3;
+    int v104 = seed + 4;
+    int v105 = seed + 5;
+    int v106 = seed + 6;
+    int v107 = seed + 7;
+    int v108 = seed + 8;
+    int v109 = seed + 9;
+    int v110 = seed + 10;
+    int v111 = seed + 11;
+    int v112 = seed + 12;
+    int v113 = seed + 13;
+    int v114 = seed + 14;
+    int v115 = seed + 15;
+    int v116 = seed + 16;
+    int v117 = seed + 17;
+    int v118 = seed + 18;
+    int v119 = seed + 19;
+    int v120 = seed + 20;
+    int v121 = seed + 21;
+    int v122 = seed + 22;
+    int v123 = seed + 23;
+    int v124 = seed + 24;
+    int v125 = seed + 25;
+    int v126 = seed + 26;
+    int v127 = seed + 27;
+    int v128 = seed + 28;
+    int v129 = seed + 29;
+    int v130 = seed + 30;
+    int v131 = seed + 31;
+    int v132 = seed + 32;
+    int v133 = seed + 33;
+    int v134 = seed + 34;
+    int v135 = seed + 35;
+    int v136 = seed + 36;
+    int v137 = seed + 37;
+    int v138 = seed + 38;
+    int v139 = seed + 39;
+    int v140 = seed + 40;
+    int v141 = seed + 41;
+    int v142 = seed + 42;
+    int v143 = seed + 43;
+    int v144 = seed + 44;
+    int v145 = seed + 45;
+    int v146 = seed + 46;
+    int v147 = seed + 47;
+    int v148 = seed + 48;
+    int v149 = seed + 49;
+    int v150 = seed + 50;
+    int v151 = seed + 51;
+    int v152 = seed + 52;
+    int v153 = seed + 53;
+    int v154 = seed + 54;
+    int v155 = seed + 55;
+    int v156 = seed + 56;
+    int v157 = seed + 57;
+    int v158 = seed + 58;
+    int v159 = seed + 59;
+    int v160 = seed + 60;
+    int v161 = seed + 61;
+    int v162 = seed + 62;
+    int v163 = seed + 63;
+    int v164 = seed + 64;
+    int v165 = seed + 65;
+    int v166 = seed + 66;
+    int v167 = seed + 67;
+    int v168 = seed + 68;
+    int v169 = seed + 69;
+    int v170 = seed + 70;
+    int v171 = seed + 71;
+    int v172 = seed + 72;
+    int v173 = seed + 73;
+    int v174 = seed + 74;
+    int v175 = seed + 75;
+    int v176 = seed + 76;
+    int v177 = seed + 77;
+    int v178 = seed + 78;
+    int v179 = seed + 79;
+    int v180 = seed + 80;
+    int v181 = seed + 81;
+    int v182 = seed + 82;
+    int v183 = seed + 83;
+    int v184 = seed + 84;
+    int v185 = seed + 85;
+    int v186 = seed + 86;
+    int v187 = seed + 87;
+    int v188 = seed + 88;
+    int v189 = seed + 89;
+    int v190 = seed + 90;
+    int v191 = seed + 91;
+    int v192 = seed + 92;
+    int v193 = seed + 93;
+    int v194 = seed + 94;
+    int v195 = seed + 95;
+    int v196 = seed + 96;
+    int v197 = seed + 97;
+    int v198 = seed + 98;
+    int v199 = seed + 99;
+    int v200 = seed + 0;
+    int v201 = seed + 1;
+    int v202 = seed + 2;
+    int v203 = seed + 3;
+    int v204 = seed + 4;
+    int v205 = seed + 5;
+    int v206 = seed + 6;
+    int v207 = seed + 7;
+    int v208 = seed + 8;
+    int v209 = seed + 9;
+    int v210 = seed + 10;
+    int v211 = seed + 11;
+    int v212 = seed + 12;
+    int v213 = seed + 13;
+    int v214 = seed + 14;
+    int v215 = seed + 15;
+    int v216 = seed + 16;
+    int v217 = seed + 17;
+    int v218 = seed + 18;
+    int v219 = seed + 19;
+    int v220 = seed + 20;
+    int v221 = seed + 21;
+    int v222 = seed + 22;
+    int v223 = seed + 23;
+    int v224 = seed + 24;
+    int v225 = seed + 25;
+    int v226 = seed + 26;
+    int v227 = seed + 27;
+    int v228 = seed + 28;
+    int v229 = seed + 29;
+    int v230 = seed + 30;
+    int v231 = seed + 31;
+    int v232 = seed + 32;
+    int v233 = seed + 33;
+    int v234 = seed + 34;
+    int v235 = seed + 35;
+    int v236 = seed + 36;
+    int v237 = seed + 37;
+    int v238 = seed + 38;
+    int v239 = seed + 39;
+    int v240 = seed + 40;
+    int v241 = seed + 41;
+    int v242 = seed + 42;
+    int v243 = seed + 43;
+    int v244 = seed + 44;
+    int v245 = seed + 45;
+    int v246 = seed + 46;
+    int v247 = seed + 47;
+    int v248 = seed + 48;
+    int v249 = seed + 49;
+    int v250 = seed + 50;
+    int v251 = seed + 51;
+    int v252 = seed + 52;
+    int v253 = seed + 53;
+    int v254 = seed + 54;
+    int v255 = seed + 55;
+    int v256 = seed + 56;
+    int v257 = seed + 57;
+    int v258 = seed + 58;
+    int v259 = seed + 59;
+    int v260 = seed + 60;
+    int v261 = seed + 61;
+    int v262 = seed + 62;
+    int v263 = seed + 63;
+    int v264 = seed + 64;
+    int v265 = seed + 65;
+    int v266 = seed + 66;
+    int v267 = seed + 67;
+    int v268 = seed + 68;
+    int v269 = seed + 69;
+    int v270 = seed + 70;
+    int v271 = seed + 71;
+    int v272 = seed + 72;
+    int v273 = seed + 73;
+    int v274 = seed + 74;
+    int v275 = seed + 75;
+    int v276 = seed + 76;
+    int v277 = seed + 77;
+    int v278 = seed + 78;
+    int v279 = seed + 79;
+    int v280 = seed + 80;
+    int v281 = seed + 81;
+    int v282 = seed + 82;
+    int v283 = seed + 83;
+    int v284 = seed + 84;
+    int v285 = seed + 85;
+    int v286 = seed + 86;
+    int v287 = seed + 87;
+    int v288 = seed + 88;
+    int v289 = seed + 89;
+    int v290 = seed + 90;
+    int v291 = seed + 91;
+    int v292 = seed + 92;
+    int v293 = seed + 93;
+    int v294 = seed + 94;
+    int v295 = seed + 95;
+    int v296 = seed + 96;
+    int v297 = seed + 97;
+    int v298 = seed + 98;
+    int v299 = seed + 99;
+    int[] array = new int[1];
+    array[0] = v299;
+    int[] alias = array;
+    v299 += 1000;
+    v298 -= 2000;
+    v297 += 30000;
+    v296 -= 1;
+    v295++;
+    v1 += 500;
+    v2 -= 100;
+    return v0 + v1 + v2 + v3 + v4 + v5 + v6 + v7 + v8 + v9 + v10 + v11 + v12 + v13 + v14 + v15 + v16 + v17 + v18 + v19 + v20 + v21 + v22 + v23 + v24 + v25 + v26 + v27 + v28 + v29 + v30 + v31 + v32 + v33 + v34 + v35 + v36 + v37 + v38 + v39 + v40 + v41 + v42 + v43 + v44 + v45 + v46 + v47 + v48 + v49 + v50 + v51 + v52 + v53 + v54 + v55 + v56 + v57 + v58 + v59 + v60 + v61 + v62 + v63 + v64 + v65 + v66 + v67 + v68 + v69 + v70 + v71 + v72 + v73 + v74 + v75 + v76 + v77 + v78 + v79 + v80 + v81 + v82 + v83 + v84 + v85 + v86 + v87 + v88 + v89 + v90 + v91 + v92 + v93 + v94 + v95 + v96 + v97 + v98 + v99 + v100 + v101 + v102 + v103 + v104 + v105 + v106 + v107 + v108 + v109 + v110 + v111 + v112 + v113 + v114 + v115 + v116 + v117 + v118 + v119 + v120 + v121 + v122 + v123 + v124 + v125 + v126 + v127 + v128 + v129 + v130 + v131 + v132 + v133 + v134 + v135 + v136 + v137 + v138 + v139 + v140 + v141 + v142 + v143 + v144 + v145 + v146 + v147 + v148 + v149 + v150 + v151 + v152 + v153 + v154 + v155 + v156 + v157 + v158 + v159 + v160 + v161 + v162 + v163 + v164 + v165 + v166 + v167 + v168 + v169 + v170 + v171 + v172 + v173 + v174 + v175 + v176 + v177 + v178 + v179 + v180 + v181 + v182 + v183 + v184 + v185 + v186 + v187 + v188 + v189 + v190 + v191 + v192 + v193 + v194 + v195 + v196 + v197 + v198 + v199 + v200 + v201 + v202 + v203 + v204 + v205 + v206 + v207 + v208 + v209 + v210 + v211 + v212 + v213 + v214 + v215 + v216 + v217 + v218 + v219 + v220 + v221 + v222 + v223 + v224 + v225 + v226 + v227 + v228 + v229 + v230 + v231 + v232 + v233 + v234 + v235 + v236 + v237 + v238 + v239 + v240 + v241 + v242 + v243 + v244 + v245 + v246 + v247 + v248 + v249 + v250 + v251 + v252 + v253 + v254 + v255 + v256 + v257 + v258 + v259 + v260 + v261 + v262 + v263 + v264 + v265 + v266 + v267 + v268 + v269 + v270 + v271 + v272 + v273 + v274 + v275 + v276 + v277 + v278 + v279 + v280 + v281 + v282 + v283 + v284 + v285 + v286 + v287 + v288 + v289 + v290 + v291 + v292 + v293 + v294 + v295 + v296 + v297 + v298 + v299
+        + alias[0];
+  }
+
+  static int narrowIinc(int x) {
+    x -= 5;
+    x += 127;
+    x -= 128;
+    return x;
+  }
+
+  static int countdown(int n) {
+    int steps = 0;
+    for (int i = n; i > 0; i -= 7) {
+      steps++;
+    }
+    return steps;
+  }
+
+  public static int main(String[] args) {
+    int checks = 0;
+    checks += manyLocals(1) == 44650 ? 1 : 0;
+    checks += narrowIinc(10) == 4 ? 2 : 0;
+    checks += countdown(50) == 8 ? 4 : 0;
+    return checks;
+  }
+}
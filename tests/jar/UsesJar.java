@@ -0,0 +1,10 @@
+import org.example.launcher.application.entrypoint.mainclass.Helper;
+
+public class UsesJar {
+  public static int main(String[] args) {
+    int checks = 0;
+    checks += Helper.twice(-4) == -8 ? 1 : 0;
+    checks += Helper.name().length() == 6 ? 2 : 0;
+    return checks;
+  }
+}
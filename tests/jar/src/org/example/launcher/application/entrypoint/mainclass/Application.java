@@ -0,0 +1,11 @@
+package org.example.launcher.application.entrypoint.mainclass;
+
+public class Application {
+  public static int main(String[] args) {
+    int checks = 0;
+    checks += Helper.twice(21) == 42 ? 1 : 0;
+    checks += Helper.name().equals("helper") ? 2 : 0;
+    checks += args.length == 1 && args[0].equals("arg") ? 4 : 0;
+    return checks;
+  }
+}
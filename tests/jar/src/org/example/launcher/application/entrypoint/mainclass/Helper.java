@@ -0,0 +1,11 @@
+package org.example.launcher.application.entrypoint.mainclass;
+
+public class Helper {
+  public static int twice(int x) {
+    return 2 * x;
+  }
+
+  public static String name() {
+    return "helper";
+  }
+}
@@ -0,0 +1,19 @@
+public class Natives {
+  static native int twice(int x);
+
+  native String greet(String name);
+
+  static native void unbound();
+
+  public static int main(String[] args) {
+    int checks = 0;
+    checks += twice(21) == 42 ? 1 : 0;
+    checks += new Natives().greet("mist").equals("hello, mist") ? 2 : 0;
+    try {
+      unbound();
+    } catch (UnsatisfiedLinkError e) {
+      checks += e.getMessage().equals("Natives.unbound()V") ? 4 : 0;
+    }
+    return checks;
+  }
+}